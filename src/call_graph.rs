@@ -0,0 +1,209 @@
+//! Project-wide call graph construction
+
+use crate::{
+    extract::calls_in_range,
+    parser::{retained_source, syntax_tree_for},
+    search::search_functions,
+    CallGraph, CallGraphNode, CodeConstruct, ParsedProject,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Build a call graph over every function in a project
+/// 
+/// Each named function found by `search_functions` becomes a node, and the
+/// calls made in its body (see `extract_calls`) become edges. Calls are
+/// resolved by the final segment of the called name (`self.save` resolves to
+/// `save`), preferring a function in the caller's own file and otherwise
+/// linking every project function with that name. Calls that match no
+/// project function are linked to external nodes.
+/// 
+/// Files whose source is neither retained nor readable from disk contribute
+/// no nodes.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to analyze
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{build_call_graph, parse_directory, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     let graph = build_call_graph(&project);
+///     
+///     for caller in graph.callers_of("parse_source") {
+///         println!("called by {}", caller.qualified_name);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn build_call_graph(project: &ParsedProject) -> CallGraph {
+    let mut graph = CallGraph::default();
+    
+    // First pass: a node per named function, remembering its calls
+    let mut pending_calls: Vec<(usize, Vec<String>)> = Vec::new();
+    for file in &project.files {
+        let Ok(source) = retained_source(file) else {
+            continue;
+        };
+        let Some(tree) = syntax_tree_for(file, &source) else {
+            continue;
+        };
+        
+        for function in search_functions(file, None) {
+            let Some(name) = function.name.clone() else {
+                continue;
+            };
+            let index = graph.nodes.len();
+            graph.nodes.push(CallGraphNode {
+                qualified_name: qualified_name(&file.file_path, &function),
+                name,
+                file_path: Some(file.file_path.clone()),
+                external: false,
+            });
+            pending_calls.push((index, calls_in_range(&tree, &source, function.start_byte, function.end_byte)));
+        }
+    }
+    
+    let mut functions_by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, node) in graph.nodes.iter().enumerate() {
+        functions_by_name.entry(node.name.clone()).or_default().push(index);
+    }
+    
+    // Second pass: resolve calls by name, creating external nodes as needed
+    let mut external_nodes: HashMap<String, usize> = HashMap::new();
+    let mut seen_edges: HashSet<(usize, usize)> = HashSet::new();
+    for (caller, calls) in pending_calls {
+        for call in calls {
+            let targets = match functions_by_name.get(callee_base_name(&call)) {
+                Some(candidates) => {
+                    let caller_file = &graph.nodes[caller].file_path;
+                    let same_file: Vec<usize> = candidates
+                        .iter()
+                        .copied()
+                        .filter(|&candidate| &graph.nodes[candidate].file_path == caller_file)
+                        .collect();
+                    if same_file.is_empty() { candidates.clone() } else { same_file }
+                }
+                None => {
+                    let index = *external_nodes.entry(call.clone()).or_insert_with(|| {
+                        graph.nodes.push(CallGraphNode {
+                            qualified_name: call.clone(),
+                            name: call.clone(),
+                            file_path: None,
+                            external: true,
+                        });
+                        graph.nodes.len() - 1
+                    });
+                    vec![index]
+                }
+            };
+            
+            for callee in targets {
+                if seen_edges.insert((caller, callee)) {
+                    graph.edges.push((caller, callee));
+                }
+            }
+        }
+    }
+    
+    graph
+}
+
+impl CallGraph {
+    /// Get the functions that call the named function
+    /// 
+    /// `name` may be either a qualified name or a plain function name; a
+    /// plain name matches every function with that name.
+    pub fn callers_of(&self, name: &str) -> Vec<&CallGraphNode> {
+        self.connected(name, |&(caller, callee)| (callee, caller))
+    }
+    
+    /// Get the functions (including external ones) called by the named function
+    /// 
+    /// `name` may be either a qualified name or a plain function name; a
+    /// plain name matches every function with that name.
+    pub fn callees_of(&self, name: &str) -> Vec<&CallGraphNode> {
+        self.connected(name, |&(caller, callee)| (caller, callee))
+    }
+    
+    /// Follow edges from nodes matching `name`, with `orient` mapping an edge to (from, to)
+    fn connected<F>(&self, name: &str, orient: F) -> Vec<&CallGraphNode>
+    where
+        F: Fn(&(usize, usize)) -> (usize, usize),
+    {
+        let mut results: Vec<&CallGraphNode> = Vec::new();
+        let mut seen: HashSet<usize> = HashSet::new();
+        for edge in &self.edges {
+            let (from, to) = orient(edge);
+            let node = &self.nodes[from];
+            if (node.qualified_name == name || node.name == name)
+                && seen.insert(to) {
+                results.push(&self.nodes[to]);
+            }
+        }
+        results
+    }
+}
+
+/// Build `file_path::Outer.Inner.name` from a construct and its named ancestors
+fn qualified_name(file_path: &str, construct: &CodeConstruct) -> String {
+    let mut segments = Vec::new();
+    let mut current = Some(construct);
+    while let Some(node) = current {
+        if let Some(name) = &node.name {
+            segments.push(name.as_str());
+        }
+        current = node.parent.as_deref();
+    }
+    segments.reverse();
+    
+    format!("{}::{}", file_path, segments.join("."))
+}
+
+/// Get the final name segment of a call (`self.client.send` -> `send`, `Config::load` -> `load`)
+fn callee_base_name(call: &str) -> &str {
+    call.rsplit(['.', ':']).next().unwrap_or(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_string_sync, Language};
+
+    #[test]
+    fn test_build_call_graph_across_files() {
+        let mut app = parse_string_sync(
+            "from util import helper\n\ndef main():\n    helper()\n    print('done')\n",
+            Language::Python,
+        ).unwrap();
+        app.file_path = "app.py".to_string();
+        let mut util = parse_string_sync(
+            "class Store:\n    def save(self):\n        self.flush()\n\n    def flush(self):\n        pass\n\ndef helper():\n    Store().save()\n",
+            Language::Python,
+        ).unwrap();
+        util.file_path = "util.py".to_string();
+        
+        let project = ParsedProject::from_files(vec![app, util]);
+        let graph = build_call_graph(&project);
+        
+        let callers: Vec<_> = graph.callers_of("helper").iter().map(|n| n.qualified_name.as_str()).collect();
+        assert_eq!(callers, vec!["app.py::main"]);
+        
+        let callees = graph.callees_of("app.py::main");
+        assert_eq!(callees.len(), 2);
+        assert_eq!(callees[0].qualified_name, "util.py::helper");
+        assert!(!callees[0].external);
+        assert_eq!(callees[1].name, "print");
+        assert!(callees[1].external);
+        assert_eq!(callees[1].file_path, None);
+        
+        let save_callees: Vec<_> = graph.callees_of("util.py::Store.save").iter().map(|n| n.qualified_name.as_str()).collect();
+        assert_eq!(save_callees, vec!["util.py::Store.flush"]);
+        let helper_callees: Vec<_> = graph.callees_of("helper").iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(helper_callees, vec!["save", "Store"]);
+    }
+}
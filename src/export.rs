@@ -0,0 +1,310 @@
+//! Export of parsed results to line-oriented and editor-facing formats
+
+use crate::{
+    node_for, parser::retained_source, CodeConstruct, ConstructMetadata, DocumentPosition, DocumentRange, DocumentSymbol, Error,
+    ParsedFile, ParsedProject, SymbolKind,
+};
+use serde::Serialize;
+use std::io::Write;
+use tree_sitter::Tree;
+
+/// A single construct as written to JSON Lines, without `parent`/`children`
+#[derive(Serialize)]
+struct ConstructRecord<'a> {
+    file_path: &'a str,
+    node_type: &'a str,
+    name: Option<&'a str>,
+    start_line: usize,
+    end_line: usize,
+    start_column: usize,
+    end_column: usize,
+    start_byte: usize,
+    end_byte: usize,
+    source_code: &'a str,
+    metadata: &'a ConstructMetadata,
+}
+
+impl<'a> ConstructRecord<'a> {
+    fn new(file_path: &'a str, construct: &'a CodeConstruct) -> Self {
+        Self {
+            file_path,
+            node_type: &construct.node_type,
+            name: construct.name.as_deref(),
+            start_line: construct.start_line,
+            end_line: construct.end_line,
+            start_column: construct.start_column,
+            end_column: construct.end_column,
+            start_byte: construct.start_byte,
+            end_byte: construct.end_byte,
+            source_code: &construct.source_code,
+            metadata: &construct.metadata,
+        }
+    }
+}
+
+/// Write every construct in a project as JSON Lines
+/// 
+/// Each line is one JSON object holding the construct's file path, node
+/// type, name, position, source code and metadata. The recursive `parent`
+/// and `children` fields are left out; every nested construct gets its own
+/// line instead, so each construct appears exactly once.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to export
+/// * `writer` - Destination for the output, such as a file or stdout
+/// 
+/// # Errors
+/// 
+/// Returns `Error::Io` if writing fails.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_directory, write_project_jsonl, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     let file = std::fs::File::create("constructs.jsonl")?;
+///     write_project_jsonl(&project, std::io::BufWriter::new(file))?;
+///     Ok(())
+/// }
+/// ```
+pub fn write_project_jsonl<W: Write>(project: &ParsedProject, mut writer: W) -> Result<(), Error> {
+    for file in &project.files {
+        for construct in file.all_constructs() {
+            serde_json::to_writer(&mut writer, &ConstructRecord::new(&file.file_path, construct))
+                .map_err(|e| Error::Io(e.to_string()))?;
+            writer.write_all(b"\n").map_err(|e| Error::Io(e.to_string()))?;
+        }
+    }
+    
+    writer.flush().map_err(|e| Error::Io(e.to_string()))
+}
+
+/// Export every construct in a project as a JSON Lines string
+/// 
+/// See `write_project_jsonl` for the record format; prefer it for large
+/// projects to avoid building the whole output in memory.
+pub fn project_to_jsonl(project: &ParsedProject) -> String {
+    let mut output = Vec::new();
+    // Writing into memory cannot fail and the records only contain strings and numbers
+    write_project_jsonl(project, &mut output).expect("serializing constructs to memory failed");
+    String::from_utf8(output).expect("serde_json produced invalid UTF-8")
+}
+
+/// Build an LSP-style document symbol outline of a file
+/// 
+/// Symbols follow the file's construct hierarchy. Each construct's kind is
+/// looked up from its node type:
+/// 
+/// | Node types | Kind |
+/// |------------|------|
+/// | `function_definition`, `function_item`, `function_signature_item`, `function_declaration` | `Function`, or `Method` inside a class-like symbol |
+/// | `method_definition`, `method_declaration` | `Method` |
+/// | `constructor_declaration`, `init_declaration` | `Constructor` |
+/// | `class_definition`, `class_declaration`, `class_specifier` | `Class` |
+/// | `struct_item`, `struct_specifier` | `Struct` |
+/// | `enum_item`, `enum_declaration`, `enum_specifier`, `enum_definition` | `Enum` |
+/// | `trait_item`, `trait_definition`, `interface_declaration`, `protocol_declaration` | `Interface` |
+/// | `impl_item`, `object_definition` | `Object` |
+/// | `mod_item`, `module` | `Module` |
+/// | `namespace_definition` | `Namespace` |
+/// | `const_item` | `Constant` |
+/// | `static_item`, `variable_declaration`, `val_definition`, `var_definition`, `property_declaration` | `Variable` |
+/// | `field_declaration`, `public_field_definition` | `Field` |
+/// | `type_item`, `type_alias_declaration` | `TypeParameter` |
+/// 
+/// Constructs with another node type or without a name (such as Python
+/// decorator wrappers) are left out, and their children take their place.
+/// Selection ranges point at the name when the syntax tree is retained.
+/// Columns are converted to UTF-16 code units using the file's source; if
+/// the source can no longer be read they are left as byte offsets.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to outline
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{document_symbols, parse_string_sync, Language, SymbolKind};
+/// 
+/// let parsed = parse_string_sync("class Shape:\n    def area(self):\n        pass\n", Language::Python).unwrap();
+/// let symbols = document_symbols(&parsed);
+/// 
+/// assert_eq!(symbols[0].kind, SymbolKind::Class);
+/// assert_eq!(symbols[0].children[0].name, "area");
+/// assert_eq!(symbols[0].children[0].kind, SymbolKind::Method);
+/// ```
+pub fn document_symbols(parsed_file: &ParsedFile) -> Vec<DocumentSymbol> {
+    let roots = parsed_file.constructs.iter().filter(|construct| construct.depth == 0);
+    let source = retained_source(parsed_file).ok();
+    symbols_for(roots, parsed_file.syntax_tree.as_ref(), source.as_deref(), false)
+}
+
+/// Convert constructs into symbols, hoisting the children of unmapped constructs
+fn symbols_for<'a>(
+    constructs: impl IntoIterator<Item = &'a CodeConstruct>,
+    tree: Option<&Tree>,
+    source: Option<&str>,
+    in_type: bool,
+) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+    for construct in constructs {
+        match (symbol_kind(&construct.node_type, in_type), &construct.name) {
+            (Some(kind), Some(name)) => {
+                let is_type = matches!(
+                    kind,
+                    SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Interface | SymbolKind::Object
+                );
+                let range = construct_range(construct, source);
+                symbols.push(DocumentSymbol {
+                    name: name.clone(),
+                    kind,
+                    range,
+                    selection_range: name_range(construct, tree, source).unwrap_or(range),
+                    children: symbols_for(&construct.children, tree, source, is_type),
+                });
+            }
+            _ => symbols.extend(symbols_for(&construct.children, tree, source, in_type)),
+        }
+    }
+    symbols
+}
+
+/// Map a node type to its symbol kind, following the table on `document_symbols`
+fn symbol_kind(node_type: &str, in_type: bool) -> Option<SymbolKind> {
+    let kind = match node_type {
+        "function_definition" | "function_item" | "function_signature_item" | "function_declaration" if in_type => {
+            SymbolKind::Method
+        }
+        "function_definition" | "function_item" | "function_signature_item" | "function_declaration" => {
+            SymbolKind::Function
+        }
+        "method_definition" | "method_declaration" => SymbolKind::Method,
+        "constructor_declaration" | "init_declaration" => SymbolKind::Constructor,
+        "class_definition" | "class_declaration" | "class_specifier" => SymbolKind::Class,
+        "struct_item" | "struct_specifier" => SymbolKind::Struct,
+        "enum_item" | "enum_declaration" | "enum_specifier" | "enum_definition" => SymbolKind::Enum,
+        "trait_item" | "trait_definition" | "interface_declaration" | "protocol_declaration" => SymbolKind::Interface,
+        "impl_item" | "object_definition" => SymbolKind::Object,
+        "mod_item" | "module" => SymbolKind::Module,
+        "namespace_definition" => SymbolKind::Namespace,
+        "const_item" => SymbolKind::Constant,
+        "static_item" | "variable_declaration" | "val_definition" | "var_definition" | "property_declaration" => {
+            SymbolKind::Variable
+        }
+        "field_declaration" | "public_field_definition" => SymbolKind::Field,
+        "type_item" | "type_alias_declaration" => SymbolKind::TypeParameter,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// The full range of a construct as a `DocumentRange`
+fn construct_range(construct: &CodeConstruct, source: Option<&str>) -> DocumentRange {
+    DocumentRange {
+        start: position(source, construct.start_line - 1, construct.start_column, construct.start_byte),
+        end: position(source, construct.end_line - 1, construct.end_column, construct.end_byte),
+    }
+}
+
+/// The range of a construct's `name` field, if the syntax tree is available
+fn name_range(construct: &CodeConstruct, tree: Option<&Tree>, source: Option<&str>) -> Option<DocumentRange> {
+    let name = node_for(construct, tree?)?.child_by_field_name("name")?;
+    let (start, end) = (name.start_position(), name.end_position());
+    Some(DocumentRange {
+        start: position(source, start.row, start.column, name.start_byte()),
+        end: position(source, end.row, end.column, name.end_byte()),
+    })
+}
+
+/// Build a position from a byte column, counting the column in UTF-16 code units when the source is known
+fn position(source: Option<&str>, line: usize, column: usize, byte: usize) -> DocumentPosition {
+    let character = source
+        .and_then(|source| source.get(byte.saturating_sub(column)..byte))
+        .map_or(column, |prefix| prefix.encode_utf16().count());
+    DocumentPosition { line, character }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_string_sync, Language};
+
+    #[test]
+    fn test_project_to_jsonl() {
+        let mut python = parse_string_sync("class Greeter:\n    def greet(self):\n        pass\n", Language::Python).unwrap();
+        python.file_path = "greeter.py".to_string();
+        let mut rust = parse_string_sync("pub fn main() {}\n", Language::Rust).unwrap();
+        rust.file_path = "main.rs".to_string();
+        let total_constructs = python.constructs.len() + rust.constructs.len();
+        
+        let project = ParsedProject::from_files(vec![python, rust]);
+        
+        let jsonl = project_to_jsonl(&project);
+        let lines: Vec<_> = jsonl.lines().collect();
+        assert_eq!(lines.len(), total_constructs);
+        
+        let method: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(method["file_path"], "greeter.py");
+        assert_eq!(method["name"], "greet");
+        assert_eq!(method["start_line"], 2);
+        assert!(method.get("parent").is_none());
+        assert!(method.get("children").is_none());
+        
+        let main: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(main["metadata"]["visibility"], "pub");
+    }
+    
+    #[test]
+    fn test_document_symbols() {
+        let source = "class Shape:\n    @property\n    def area(self):\n        pass\n\n    def scale(self, factor):\n        pass\n\ndef helper():\n    pass\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        let symbols = document_symbols(&parsed);
+        
+        let outline: Vec<_> = symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(outline, vec![("Shape", SymbolKind::Class), ("helper", SymbolKind::Function)]);
+        
+        // The decorated method is hoisted out of its unnamed wrapper
+        let methods: Vec<_> = symbols[0].children.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(methods, vec![("area", SymbolKind::Method), ("scale", SymbolKind::Method)]);
+        assert!(symbols[0].children.iter().all(|s| s.children.is_empty()));
+        
+        let shape = &symbols[0];
+        assert_eq!(shape.range.start, DocumentPosition { line: 0, character: 0 });
+        assert_eq!(shape.range.end.line, 6);
+        assert_eq!(shape.selection_range, DocumentRange {
+            start: DocumentPosition { line: 0, character: 6 },
+            end: DocumentPosition { line: 0, character: 11 },
+        });
+        assert_eq!(shape.children[0].selection_range.start, DocumentPosition { line: 2, character: 8 });
+        
+        // Nested constructs stay nested after a JSON round-trip
+        let restored: ParsedFile = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+        let outline: Vec<_> = document_symbols(&restored).iter().map(|s| (s.name.clone(), s.children.len())).collect();
+        assert_eq!(outline, vec![("Shape".to_string(), 2), ("helper".to_string(), 0)]);
+    }
+    
+    #[test]
+    fn test_document_symbols_use_lsp_encoding() {
+        // `é` is two bytes but one UTF-16 unit, `😀` four bytes but two units
+        let source = "/* é😀 */ struct Shape {}\n";
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        let shape = &document_symbols(&parsed)[0];
+        assert_eq!(shape.range.start, DocumentPosition { line: 0, character: 10 });
+        assert_eq!(shape.selection_range, DocumentRange {
+            start: DocumentPosition { line: 0, character: 17 },
+            end: DocumentPosition { line: 0, character: 22 },
+        });
+        
+        let json = serde_json::to_value(shape).unwrap();
+        assert_eq!(json["kind"], 23);
+        let restored: DocumentSymbol = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.kind, SymbolKind::Struct);
+        assert!(serde_json::from_str::<SymbolKind>("0").is_err());
+    }
+}
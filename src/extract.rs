@@ -0,0 +1,1174 @@
+//! Extraction of auxiliary code elements (literals, etc.) from syntax trees
+
+use crate::{
+    languages::{detect_language_by_extension, get_function_node_types, get_tree_sitter_language},
+    parser::{retained_source, slice_source, strip_comment_markers, syntax_tree_for, walk_tree},
+    search::node_for,
+    utils::language_from_string,
+    CodeConstruct, Error, ImportInfo, ImportedSymbol, Language, ParsedFile, ParsedProject, Range, SlocStats,
+    StringLiteral, TodoItem,
+};
+use regex::Regex;
+use tree_sitter::{Node, Parser, Tree};
+
+/// Extract all string literals from a parsed file
+/// 
+/// This function walks the retained syntax tree and collects every string
+/// literal together with its location, which is useful for auditing
+/// user-facing text (e.g. for internationalization). Template strings such as
+/// JavaScript template literals and Python f-strings are flagged via
+/// `is_template`.
+/// 
+/// Besides the full `text`, each literal carries its `content` without
+/// delimiters, taken from the grammar's tokens rather than by trimming quotes,
+/// so Rust raw strings (`r#"..."#`), Python triple-quoted and prefixed
+/// strings, C++ raw strings (`R"delim(...)delim"`), Java text blocks, Go raw
+/// strings and Lua long strings (`[==[...]==]`) keep any quotes they contain.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to extract string literals from
+/// 
+/// # Returns
+/// 
+/// A `Result` containing the string literals in source order, or an `Error`
+/// if the syntax tree is unavailable or the source cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{extract_string_literals, parse_file, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("app.js", Language::JavaScript).await?;
+///     
+///     for literal in extract_string_literals(&parsed)? {
+///         println!("{}:{} {}", literal.start_line, literal.start_column, literal.text);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn extract_string_literals(parsed_file: &ParsedFile) -> Result<Vec<StringLiteral>, Error> {
+    let tree = parsed_file.syntax_tree.as_ref()
+        .ok_or(Error::SyntaxTreeUnavailable)?;
+    let source = retained_source(parsed_file)?;
+    
+    let mut literals = Vec::new();
+    collect_string_literals(tree.root_node(), &source, &parsed_file.language, &mut literals);
+    
+    Ok(literals)
+}
+
+/// Collect the string literal nodes below `node`
+fn collect_string_literals(
+    node: Node,
+    source: &str,
+    language: &Language,
+    literals: &mut Vec<StringLiteral>,
+) {
+    let node_types = string_literal_node_types(language);
+    walk_tree(node, |node| {
+        if !node_types.contains(&node.kind()) {
+            return true;
+        }
+        let start_point = node.start_position();
+        let end_point = node.end_position();
+        
+        literals.push(StringLiteral {
+            text: slice_source(source, node.start_byte(), node.end_byte()),
+            content: string_content(node, source),
+            start_line: start_point.row + 1, // Convert to 1-based
+            end_line: end_point.row + 1,
+            start_column: start_point.column,
+            end_column: end_point.column,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            is_template: is_template_string(node, source),
+        });
+        // Template substitutions can hold further literals
+        true
+    });
+}
+
+/// Get the node types that represent string literals for a language
+fn string_literal_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &["string"],
+        Language::JavaScript | Language::TypeScript => &["string", "template_string"],
+        Language::Rust => &["string_literal", "raw_string_literal"],
+        Language::Java => &["string_literal"],
+        Language::C | Language::Cpp => &["string_literal", "raw_string_literal"],
+        Language::Go => &["interpreted_string_literal", "raw_string_literal"],
+        Language::Lua => &["string"],
+        _ => &[],
+    }
+}
+
+/// Get the text of a string literal between its opening and closing delimiters
+/// 
+/// Delimiters are the anonymous tokens at either end of the literal (quotes,
+/// prefixes such as `b"` or `R"`, the parentheses of C++ raw strings) plus
+/// the named `string_start`/`string_end` and `raw_string_delimiter` tokens.
+/// Rust raw strings expose no delimiter tokens, only their content.
+fn string_content(node: Node, source: &str) -> String {
+    let is_delimiter = |child: &Node| {
+        !child.is_named() || matches!(child.kind(), "string_start" | "string_end" | "raw_string_delimiter")
+    };
+    
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    let (Some(first), Some(last)) = (
+        children.iter().position(|child| !is_delimiter(child)),
+        children.iter().rposition(|child| !is_delimiter(child)),
+    ) else {
+        // Only delimiters, so the literal is empty
+        return String::new();
+    };
+    
+    let start = match first {
+        0 => children[first].start_byte(),
+        _ => children[first - 1].end_byte(),
+    };
+    let end = children.get(last + 1).map_or(children[last].end_byte(), |closing| closing.start_byte());
+    slice_source(source, start, end)
+}
+
+/// Check whether a string node is a template/format string
+fn is_template_string(node: Node, source: &str) -> bool {
+    match node.kind() {
+        "template_string" => true,
+        // Python f-strings carry their prefix in the `string_start` token
+        "string" => node
+            .child(0)
+            .filter(|start| start.kind() == "string_start")
+            .map(|start| {
+                slice_source(source, start.start_byte(), start.end_byte())
+                    .chars()
+                    .any(|c| c == 'f' || c == 'F')
+            })
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Scan the comments of a project for `TODO`-style annotations
+/// 
+/// Recognizes `TODO`, `FIXME`, `HACK`, `XXX` and `NOTE` (uppercase only, so
+/// prose like "Note that" is skipped) at the start of a comment line,
+/// followed by an assignee in parentheses, a colon, or both:
+/// `TODO(alice): fix this`, `FIXME: leak`, `HACK(bob) work around #12`.
+/// Mentions elsewhere in the text ("see the TODO list") are skipped. Each
+/// line of a block comment is scanned on its own. Comments are found in the
+/// syntax tree, so files whose source is neither retained nor readable from
+/// disk are skipped.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to scan
+/// 
+/// # Returns
+/// 
+/// The annotations in file order, then line order.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_directory, scan_annotations, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     for item in scan_annotations(&project) {
+///         let owner = item.assignee.as_deref().unwrap_or("unassigned");
+///         println!("{}:{} {} ({}) {}", item.file_path, item.line, item.tag, owner, item.message);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn scan_annotations(project: &ParsedProject) -> Vec<TodoItem> {
+    let pattern = Regex::new(r"^\s*(TODO|FIXME|HACK|XXX|NOTE)(?:\(([^)]*)\):?|:)\s*(.*)")
+        .expect("valid annotation pattern");
+    
+    let mut items = Vec::new();
+    for file in &project.files {
+        let Ok(source) = retained_source(file) else {
+            continue;
+        };
+        let Some(tree) = syntax_tree_for(file, &source) else {
+            continue;
+        };
+        collect_annotations(tree.root_node(), &source, &file.file_path, &pattern, &mut items);
+    }
+    items
+}
+
+/// Check whether a node is a comment in any of the supported grammars
+fn is_comment_node(node: Node) -> bool {
+    matches!(node.kind(), "comment" | "line_comment" | "block_comment")
+}
+
+/// Collect annotations from the comment nodes below `node`
+fn collect_annotations(node: Node, source: &str, file_path: &str, pattern: &Regex, items: &mut Vec<TodoItem>) {
+    walk_tree(node, |node| {
+        if !is_comment_node(node) {
+            return true;
+        }
+        let text = slice_source(source, node.start_byte(), node.end_byte());
+        for (offset, line) in text.lines().enumerate() {
+            let line = strip_comment_markers(line);
+            let Some(captures) = pattern.captures(&line) else {
+                continue;
+            };
+            items.push(TodoItem {
+                tag: captures[1].to_string(),
+                assignee: captures.get(2).map(|assignee| assignee.as_str().trim().to_string()),
+                message: captures[3].trim().to_string(),
+                file_path: file_path.to_string(),
+                line: node.start_position().row + offset + 1,
+            });
+        }
+        false
+    });
+}
+
+/// Count the total, code, comment and blank lines of a file
+/// 
+/// Comments are found by walking the syntax tree for comment nodes, the same
+/// way `scan_annotations` does. A line holding both code and a trailing
+/// comment counts as code; lines inside a multi-line string count as code.
+/// Files whose source is neither retained nor readable from disk yield
+/// all-zero stats.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to measure
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string_sync, sloc, Language};
+/// 
+/// let source = "// Entry point\nfn main() {\n\n    run(); // go\n}\n";
+/// let parsed = parse_string_sync(source, Language::Rust).unwrap();
+/// 
+/// let stats = sloc(&parsed);
+/// assert_eq!((stats.total, stats.code, stats.comment, stats.blank), (5, 3, 1, 1));
+/// ```
+pub fn sloc(parsed_file: &ParsedFile) -> SlocStats {
+    let Ok(source) = retained_source(parsed_file) else {
+        return SlocStats::default();
+    };
+    let lines: Vec<&str> = source.lines().collect();
+    let mut has_code = vec![false; lines.len()];
+    let mut has_comment = vec![false; lines.len()];
+    if let Some(tree) = syntax_tree_for(parsed_file, &source) {
+        mark_lines(tree.root_node(), &mut has_code, &mut has_comment);
+    }
+    
+    let mut stats = SlocStats { total: lines.len(), ..SlocStats::default() };
+    for (row, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            stats.blank += 1;
+        } else if has_comment[row] && !has_code[row] {
+            stats.comment += 1;
+        } else {
+            stats.code += 1;
+        }
+    }
+    stats
+}
+
+/// Mark the rows covered by comments and by code tokens below `node`
+fn mark_lines(node: Node, has_code: &mut [bool], has_comment: &mut [bool]) {
+    walk_tree(node, |node| {
+        let is_comment = is_comment_node(node);
+        if !is_comment && node.child_count() > 0 {
+            return true;
+        }
+        if node.start_byte() == node.end_byte() {
+            return false; // Zero-width tokens such as MISSING nodes or Python dedents
+        }
+        let start = node.start_position().row;
+        let end = node.end_position();
+        // A token ending at the start of a line (e.g. a newline) doesn't cover that line
+        let last = if end.column == 0 && end.row > start { end.row - 1 } else { end.row };
+        let marks = if is_comment { &mut *has_comment } else { &mut *has_code };
+        for mark in marks.iter_mut().take(last + 1).skip(start) {
+            *mark = true;
+        }
+        false
+    });
+}
+
+/// Extract the names of functions called within a construct
+/// 
+/// This re-parses `full_source` (the source of the file the construct came
+/// from), locates the construct by its byte range and walks its subtree for
+/// call nodes (`call`, `call_expression`, `method_invocation`). Plain calls
+/// yield the callee name (`foo`); method and path calls keep a receiver that
+/// is a simple name or dotted path (`self.client.get`, `Config::load`), and
+/// fall back to the bare method name when the receiver is an arbitrary
+/// expression such as another call.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct (typically a function) to inspect
+/// * `language` - The programming language of the source
+/// * `full_source` - The complete source text the construct was parsed from
+/// 
+/// # Returns
+/// 
+/// The called names without duplicates, in the order the calls start in the
+/// source (so `a(b())` yields `a` before `b`). Calls in nested functions are
+/// included. Returns an empty vector if the language is not
+/// supported or the construct cannot be located.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{extract_calls, parse_string_sync, Language};
+/// 
+/// let source = "def main():\n    config = load()\n    config.validate()\n";
+/// let parsed = parse_string_sync(source, Language::Python).unwrap();
+/// 
+/// let calls = extract_calls(&parsed.constructs[0], &Language::Python, source);
+/// assert_eq!(calls, vec!["load", "config.validate"]);
+/// ```
+pub fn extract_calls(construct: &CodeConstruct, language: &Language, full_source: &str) -> Vec<String> {
+    let Ok(ts_language) = get_tree_sitter_language(language) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&ts_language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(full_source, None) else {
+        return Vec::new();
+    };
+    
+    calls_in_range(&tree, full_source, construct.start_byte, construct.end_byte)
+}
+
+/// Collect callee names from the node spanning a byte range of an already parsed tree
+pub(crate) fn calls_in_range(tree: &Tree, source: &str, start_byte: usize, end_byte: usize) -> Vec<String> {
+    let mut calls = Vec::new();
+    if let Some(node) = tree.root_node().descendant_for_byte_range(start_byte, end_byte) {
+        collect_calls(node, source, &mut calls);
+    }
+    calls
+}
+
+/// Collect callee names from the call nodes below `node`
+fn collect_calls(node: Node, source: &str, calls: &mut Vec<String>) {
+    walk_tree(node, |node| {
+        if let Some(callee) = callee_name(node, source)
+            && !calls.contains(&callee) {
+            calls.push(callee);
+        }
+        true
+    });
+}
+
+/// Get the callee of a call node, or None if the node is not a call
+fn callee_name(node: Node, source: &str) -> Option<String> {
+    match node.kind() {
+        // Python, JavaScript/TypeScript, Rust, C/C++ and Go
+        "call" | "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            let text = slice_source(source, function.start_byte(), function.end_byte());
+            if is_simple_path(&text) {
+                return Some(text);
+            }
+            
+            // Member access on a complex receiver: keep only the member name
+            ["attribute", "property", "field", "name"]
+                .iter()
+                .find_map(|field| function.child_by_field_name(field))
+                .map(|member| slice_source(source, member.start_byte(), member.end_byte()))
+        }
+        // Java keeps the receiver and method name as separate fields
+        "method_invocation" => {
+            let name = node.child_by_field_name("name")?;
+            let name = slice_source(source, name.start_byte(), name.end_byte());
+            
+            match node.child_by_field_name("object") {
+                Some(object) => {
+                    let receiver = slice_source(source, object.start_byte(), object.end_byte());
+                    if is_simple_path(&receiver) {
+                        Some(format!("{}.{}", receiver, name))
+                    } else {
+                        Some(name)
+                    }
+                }
+                None => Some(name),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Check whether text is a plain name or a dotted/scoped path of names
+fn is_simple_path(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.' || c == ':')
+}
+
+/// Find every `unsafe` block and item in a Rust file
+/// 
+/// Each use of the `unsafe` keyword is reported as the range of the code it
+/// applies to: `unsafe { ... }` blocks, `unsafe fn` items, `unsafe impl` and
+/// `unsafe trait` items, and `unsafe extern` blocks. Ranges are in source
+/// order; nested occurrences (an unsafe block inside an unsafe fn) are each
+/// reported. Other languages yield no ranges.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to inspect; its syntax tree must be retained
+/// 
+/// # Errors
+/// 
+/// Returns `Error::SyntaxTreeUnavailable` if the syntax tree was not retained.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{find_unsafe, parse_file, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/ffi.rs", Language::Rust).await?;
+///     
+///     for range in find_unsafe(&parsed)? {
+///         println!("unsafe at lines {}-{}", range.start_point.row + 1, range.end_point.row + 1);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn find_unsafe(parsed_file: &ParsedFile) -> Result<Vec<Range>, Error> {
+    let tree = parsed_file.syntax_tree.as_ref()
+        .ok_or(Error::SyntaxTreeUnavailable)?;
+    
+    let mut ranges = Vec::new();
+    if parsed_file.language == Language::Rust {
+        collect_unsafe(tree.root_node(), &mut ranges);
+    }
+    Ok(ranges)
+}
+
+/// Collect the ranges governed by the `unsafe` keywords below `node`
+fn collect_unsafe(node: Node, ranges: &mut Vec<Range>) {
+    walk_tree(node, |node| {
+        if node.kind() == "unsafe" && !node.is_named()
+            && let Some(mut owner) = node.parent() {
+            // `unsafe fn` nests the keyword in `function_modifiers`
+            if owner.kind() == "function_modifiers"
+                && let Some(item) = owner.parent() {
+                owner = item;
+            }
+            ranges.push(owner.range());
+        }
+        true
+    });
+}
+
+/// Reconstruct the one-line signature of a function, without its body
+/// 
+/// The function is located in the file's syntax tree (re-parsed if it was
+/// dropped) and the tokens before its `body` node are kept, with the
+/// whitespace between them (such as line breaks in long parameter lists)
+/// collapsed to single spaces. String literals are copied verbatim. Trailing
+/// syntax that only introduces the body (Python's `:`, an arrow's `=>`) is
+/// dropped, and decorators are left out. Functions without a body, such as
+/// TypeScript overload declarations, yield their whole text.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file the construct came from
+/// * `construct` - The function construct (see `get_function_node_types`), or a
+///   Python `decorated_definition` wrapping one
+/// 
+/// # Returns
+/// 
+/// The signature, or `None` if the construct is not a function or the
+/// file's source cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string_sync, signature, Language};
+/// 
+/// let source = "fn foo(a: i32, b: &str) -> bool {\n    a > 0 && !b.is_empty()\n}\n";
+/// let parsed = parse_string_sync(source, Language::Rust).unwrap();
+/// 
+/// let line = signature(&parsed, &parsed.constructs[0]);
+/// assert_eq!(line.as_deref(), Some("fn foo(a: i32, b: &str) -> bool"));
+/// ```
+pub fn signature(parsed_file: &ParsedFile, construct: &CodeConstruct) -> Option<String> {
+    let kind = construct.node_type.as_str();
+    if kind != "decorated_definition" && !get_function_node_types(&parsed_file.language).contains(&kind) {
+        return None;
+    }
+    
+    let source = retained_source(parsed_file).ok()?;
+    let tree = syntax_tree_for(parsed_file, &source)?;
+    let mut node = node_for(construct, &tree)?;
+    if let Some(definition) = node.child_by_field_name("definition") {
+        node = definition;
+    }
+    let end = node.child_by_field_name("body").map_or(node.end_byte(), |body| body.start_byte());
+    
+    let mut tokens = header_tokens(node, end);
+    while tokens.last().is_some_and(|token| matches!(token.kind(), ":" | ";" | "=>")) {
+        tokens.pop();
+    }
+    
+    let mut line = String::new();
+    let mut previous: Option<Node> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        // A trailing comma before the closing parenthesis goes with the line breaks
+        if token.kind() == "," && tokens.get(i + 1).is_some_and(|next| next.kind() == ")") {
+            continue;
+        }
+        if let Some(previous) = previous
+            && previous.kind() != "("
+            && token.kind() != ")"
+            && slice_source(&source, previous.end_byte(), token.start_byte()).chars().any(char::is_whitespace)
+        {
+            line.push(' ');
+        }
+        line.push_str(&slice_source(&source, token.start_byte(), token.end_byte()));
+        previous = Some(*token);
+    }
+    
+    Some(line)
+}
+
+/// Collect the tokens of a node that start before `end`, treating string literals as single tokens
+fn header_tokens(node: Node, end: usize) -> Vec<Node> {
+    let mut tokens = Vec::new();
+    let mut cursor = node.walk();
+    loop {
+        let current = cursor.node();
+        let atomic = current.child_count() == 0 || current.kind().contains("string") || current.kind() == "char_literal";
+        if current.start_byte() < end && atomic {
+            tokens.push(current);
+        }
+        if current.start_byte() < end && !atomic && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() || cursor.node() == node {
+                return tokens;
+            }
+        }
+    }
+}
+
+/// Extract the fenced code blocks of a Markdown file, tagged by their language hint
+/// 
+/// The hint is the first word of the info string after the opening fence
+/// (so ```` ```rust,no_run ```` is Rust). It is resolved with
+/// `language_from_string`, falling back to treating it as a file extension;
+/// blocks without a hint, or with one that names no known language, are
+/// tagged `None`. Each block's code can then be handed to `parse_string`.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed Markdown file
+/// 
+/// # Returns
+/// 
+/// The code blocks in document order, or an empty list if the file is not
+/// Markdown or its source cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{extract_code_blocks, parse_file, parse_string, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let readme = parse_file("README.md", Language::Markdown).await?;
+///     
+///     for (language, code) in extract_code_blocks(&readme) {
+///         if let Some(language) = language {
+///             let parsed = parse_string(&code, language).await?;
+///             println!("{} constructs in block", parsed.constructs.len());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn extract_code_blocks(parsed_file: &ParsedFile) -> Vec<(Option<Language>, String)> {
+    if parsed_file.language != Language::Markdown {
+        return Vec::new();
+    }
+    let Ok(source) = retained_source(parsed_file) else {
+        return Vec::new();
+    };
+    let Some(tree) = syntax_tree_for(parsed_file, &source) else {
+        return Vec::new();
+    };
+    
+    let mut blocks = Vec::new();
+    collect_code_blocks(tree.root_node(), &source, &mut blocks);
+    blocks
+}
+
+/// Collect the `fenced_code_block` nodes below `node` with their language hints
+fn collect_code_blocks(node: Node, source: &str, blocks: &mut Vec<(Option<Language>, String)>) {
+    walk_tree(node, |node| {
+        if node.kind() != "fenced_code_block" {
+            return true;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        
+        let language = children
+            .iter()
+            .find(|child| child.kind() == "info_string")
+            .and_then(|info| source.get(info.start_byte()..info.end_byte()))
+            .and_then(|info| info.split([' ', ',', '{']).next())
+            .filter(|hint| !hint.is_empty())
+            .and_then(|hint| {
+                language_from_string(hint).or_else(|| detect_language_by_extension(&format!("block.{}", hint)))
+            });
+        let code = children
+            .iter()
+            .find(|child| child.kind() == "code_fence_content")
+            .map(|content| slice_source(source, content.start_byte(), content.end_byte()).to_string())
+            .unwrap_or_default();
+        
+        blocks.push((language, code));
+        false
+    });
+}
+
+/// Parse the imports of a file into module paths and imported names
+/// 
+/// Walks the syntax tree for import statements and splits each into the
+/// module it imports from and the names it brings into scope, for dependency
+/// analysis without re-parsing statement text:
+/// 
+/// * Python: `import a.b as c` and `from ..pkg import x as y`, keeping the
+///   leading dots of relative imports in the module
+/// * Rust: `use a::b::{c, d as e}`, with one entry per module path in nested
+///   groups; a single-segment `use foo;` imports the module itself
+/// * JavaScript/TypeScript: `import d, { a as b } from 'm'`, with the default
+///   import named `default` and `* as ns` recorded as the module alias
+/// * Go: `import f "os/exec"`, with the package name as the alias
+/// * Java: `import java.util.List;` and `import java.io.*;`
+/// 
+/// Wildcard imports are reported as a symbol named `*`. Other languages, and
+/// files whose source is neither retained nor readable from disk, have no
+/// imports.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to read imports from
+/// 
+/// # Returns
+/// 
+/// The imports in source order.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{imported_modules, parse_string_sync, Language};
+/// 
+/// let parsed = parse_string_sync("from os.path import join as j, exists\n", Language::Python).unwrap();
+/// 
+/// let imports = imported_modules(&parsed);
+/// assert_eq!(imports[0].module, "os.path");
+/// assert_eq!(imports[0].symbols[0].alias.as_deref(), Some("j"));
+/// assert_eq!(imports[0].symbols[1].name, "exists");
+/// ```
+pub fn imported_modules(parsed_file: &ParsedFile) -> Vec<ImportInfo> {
+    let Ok(source) = retained_source(parsed_file) else {
+        return Vec::new();
+    };
+    let Some(tree) = syntax_tree_for(parsed_file, &source) else {
+        return Vec::new();
+    };
+    
+    let mut imports = Vec::new();
+    collect_imports(tree.root_node(), &source, &parsed_file.language, &mut imports);
+    imports
+}
+
+/// Collect the imports below `node`
+fn collect_imports(node: Node, source: &str, language: &Language, imports: &mut Vec<ImportInfo>) {
+    walk_tree(node, |node| {
+        let line = node.start_position().row + 1;
+        match (language, node.kind()) {
+            (Language::Python, "import_statement") => python_imports(node, source, line, imports),
+            (Language::Python, "import_from_statement") => imports.push(python_from_import(node, source, line)),
+            (Language::Rust, "use_declaration") => rust_imports(node, source, line, imports),
+            (Language::JavaScript | Language::TypeScript, "import_statement") => {
+                imports.extend(javascript_import(node, source, line));
+            }
+            (Language::Go, "import_spec") => imports.extend(go_import(node, source, line)),
+            (Language::Java, "import_declaration") => imports.extend(java_import(node, source, line)),
+            _ => return true,
+        }
+        false
+    });
+}
+
+/// Source text of a node
+fn node_text(node: Node, source: &str) -> String {
+    slice_source(source, node.start_byte(), node.end_byte())
+}
+
+/// A name with an optional alias, from a node with `name` and `alias` fields or a plain name node
+fn aliased_name(node: Node, source: &str) -> (String, Option<String>) {
+    match (node.child_by_field_name("name"), node.child_by_field_name("alias")) {
+        (Some(name), alias) => (node_text(name, source), alias.map(|alias| node_text(alias, source))),
+        (None, _) => (node_text(node, source), None),
+    }
+}
+
+/// Python `import a.b, c as d`, one import per module
+fn python_imports(node: Node, source: &str, line: usize, imports: &mut Vec<ImportInfo>) {
+    let mut cursor = node.walk();
+    for name in node.children_by_field_name("name", &mut cursor) {
+        let (module, alias) = aliased_name(name, source);
+        imports.push(ImportInfo { module, symbols: Vec::new(), alias, line });
+    }
+}
+
+/// Python `from module import a as b, c` or `from module import *`
+fn python_from_import(node: Node, source: &str, line: usize) -> ImportInfo {
+    let module = node
+        .child_by_field_name("module_name")
+        .map(|module| node_text(module, source))
+        .unwrap_or_default();
+    
+    let mut cursor = node.walk();
+    let mut symbols: Vec<ImportedSymbol> = node
+        .children_by_field_name("name", &mut cursor)
+        .map(|name| {
+            let (name, alias) = aliased_name(name, source);
+            ImportedSymbol { name, alias }
+        })
+        .collect();
+    let mut cursor = node.walk();
+    if node.children(&mut cursor).any(|child| child.kind() == "wildcard_import") {
+        symbols.push(ImportedSymbol { name: "*".to_string(), alias: None });
+    }
+    
+    ImportInfo { module, symbols, alias: None, line }
+}
+
+/// Rust `use` declarations, grouping the imported names of each module path
+fn rust_imports(node: Node, source: &str, line: usize, imports: &mut Vec<ImportInfo>) {
+    let Some(argument) = node.child_by_field_name("argument") else {
+        return;
+    };
+    let mut leaves = Vec::new();
+    rust_use_leaves(argument, source, "", &mut leaves);
+    
+    let first = imports.len();
+    for (path, alias) in leaves {
+        let Some((module, name)) = path.rsplit_once("::") else {
+            // `use foo;` imports a crate or module itself
+            imports.push(ImportInfo { module: path, symbols: Vec::new(), alias, line });
+            continue;
+        };
+        let symbol = ImportedSymbol { name: name.to_string(), alias };
+        match imports[first..].iter_mut().find(|import| import.module == module && !import.symbols.is_empty()) {
+            Some(import) => import.symbols.push(symbol),
+            None => imports.push(ImportInfo { module: module.to_string(), symbols: vec![symbol], alias: None, line }),
+        }
+    }
+}
+
+/// Flatten a `use` tree into full paths with their aliases
+fn rust_use_leaves(node: Node, source: &str, prefix: &str, leaves: &mut Vec<(String, Option<String>)>) {
+    // Paths may be split across lines, e.g. inside long `use` lists
+    let path_text = |node: Node| node_text(node, source).split_whitespace().collect::<String>();
+    let join = |path: String| if prefix.is_empty() { path } else { format!("{}::{}", prefix, path) };
+    
+    match node.kind() {
+        "scoped_use_list" => {
+            let prefix = match node.child_by_field_name("path") {
+                Some(path) => join(path_text(path)),
+                None => prefix.to_string(),
+            };
+            if let Some(list) = node.child_by_field_name("list") {
+                rust_use_leaves(list, source, &prefix, leaves);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor).filter(|child| !is_comment_node(*child)) {
+                rust_use_leaves(child, source, prefix, leaves);
+            }
+        }
+        "use_as_clause" => {
+            let path = node.child_by_field_name("path").map(path_text).unwrap_or_default();
+            let alias = node.child_by_field_name("alias").map(|alias| node_text(alias, source));
+            leaves.push((join(path), alias));
+        }
+        // Identifiers, scoped paths, `self` and `a::*` wildcards
+        _ => leaves.push((join(path_text(node)), None)),
+    }
+}
+
+/// JavaScript/TypeScript `import d, { a as b } from 'm'`, `import * as ns from 'm'` or `import 'm'`
+fn javascript_import(node: Node, source: &str, line: usize) -> Option<ImportInfo> {
+    // `import x = require('m')` in TypeScript has no source string
+    let module = string_content(node.child_by_field_name("source")?, source);
+    let mut import = ImportInfo { module, symbols: Vec::new(), alias: None, line };
+    
+    let mut cursor = node.walk();
+    let Some(clause) = node.children(&mut cursor).find(|child| child.kind() == "import_clause") else {
+        return Some(import);
+    };
+    let mut cursor = clause.walk();
+    for part in clause.named_children(&mut cursor) {
+        match part.kind() {
+            "identifier" => import.symbols.push(ImportedSymbol {
+                name: "default".to_string(),
+                alias: Some(node_text(part, source)),
+            }),
+            "namespace_import" => {
+                let mut cursor = part.walk();
+                import.alias = part
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() == "identifier")
+                    .map(|alias| node_text(alias, source));
+            }
+            "named_imports" => {
+                let mut cursor = part.walk();
+                for specifier in part.named_children(&mut cursor).filter(|child| child.kind() == "import_specifier") {
+                    let (name, alias) = aliased_name(specifier, source);
+                    import.symbols.push(ImportedSymbol { name, alias });
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(import)
+}
+
+/// Go `import f "os/exec"`, one spec of a possibly grouped import
+fn go_import(node: Node, source: &str, line: usize) -> Option<ImportInfo> {
+    Some(ImportInfo {
+        module: string_content(node.child_by_field_name("path")?, source),
+        symbols: Vec::new(),
+        alias: node.child_by_field_name("name").map(|name| node_text(name, source)),
+        line,
+    })
+}
+
+/// Java `import a.b.C;` or `import a.b.*;`, including static imports
+fn java_import(node: Node, source: &str, line: usize) -> Option<ImportInfo> {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    let path = node_text(
+        *children.iter().find(|child| matches!(child.kind(), "scoped_identifier" | "identifier"))?,
+        source,
+    );
+    
+    let (module, name) = if children.iter().any(|child| child.kind() == "asterisk") {
+        (path, "*".to_string())
+    } else {
+        let (module, name) = path.rsplit_once('.')?;
+        (module.to_string(), name.to_string())
+    };
+    Some(ImportInfo { module, symbols: vec![ImportedSymbol { name, alias: None }], alias: None, line })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_string_sync;
+
+    #[test]
+    fn test_extract_string_literals() {
+        let python = "greeting = f\"Hello {name}\"\nfarewell = 'Goodbye'\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        
+        let literals = extract_string_literals(&parsed).unwrap();
+        assert_eq!(literals.len(), 2);
+        assert_eq!(literals[0].text, "f\"Hello {name}\"");
+        assert!(literals[0].is_template);
+        assert_eq!((literals[0].start_line, literals[0].start_column), (1, 11));
+        assert_eq!(literals[1].text, "'Goodbye'");
+        assert!(!literals[1].is_template);
+        assert_eq!(literals[1].start_line, 2);
+        
+        let javascript = "const title = `Welcome ${user}`;\nalert(\"Saved\");\n";
+        let parsed = parse_string_sync(javascript, Language::JavaScript).unwrap();
+        
+        let literals = extract_string_literals(&parsed).unwrap();
+        assert_eq!(literals.len(), 2);
+        assert_eq!(literals[0].text, "`Welcome ${user}`");
+        assert!(literals[0].is_template);
+        assert_eq!(literals[1].text, "\"Saved\"");
+        assert!(!literals[1].is_template);
+        assert_eq!((literals[1].start_line, literals[1].start_column), (2, 6));
+    }
+
+    #[test]
+    fn test_string_literal_content() {
+        let rust = "let a = r#\"say \"hi\"\"#;\nlet b = \"x\\\"y\";\nlet c = r\"\";\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        
+        let literals = extract_string_literals(&parsed).unwrap();
+        assert_eq!(literals[0].text, "r#\"say \"hi\"\"#");
+        assert_eq!(literals[0].content, "say \"hi\"");
+        assert_eq!(literals[1].content, "x\\\"y");
+        assert_eq!(literals[2].content, "");
+        
+        let python = "doc = \"\"\"say \"hi\" \"\"\"\nraw = rb'x'\nempty = ''\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        
+        let contents: Vec<_> = extract_string_literals(&parsed).unwrap().into_iter().map(|l| l.content).collect();
+        assert_eq!(contents, vec!["say \"hi\" ", "x", ""]);
+    }
+
+    #[test]
+    #[cfg(feature = "cpp")]
+    fn test_cpp_raw_string_content() {
+        let cpp = "auto query = R\"sql(SELECT \"name\" FROM t)sql\";\n";
+        let parsed = parse_string_sync(cpp, Language::Cpp).unwrap();
+        
+        let literals = extract_string_literals(&parsed).unwrap();
+        assert_eq!(literals[0].content, "SELECT \"name\" FROM t");
+    }
+
+    #[test]
+    fn test_imported_modules_python_and_rust() {
+        let symbol = |name: &str, alias: Option<&str>| ImportedSymbol { name: name.to_string(), alias: alias.map(str::to_string) };
+        
+        let python = "import os.path, numpy as np\nfrom ..pkg.models import User as U, Group\nfrom typing import *\n";
+        let imports = imported_modules(&parse_string_sync(python, Language::Python).unwrap());
+        let modules: Vec<_> = imports.iter().map(|i| (i.module.as_str(), i.alias.as_deref(), i.line)).collect();
+        assert_eq!(modules, vec![("os.path", None, 1), ("numpy", Some("np"), 1), ("..pkg.models", None, 2), ("typing", None, 3)]);
+        assert_eq!(imports[2].symbols, vec![symbol("User", Some("U")), symbol("Group", None)]);
+        assert_eq!(imports[3].symbols, vec![symbol("*", None)]);
+        
+        let rust = "use std::collections::{HashMap, BTreeSet as Set, hash_map::Entry};\nuse std::io::{self, Read};\nuse serde;\n";
+        let imports = imported_modules(&parse_string_sync(rust, Language::Rust).unwrap());
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["std::collections", "std::collections::hash_map", "std::io", "serde"]);
+        assert_eq!(imports[0].symbols, vec![symbol("HashMap", None), symbol("BTreeSet", Some("Set"))]);
+        assert_eq!(imports[1].symbols, vec![symbol("Entry", None)]);
+        assert_eq!(imports[2].symbols, vec![symbol("self", None), symbol("Read", None)]);
+        assert!(imports[3].symbols.is_empty());
+    }
+
+    #[test]
+    fn test_imported_modules_javascript() {
+        let javascript = "import React, { useState as useLocal } from 'react';\nimport * as fs from \"fs\";\nimport './styles.css';\n";
+        let imports = imported_modules(&parse_string_sync(javascript, Language::JavaScript).unwrap());
+        
+        let modules: Vec<_> = imports.iter().map(|i| (i.module.as_str(), i.alias.as_deref())).collect();
+        assert_eq!(modules, vec![("react", None), ("fs", Some("fs")), ("./styles.css", None)]);
+        let symbols: Vec<_> = imports[0].symbols.iter().map(|s| (s.name.as_str(), s.alias.as_deref())).collect();
+        assert_eq!(symbols, vec![("default", Some("React")), ("useState", Some("useLocal"))]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "go", feature = "java"))]
+    fn test_imported_modules_go_and_java() {
+        let go = "package main\nimport (\n\t\"fmt\"\n\tx \"os/exec\"\n)\n";
+        let imports = imported_modules(&parse_string_sync(go, Language::Go).unwrap());
+        let modules: Vec<_> = imports.iter().map(|i| (i.module.as_str(), i.alias.as_deref(), i.line)).collect();
+        assert_eq!(modules, vec![("fmt", None, 3), ("os/exec", Some("x"), 4)]);
+        
+        let java = "import java.util.List;\nimport java.io.*;\nclass A {}\n";
+        let imports = imported_modules(&parse_string_sync(java, Language::Java).unwrap());
+        let modules: Vec<_> = imports.iter().map(|i| (i.module.as_str(), i.symbols[0].name.as_str())).collect();
+        assert_eq!(modules, vec![("java.util", "List"), ("java.io", "*")]);
+    }
+
+    #[test]
+    fn test_extract_calls_python() {
+        let source = "class Service:\n    def run(self, items):\n        data = fetch(items)\n        self.client.send(data)\n        get_parser().parse(data)\n        print(len(data))\n        print('done')\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        let run = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("run")).unwrap();
+        
+        let calls = extract_calls(run, &Language::Python, source);
+        assert_eq!(calls, vec!["fetch", "self.client.send", "parse", "get_parser", "print", "len"]);
+    }
+
+    #[test]
+    fn test_extract_calls_javascript() {
+        let source = "function render(user) {\n  const view = buildView(user);\n  document.body.appendChild(view);\n  fetch(url).then(handle);\n  return format(user.name);\n}\n";
+        let parsed = parse_string_sync(source, Language::JavaScript).unwrap();
+        
+        let calls = extract_calls(&parsed.constructs[0], &Language::JavaScript, source);
+        assert_eq!(calls, vec!["buildView", "document.body.appendChild", "then", "fetch", "format"]);
+    }
+
+    #[test]
+    fn test_find_unsafe() {
+        let source = "unsafe fn raw_read(ptr: *const u8) -> u8 {\n    *ptr\n}\n\nunsafe impl Send for Handle {}\n\nfn safe_wrapper(ptr: *const u8) -> u8 {\n    let value = unsafe {\n        raw_read(ptr)\n    };\n    value\n}\n";
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        
+        let lines: Vec<_> = find_unsafe(&parsed).unwrap()
+            .iter()
+            .map(|range| (range.start_point.row + 1, range.end_point.row + 1))
+            .collect();
+        assert_eq!(lines, vec![(1, 3), (5, 5), (8, 10)]);
+        
+        let python = parse_string_sync("unsafe = True\n", Language::Python).unwrap();
+        assert!(find_unsafe(&python).unwrap().is_empty());
+    }
+    
+    #[test]
+    #[cfg(feature = "markdown")]
+    fn test_extract_code_blocks() {
+        let markdown = "# Usage\n\n```rust,no_run\nfn main() {}\n```\n\nSome text.\n\n```py\ndef helper():\n    pass\n```\n\n```\nplain\n```\n";
+        let parsed = parse_string_sync(markdown, Language::Markdown).unwrap();
+        
+        let blocks = extract_code_blocks(&parsed);
+        assert_eq!(blocks, vec![
+            (Some(Language::Rust), "fn main() {}\n".to_string()),
+            (Some(Language::Python), "def helper():\n    pass\n".to_string()),
+            (None, "plain\n".to_string()),
+        ]);
+        
+        let rust = parse_string_sync(&blocks[0].1, Language::Rust).unwrap();
+        assert_eq!(rust.constructs[0].name.as_deref(), Some("main"));
+        assert!(extract_code_blocks(&rust).is_empty());
+    }
+    
+    #[test]
+    fn test_signature() {
+        let rust = "pub fn parse<T: FromStr>(\n    input: &str,\n    strict: bool,\n) -> Result<T, Error> where T::Err: Debug {\n    todo!()\n}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        assert_eq!(
+            signature(&parsed, &parsed.constructs[0]).as_deref(),
+            Some("pub fn parse<T: FromStr>(input: &str, strict: bool) -> Result<T, Error> where T::Err: Debug"),
+        );
+        
+        let python = "class Greeter:\n    @staticmethod\n    def greet(name: str, punctuation: str = \"!\") -> str:\n        return name + punctuation\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let constructs = parsed.all_constructs();
+        let decorated = constructs.iter().find(|c| c.node_type == "decorated_definition").unwrap();
+        let method = constructs.iter().find(|c| c.node_type == "function_definition").unwrap();
+        let expected = "def greet(name: str, punctuation: str = \"!\") -> str";
+        assert_eq!(signature(&parsed, decorated).as_deref(), Some(expected));
+        assert_eq!(signature(&parsed, method).as_deref(), Some(expected));
+        assert_eq!(signature(&parsed, constructs[0]), None);
+        
+        // Whitespace inside string literals is left alone
+        let python = "def pad(\n    text,\n    fill=\"a  ( b\",\n):\n    pass\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        assert_eq!(signature(&parsed, &parsed.constructs[0]).as_deref(), Some("def pad(text, fill=\"a  ( b\")"));
+        
+        let typescript = "function add(a: number, b: number): number { return a + b; }\nclass Calc {\n  async total(values: number[]): Promise<number> {\n    return 0;\n  }\n}\nconst twice = (x: number) => x * 2;\n";
+        let parsed = parse_string_sync(typescript, Language::TypeScript).unwrap();
+        let signatures: Vec<_> = crate::search_functions(&parsed, None)
+            .iter()
+            .filter_map(|f| signature(&parsed, f))
+            .collect();
+        assert_eq!(signatures, vec![
+            "function add(a: number, b: number): number",
+            "async total(values: number[]): Promise<number>",
+            "(x: number)",
+        ]);
+    }
+    
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_signature_of_constructor() {
+        let java = "class A {\n    A() {}\n    public A(int x, String label) { this.x = x; }\n}\n";
+        let parsed = parse_string_sync(java, Language::Java).unwrap();
+        let signatures: Vec<_> = crate::search_functions(&parsed, None)
+            .iter()
+            .filter_map(|f| signature(&parsed, f))
+            .collect();
+        assert_eq!(signatures, vec!["A()", "public A(int x, String label)"]);
+    }
+    
+    #[test]
+    fn test_scan_annotations() {
+        let source = r#"
+// TODO(alice): fix this
+fn main() {
+    let x = 1; // FIXME: overflow on large inputs
+    /* HACK(bob) works around upstream bug
+     * XXX: remove after 2.0 */
+}
+// Note that this is not an annotation
+// Neither is the TODO: list in the README, or a TODO without a colon
+// NOTE: keep in sync with the parser
+"#;
+        let mut file = parse_string_sync(source, Language::Rust).unwrap();
+        file.file_path = "main.rs".to_string();
+        let project = ParsedProject::from_files(vec![file]);
+        
+        let items: Vec<_> = scan_annotations(&project)
+            .into_iter()
+            .map(|item| (item.tag, item.assignee, item.message, item.line))
+            .collect();
+        let owned = |name: &str| Some(name.to_string());
+        assert_eq!(items, vec![
+            ("TODO".to_string(), owned("alice"), "fix this".to_string(), 2),
+            ("FIXME".to_string(), None, "overflow on large inputs".to_string(), 4),
+            ("HACK".to_string(), owned("bob"), "works around upstream bug".to_string(), 5),
+            ("XXX".to_string(), None, "remove after 2.0".to_string(), 6),
+            ("NOTE".to_string(), None, "keep in sync with the parser".to_string(), 10),
+        ]);
+    }
+    
+    #[test]
+    fn test_sloc() {
+        let source = "# Module header\n# spanning two lines\n\nimport os  # trailing comment\n\n\ndef main():\n    text = \"\"\"\n    still code\n    \"\"\"\n    # inside the body\n    return text\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        
+        let stats = sloc(&parsed);
+        assert_eq!(stats, SlocStats { total: 12, code: 6, comment: 3, blank: 3 });
+        
+        assert_eq!(sloc(&parse_string_sync("", Language::Python).unwrap()), SlocStats::default());
+    }
+    
+    #[test]
+    fn test_tree_walks_handle_deep_nesting() {
+        // Deep enough to overflow a test thread's stack with one frame per level
+        let depth = 50_000;
+        let source = format!("value = {}{}\nimport os  # trailing\n", "[".repeat(depth), "]".repeat(depth));
+        let mut parser = Parser::new();
+        parser.set_language(&get_tree_sitter_language(&Language::Python).unwrap()).unwrap();
+        let tree = parser.parse(&source, None).unwrap();
+        
+        let mut imports = Vec::new();
+        collect_imports(tree.root_node(), &source, &Language::Python, &mut imports);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].module, "os");
+        
+        let mut has_code = vec![false; 2];
+        let mut has_comment = vec![false; 2];
+        mark_lines(tree.root_node(), &mut has_code, &mut has_comment);
+        assert_eq!((has_code, has_comment), (vec![true, true], vec![false, true]));
+        
+        let source = format!("value = {}run(\"x\"){}\n", "[".repeat(depth), "]".repeat(depth));
+        let tree = parser.parse(&source, None).unwrap();
+        
+        let mut literals = Vec::new();
+        collect_string_literals(tree.root_node(), &source, &Language::Python, &mut literals);
+        assert_eq!(literals.iter().map(|literal| literal.content.as_str()).collect::<Vec<_>>(), ["x"]);
+        
+        let mut calls = Vec::new();
+        collect_calls(tree.root_node(), &source, &mut calls);
+        assert_eq!(calls, ["run"]);
+        
+        let source = format!("fn main() {{\n    let v = {}unsafe {{ 1 }}{};\n}}\n", "(".repeat(depth), ")".repeat(depth));
+        parser.set_language(&get_tree_sitter_language(&Language::Rust).unwrap()).unwrap();
+        let tree = parser.parse(&source, None).unwrap();
+        
+        let mut ranges = Vec::new();
+        collect_unsafe(tree.root_node(), &mut ranges);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].start_point.row, 1);
+    }
+}
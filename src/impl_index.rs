@@ -0,0 +1,257 @@
+//! Indexing of Rust `impl` blocks by trait and type
+
+use crate::{
+    parser::{retained_source, slice_source, syntax_tree_for},
+    search::search_by_node_type,
+    CodeConstruct, ImplEntry, ImplIndex, Language, ParsedFile, ParsedProject,
+};
+use std::collections::HashMap;
+
+/// Build an index of every `impl` block in the Rust files of a project
+/// 
+/// The trait and target type are read from the `trait` and `type` fields of
+/// each `impl_item` in the syntax tree, so generic parameters, paths and
+/// references in the header are handled correctly. Files whose source is
+/// neither retained nor readable from disk are skipped.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to index
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{build_impl_index, parse_directory, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     let index = build_impl_index(&project);
+/// 
+///     for entry in index.impls_of_trait("Display") {
+///         println!("{} implements Display ({})", entry.type_name, entry.file_path);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn build_impl_index(project: &ParsedProject) -> ImplIndex {
+    let mut index = ImplIndex::default();
+    
+    for file in project.files.iter().filter(|file| file.language == Language::Rust) {
+        let Ok(source) = retained_source(file) else {
+            continue;
+        };
+        let Some(tree) = syntax_tree_for(file, &source) else {
+            continue;
+        };
+        
+        for construct in search_by_node_type(file, "impl_item", None) {
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(construct.start_byte, construct.end_byte)
+            else {
+                continue;
+            };
+            let Some(type_node) = node.child_by_field_name("type") else {
+                continue;
+            };
+            let text = |n: tree_sitter::Node| slice_source(&source, n.start_byte(), n.end_byte());
+            
+            index.impls.push(ImplEntry {
+                trait_name: node.child_by_field_name("trait").map(text),
+                type_name: text(type_node),
+                file_path: file.file_path.clone(),
+                start_line: construct.start_line,
+            });
+        }
+    }
+    
+    index
+}
+
+/// Group each Rust type's definition with the methods of its `impl` blocks
+/// 
+/// Maps a type's bare name to its `struct`/`enum` definition followed by
+/// the methods of every `impl` block for it in the file, inherent and trait
+/// impls alike, in source order. Impls of types defined elsewhere get an
+/// entry holding only their methods. Generic and reference impl targets are
+/// matched by bare name, so `impl<T> Display for Wrapper<T>` joins
+/// `struct Wrapper<T>`.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed Rust file (other languages yield an empty map)
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{associate_impls, parse_string_sync, Language};
+/// 
+/// let source = "struct Counter(u32);\nimpl Counter {\n    fn increment(&mut self) { self.0 += 1; }\n}\n";
+/// let parsed = parse_string_sync(source, Language::Rust).unwrap();
+/// 
+/// let counter = &associate_impls(&parsed)["Counter"];
+/// assert_eq!(counter[0].node_type, "struct_item");
+/// assert_eq!(counter[1].name.as_deref(), Some("increment"));
+/// ```
+pub fn associate_impls(parsed_file: &ParsedFile) -> HashMap<String, Vec<CodeConstruct>> {
+    let mut types: HashMap<String, Vec<CodeConstruct>> = HashMap::new();
+    if parsed_file.language != Language::Rust {
+        return types;
+    }
+    
+    let constructs = parsed_file.all_constructs();
+    
+    // Definitions first, so each entry leads with its type
+    for definition in constructs.iter().filter(|c| matches!(c.node_type.as_str(), "struct_item" | "enum_item")) {
+        if let Some(name) = &definition.name {
+            types.entry(name.clone()).or_default().push((*definition).clone());
+        }
+    }
+    
+    for block in constructs.iter().filter(|c| c.node_type == "impl_item") {
+        let Some(type_name) = &block.name else {
+            continue;
+        };
+        let methods = block.children.iter().filter(|child| child.node_type == "function_item").cloned();
+        types.entry(bare_name(type_name).to_string()).or_default().extend(methods);
+    }
+    
+    types
+}
+
+impl ImplIndex {
+    /// Get the impl blocks implementing the named trait
+    /// 
+    /// `trait_name` matches either the trait exactly as written or its bare
+    /// name, so `"Display"` finds `impl fmt::Display for ...` and `"From"`
+    /// finds `impl From<u8> for ...`.
+    pub fn impls_of_trait(&self, trait_name: &str) -> Vec<&ImplEntry> {
+        self.impls
+            .iter()
+            .filter(|entry| entry.trait_name.as_deref().is_some_and(|name| names_match(name, trait_name)))
+            .collect()
+    }
+    
+    /// Get the traits implemented by the named type, in source order
+    /// 
+    /// `type_name` matches either the type exactly as written or its bare
+    /// name, so `"MyStruct"` finds `impl Trait for MyStruct<T>` and
+    /// `impl Trait for &MyStruct`. Inherent impls are not included.
+    pub fn traits_for_type(&self, type_name: &str) -> Vec<&str> {
+        let mut traits: Vec<&str> = Vec::new();
+        for entry in &self.impls {
+            if let Some(trait_name) = entry.trait_name.as_deref()
+                && names_match(&entry.type_name, type_name)
+                && !traits.contains(&trait_name) {
+                traits.push(trait_name);
+            }
+        }
+        traits
+    }
+}
+
+/// Check whether a written type or trait matches a query, exactly or by bare name
+fn names_match(written: &str, query: &str) -> bool {
+    written == query || bare_name(written) == query
+}
+
+/// Strip references, lifetimes, generics and paths (`&'a mut fmt::Foo<T>` -> `Foo`)
+fn bare_name(written: &str) -> &str {
+    let mut name = written.trim_start_matches('&').trim_start();
+    if name.starts_with('\'') {
+        name = name.split_once(' ').map_or(name, |(_, rest)| rest.trim_start());
+    }
+    for prefix in ["mut ", "dyn "] {
+        name = name.strip_prefix(prefix).unwrap_or(name).trim_start();
+    }
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_string_sync;
+    
+    #[test]
+    fn test_build_impl_index() {
+        let source = r#"
+struct Wrapper<T>(T);
+struct Name;
+
+impl<T: Clone> Wrapper<T> {
+    fn get(&self) -> T { self.0.clone() }
+}
+
+impl<T: fmt::Debug> fmt::Display for Wrapper<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "{:?}", self.0) }
+}
+
+impl<T> Clone for Wrapper<T> where T: Clone {
+    fn clone(&self) -> Self { Wrapper(self.0.clone()) }
+}
+
+impl<'a> From<&'a str> for &'a Name {
+    fn from(_: &'a str) -> Self { &Name }
+}
+
+impl std::fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "name") }
+}
+"#;
+        let mut file = parse_string_sync(source, Language::Rust).unwrap();
+        file.file_path = "lib.rs".to_string();
+        let project = ParsedProject::from_files(vec![file]);
+        let index = build_impl_index(&project);
+        
+        assert_eq!(index.impls.len(), 5);
+        assert_eq!(index.impls[0].trait_name, None);
+        assert_eq!(index.impls[0].type_name, "Wrapper<T>");
+        assert_eq!(index.impls[0].start_line, 5);
+        
+        let display: Vec<_> = index.impls_of_trait("Display").iter().map(|e| e.type_name.as_str()).collect();
+        assert_eq!(display, vec!["Wrapper<T>", "Name"]);
+        assert_eq!(index.impls_of_trait("From")[0].type_name, "&'a Name");
+        assert!(index.impls_of_trait("Debug").is_empty());
+        
+        assert_eq!(index.traits_for_type("Wrapper"), vec!["fmt::Display", "Clone"]);
+        assert_eq!(index.traits_for_type("Name"), vec!["From<&'a str>", "std::fmt::Display"]);
+        assert!(index.traits_for_type("Missing").is_empty());
+    }
+    
+    #[test]
+    fn test_associate_impls() {
+        let source = r#"
+pub struct Point<T> { x: T, y: T }
+
+impl<T: Copy> Point<T> {
+    pub fn new(x: T, y: T) -> Self { Point { x, y } }
+    pub fn x(&self) -> T { self.x }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "({:?}, {:?})", self.x, self.y) }
+}
+
+enum Shape { Circle }
+
+impl Drop for Foreign {
+    fn drop(&mut self) {}
+}
+"#;
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        let types = associate_impls(&parsed);
+        
+        let point: Vec<_> = types["Point"].iter().map(|c| (c.node_type.as_str(), c.name.as_deref())).collect();
+        assert_eq!(point, vec![
+            ("struct_item", Some("Point")),
+            ("function_item", Some("new")),
+            ("function_item", Some("x")),
+            ("function_item", Some("fmt")),
+        ]);
+        assert_eq!(types["Shape"].len(), 1);
+        assert_eq!(types["Foreign"][0].name.as_deref(), Some("drop"));
+        assert_eq!(types.len(), 3);
+    }
+}
@@ -1,9 +1,85 @@
 //! Language detection and tree-sitter language loading
 
-use crate::{Error, Language};
+use crate::{ConstructKind, Error, Language};
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::{LazyLock, RwLock};
 use tree_sitter::Language as TSLanguage;
 
+/// A grammar registered at runtime with `register_language`
+struct CustomLanguage {
+    language: TSLanguage,
+    extensions: Vec<String>,
+    node_types: Vec<String>,
+}
+
+/// Registered custom languages, keyed by name
+static CUSTOM_LANGUAGES: LazyLock<RwLock<HashMap<String, CustomLanguage>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Register a custom tree-sitter grammar under a name
+/// 
+/// Once registered, `Language::Custom(name)` works everywhere a built-in
+/// language does: `get_tree_sitter_language` returns the grammar,
+/// `detect_language_by_extension` maps the given extensions to it (ahead of
+/// the built-in table) and `get_supported_node_types` returns `node_types`
+/// as the constructs to extract. Registering a name again replaces it.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The language name carried by `Language::Custom`
+/// * `extensions` - File extensions without the leading dot (matched case-insensitively)
+/// * `language` - The tree-sitter grammar
+/// * `node_types` - Node types to extract as constructs
+/// 
+/// # Examples
+/// 
+/// ```rust,ignore
+/// use tree_parser::{detect_language_by_extension, register_language, Language};
+/// 
+/// register_language("mydsl", &["dsl"], tree_sitter_mydsl::LANGUAGE.into(), vec![
+///     "rule_definition".to_string(),
+/// ]);
+/// assert_eq!(detect_language_by_extension("build.dsl"), Some(Language::Custom("mydsl".to_string())));
+/// ```
+pub fn register_language(name: &str, extensions: &[&str], language: TSLanguage, node_types: Vec<String>) {
+    let custom = CustomLanguage {
+        language,
+        extensions: extensions.iter().map(|extension| extension.to_lowercase()).collect(),
+        node_types,
+    };
+    CUSTOM_LANGUAGES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(name.to_string(), custom);
+}
+
+/// Look up a registered custom language and map it through `f`
+fn with_custom_language<T>(name: &str, f: impl FnOnce(&CustomLanguage) -> T) -> Option<T> {
+    let registry = CUSTOM_LANGUAGES.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.get(name).map(f)
+}
+
+/// Find the registered custom language claiming an extension
+fn custom_language_for_extension(extension: &str) -> Option<Language> {
+    let registry = CUSTOM_LANGUAGES.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry
+        .iter()
+        .find(|(_, custom)| custom.extensions.iter().any(|candidate| candidate == extension))
+        .map(|(name, _)| Language::Custom(name.clone()))
+}
+
+/// Get the extensions of every registered custom language
+pub(crate) fn custom_language_extensions() -> Vec<String> {
+    let registry = CUSTOM_LANGUAGES.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.values().flat_map(|custom| custom.extensions.iter().cloned()).collect()
+}
+
+/// Check whether a custom language has been registered under `name`
+pub(crate) fn is_registered_language(name: &str) -> bool {
+    with_custom_language(name, |_| ()).is_some()
+}
+
 /// Get the tree-sitter language for a given Language enum
 pub fn get_tree_sitter_language(language: &Language) -> Result<TSLanguage, Error> {
     match language {
@@ -23,45 +99,170 @@ pub fn get_tree_sitter_language(language: &Language) -> Result<TSLanguage, Error
         Language::Cpp => Ok(tree_sitter_cpp::LANGUAGE.into()),
         #[cfg(feature = "go")]
         Language::Go => Ok(tree_sitter_go::LANGUAGE.into()),
+        #[cfg(feature = "markdown")]
+        Language::Markdown => Ok(tree_sitter_md::LANGUAGE.into()),
+        #[cfg(feature = "scala")]
+        Language::Scala => Ok(tree_sitter_scala::LANGUAGE.into()),
+        #[cfg(feature = "lua")]
+        Language::Lua => Ok(tree_sitter_lua::LANGUAGE.into()),
+        #[cfg(feature = "swift")]
+        Language::Swift => Ok(tree_sitter_swift::LANGUAGE.into()),
+        #[cfg(feature = "ruby")]
+        Language::Ruby => Ok(tree_sitter_ruby::LANGUAGE.into()),
+        Language::Custom(name) => with_custom_language(name, |custom| custom.language.clone())
+            .ok_or_else(|| Error::UnsupportedLanguage(name.clone())),
         _ => Err(Error::UnsupportedLanguage(format!("{:?}", language))),
     }
 }
 
+/// Every built-in language, in the order their extensions are listed
+pub(crate) const BUILTIN_LANGUAGES: &[Language] = &[
+    Language::Python,
+    Language::Rust,
+    Language::JavaScript,
+    Language::TypeScript,
+    Language::Java,
+    Language::C,
+    Language::Cpp,
+    Language::Go,
+    Language::CSharp,
+    Language::Php,
+    Language::Ruby,
+    Language::Swift,
+    Language::Kotlin,
+    Language::Scala,
+    Language::Haskell,
+    Language::Lua,
+    Language::Perl,
+    Language::R,
+    Language::Bash,
+    Language::PowerShell,
+    Language::Html,
+    Language::Css,
+    Language::Sql,
+    Language::Json,
+    Language::Yaml,
+    Language::Toml,
+    Language::Xml,
+    Language::Markdown,
+];
+
+impl Language {
+    /// Get the human-readable name of the language (e.g. `"C++"`, `"TypeScript"`)
+    /// 
+    /// Custom languages return `"Custom"`; use `language_to_string` to get
+    /// the registered name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Language::Python => "Python",
+            Language::Rust => "Rust",
+            Language::JavaScript => "JavaScript",
+            Language::TypeScript => "TypeScript",
+            Language::Java => "Java",
+            Language::C => "C",
+            Language::Cpp => "C++",
+            Language::Go => "Go",
+            Language::CSharp => "C#",
+            Language::Php => "PHP",
+            Language::Ruby => "Ruby",
+            Language::Swift => "Swift",
+            Language::Kotlin => "Kotlin",
+            Language::Scala => "Scala",
+            Language::Haskell => "Haskell",
+            Language::Lua => "Lua",
+            Language::Perl => "Perl",
+            Language::R => "R",
+            Language::Bash => "Bash",
+            Language::PowerShell => "PowerShell",
+            Language::Html => "HTML",
+            Language::Css => "CSS",
+            Language::Sql => "SQL",
+            Language::Json => "JSON",
+            Language::Yaml => "YAML",
+            Language::Toml => "TOML",
+            Language::Xml => "XML",
+            Language::Markdown => "Markdown",
+            Language::Custom(_) => "Custom",
+        }
+    }
+    
+    /// Get the conventional file extension of the language, without the leading dot
+    /// 
+    /// Returns an empty string for custom languages, whose extensions live in
+    /// the `register_language` registry.
+    pub fn primary_extension(&self) -> &'static str {
+        self.all_extensions().first().copied().unwrap_or("")
+    }
+    
+    /// Get every file extension mapped to the language, lowercase and without the leading dot
+    /// 
+    /// This is the table `detect_language_by_extension` and
+    /// `get_supported_extensions` are built from. Custom languages return an
+    /// empty slice.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use tree_parser::Language;
+    /// 
+    /// assert_eq!(Language::TypeScript.all_extensions(), &["ts", "mts", "cts"]);
+    /// assert_eq!(Language::Cpp.primary_extension(), "cpp");
+    /// assert_eq!(Language::Cpp.display_name(), "C++");
+    /// ```
+    pub fn all_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::Python => &["py", "pyw", "pyi"],
+            Language::Rust => &["rs"],
+            Language::JavaScript => &["js", "mjs", "cjs"],
+            Language::TypeScript => &["ts", "mts", "cts"],
+            Language::Java => &["java"],
+            Language::C => &["c", "h"],
+            Language::Cpp => &["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx", "h++"],
+            Language::Go => &["go"],
+            Language::CSharp => &["cs"],
+            Language::Php => &["php", "phtml", "php3", "php4", "php5", "phps"],
+            Language::Ruby => &["rb", "rbw"],
+            Language::Swift => &["swift"],
+            Language::Kotlin => &["kt", "kts"],
+            Language::Scala => &["scala", "sc"],
+            Language::Haskell => &["hs", "lhs"],
+            Language::Lua => &["lua"],
+            Language::Perl => &["pl", "pm", "t", "pod"],
+            Language::R => &["r"],
+            Language::Bash => &["sh", "bash", "zsh", "fish"],
+            Language::PowerShell => &["ps1", "psm1", "psd1"],
+            Language::Html => &["html", "htm", "xhtml"],
+            Language::Css => &["css"],
+            Language::Sql => &["sql"],
+            Language::Json => &["json"],
+            Language::Yaml => &["yaml", "yml"],
+            Language::Toml => &["toml"],
+            Language::Xml => &["xml", "xsd", "xsl", "xslt"],
+            Language::Markdown => &["md", "markdown"],
+            Language::Custom(_) => &[],
+        }
+    }
+}
+
 /// Detect language by file extension
 pub fn detect_language_by_extension(file_path: &str) -> Option<Language> {
     let path = Path::new(file_path);
     let extension = path.extension()?.to_str()?.to_lowercase();
     
-    match extension.as_str() {
-        "py" | "pyw" | "pyi" => Some(Language::Python),
-        "rs" => Some(Language::Rust),
-        "js" | "mjs" | "cjs" => Some(Language::JavaScript),
-        "ts" | "mts" | "cts" => Some(Language::TypeScript),
-        "java" => Some(Language::Java),
-        "c" | "h" => Some(Language::C),
-        "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hh" | "hxx" | "h++" => Some(Language::Cpp),
-        "go" => Some(Language::Go),
-        "cs" => Some(Language::CSharp),
-        "php" | "phtml" | "php3" | "php4" | "php5" | "phps" => Some(Language::Php),
-        "rb" | "rbw" => Some(Language::Ruby),
-        "swift" => Some(Language::Swift),
-        "kt" | "kts" => Some(Language::Kotlin),
-        "scala" | "sc" => Some(Language::Scala),
-        "hs" | "lhs" => Some(Language::Haskell),
-        "lua" => Some(Language::Lua),
-        "pl" | "pm" | "t" | "pod" => Some(Language::Perl),
-        "r" | "R" => Some(Language::R),
-        "sh" | "bash" | "zsh" | "fish" => Some(Language::Bash),
-        "ps1" | "psm1" | "psd1" => Some(Language::PowerShell),
-        "html" | "htm" | "xhtml" => Some(Language::Html),
-        "css" => Some(Language::Css),
-        "sql" => Some(Language::Sql),
-        "json" => Some(Language::Json),
-        "yaml" | "yml" => Some(Language::Yaml),
-        "toml" => Some(Language::Toml),
-        "xml" | "xsd" | "xsl" | "xslt" => Some(Language::Xml),
-        _ => None,
+    // Compressed files take the language of their inner extension
+    #[cfg(feature = "gzip")]
+    if extension == "gz" {
+        return detect_language_by_extension(&path.with_extension("").to_string_lossy());
+    }
+    
+    if let Some(custom) = custom_language_for_extension(&extension) {
+        return Some(custom);
     }
+    
+    BUILTIN_LANGUAGES
+        .iter()
+        .find(|language| language.all_extensions().contains(&extension.as_str()))
+        .cloned()
 }
 
 /// Detect language by shebang line
@@ -150,9 +351,11 @@ pub fn get_supported_node_types(language: &Language) -> Vec<String> {
             "import_from_statement".to_string(),
             "assignment".to_string(),
             "decorated_definition".to_string(),
+            "lambda".to_string(),
         ],
         Language::Rust => vec![
             "function_item".to_string(),
+            "function_signature_item".to_string(), // Trait methods without a default body
             "struct_item".to_string(),
             "enum_item".to_string(),
             "impl_item".to_string(),
@@ -161,10 +364,15 @@ pub fn get_supported_node_types(language: &Language) -> Vec<String> {
             "use_declaration".to_string(),
             "const_item".to_string(),
             "static_item".to_string(),
+            "type_item".to_string(),
+            "field_declaration".to_string(),
+            "macro_definition".to_string(),
+            "macro_invocation".to_string(),
         ],
         Language::JavaScript => vec![
             "function_declaration".to_string(),
             "function_expression".to_string(),
+            "generator_function_declaration".to_string(),
             "arrow_function".to_string(),
             "class_declaration".to_string(),
             "method_definition".to_string(),
@@ -175,11 +383,15 @@ pub fn get_supported_node_types(language: &Language) -> Vec<String> {
         Language::TypeScript => vec![
             "function_declaration".to_string(),
             "function_expression".to_string(),
+            "generator_function_declaration".to_string(),
             "arrow_function".to_string(),
             "class_declaration".to_string(),
             "interface_declaration".to_string(),
             "type_alias_declaration".to_string(),
             "method_definition".to_string(),
+            "function_signature".to_string(), // Bodiless, e.g. `declare function f(): void;` in .d.ts files
+            "method_signature".to_string(),
+            "abstract_method_signature".to_string(),
             "variable_declaration".to_string(),
             "import_statement".to_string(),
             "export_statement".to_string(),
@@ -199,8 +411,10 @@ pub fn get_supported_node_types(language: &Language) -> Vec<String> {
             "struct_specifier".to_string(),
             "union_specifier".to_string(),
             "enum_specifier".to_string(),
+            "type_definition".to_string(),
             "preproc_include".to_string(),
-            "preproc_define".to_string(),
+            "preproc_def".to_string(),
+            "preproc_function_def".to_string(),
         ],
         Language::Cpp => vec![
             "function_definition".to_string(),
@@ -209,19 +423,404 @@ pub fn get_supported_node_types(language: &Language) -> Vec<String> {
             "struct_specifier".to_string(),
             "union_specifier".to_string(),
             "enum_specifier".to_string(),
+            "type_definition".to_string(),
+            "alias_declaration".to_string(),
             "namespace_definition".to_string(),
             "preproc_include".to_string(),
-            "preproc_define".to_string(),
+            "preproc_def".to_string(),
+            "preproc_function_def".to_string(),
         ],
         Language::Go => vec![
             "function_declaration".to_string(),
             "method_declaration".to_string(),
             "type_declaration".to_string(),
+            "type_alias".to_string(),
             "var_declaration".to_string(),
             "const_declaration".to_string(),
             "import_declaration".to_string(),
             "package_clause".to_string(),
         ],
+        Language::Swift => vec![
+            "function_declaration".to_string(),
+            "init_declaration".to_string(),
+            "class_declaration".to_string(), // Also covers struct, enum, extension and actor
+            "protocol_declaration".to_string(),
+            "property_declaration".to_string(),
+            "typealias_declaration".to_string(),
+            "import_declaration".to_string(),
+        ],
+        Language::Scala => vec![
+            "function_definition".to_string(),
+            "function_declaration".to_string(),
+            "class_definition".to_string(),
+            "object_definition".to_string(),
+            "trait_definition".to_string(),
+            "enum_definition".to_string(),
+            "val_definition".to_string(),
+            "var_definition".to_string(),
+            "import_declaration".to_string(),
+            "package_clause".to_string(),
+        ],
+        Language::Lua => vec![
+            "function_declaration".to_string(),
+            "function_definition".to_string(),
+            "variable_declaration".to_string(), // Modules are tables, e.g. `local M = {}`
+        ],
+        Language::Ruby => vec![
+            "class".to_string(),
+            "module".to_string(),
+            "method".to_string(),
+            "singleton_method".to_string(), // `def self.name`
+        ],
+        Language::Markdown => vec![
+            "atx_heading".to_string(),
+            "setext_heading".to_string(),
+            "fenced_code_block".to_string(),
+        ],
+        Language::Custom(name) => with_custom_language(name, |custom| custom.node_types.clone()).unwrap_or_default(),
         _ => vec![], // For unsupported languages
     }
-}
\ No newline at end of file
+}
+
+/// Get the child node kinds that carry a construct's name for a language
+/// 
+/// Names are taken from the first direct child of one of these kinds, e.g.
+/// `type_identifier` for Rust structs, `property_identifier` for JavaScript
+/// methods and `constant` for Ruby classes. Languages without a table fall
+/// back to `identifier` and `name`.
+pub fn get_name_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Python | Language::Java | Language::Scala => &["identifier"],
+        Language::Lua => &["identifier", "dot_index_expression", "method_index_expression"], // `M.foo`, `M:foo`
+        Language::Rust => &["identifier", "type_identifier"],
+        Language::JavaScript => &["identifier", "property_identifier", "private_property_identifier"],
+        Language::TypeScript => &[
+            "identifier",
+            "type_identifier",
+            "property_identifier",
+            "private_property_identifier",
+        ],
+        Language::C | Language::Cpp => &["identifier", "type_identifier"],
+        Language::Go => &["identifier", "field_identifier", "type_identifier"],
+        Language::Swift => &["simple_identifier", "type_identifier"],
+        Language::Ruby => &["constant", "identifier"],
+        Language::Markdown => &["inline"], // Heading text
+        _ => &["identifier", "name"],
+    }
+}
+
+/// Get the node types that represent functions and methods for a language
+pub fn get_function_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &["function_definition", "lambda"],
+        Language::Rust => &["function_item", "function_signature_item"],
+        Language::JavaScript => &[
+            "function_declaration",
+            "function_expression",
+            "generator_function_declaration",
+            "arrow_function",
+            "method_definition",
+        ],
+        Language::TypeScript => &[
+            "function_declaration",
+            "function_expression",
+            "generator_function_declaration",
+            "arrow_function",
+            "method_definition",
+            "function_signature",
+            "method_signature",
+            "abstract_method_signature",
+        ],
+        Language::Java => &["method_declaration", "constructor_declaration"],
+        Language::C | Language::Cpp => &["function_definition"],
+        Language::Go => &["function_declaration", "method_declaration"],
+        Language::Swift => &["function_declaration", "init_declaration"],
+        Language::Scala => &["function_definition", "function_declaration"],
+        Language::Lua => &["function_declaration", "function_definition"],
+        Language::Ruby => &["method", "singleton_method"],
+        _ => &[],
+    }
+}
+
+/// Get the node types that represent classes and other type definitions for a language
+pub fn get_class_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &["class_definition"],
+        Language::Rust => &["struct_item", "enum_item", "trait_item"],
+        Language::JavaScript => &["class_declaration"],
+        Language::TypeScript => &["class_declaration", "interface_declaration"],
+        Language::Java => &["class_declaration", "interface_declaration"],
+        Language::C => &["struct_specifier"],
+        Language::Cpp => &["class_specifier", "struct_specifier"],
+        Language::Go => &["type_declaration"],
+        Language::Swift => &["class_declaration", "protocol_declaration"],
+        Language::Scala => &["class_definition", "object_definition", "trait_definition", "enum_definition"],
+        Language::Ruby => &["class", "module"],
+        _ => &[],
+    }
+}
+
+/// Get the node types that represent type aliases and typedefs for a language
+pub fn get_type_alias_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &["type_item"],
+        Language::TypeScript => &["type_alias_declaration"],
+        Language::C => &["type_definition"],
+        Language::Cpp => &["type_definition", "alias_declaration"],
+        Language::Go => &["type_alias"],
+        Language::Swift => &["typealias_declaration"],
+        _ => &[],
+    }
+}
+
+/// Classify a construct's node type into a language-independent kind
+/// 
+/// Only the node type is considered, so a Python `function_definition`
+/// inside a class is still a `Function`; node types that are methods in
+/// every context (Java `method_declaration`, Rust trait method signatures,
+/// constructors) are `Method`. Go `type_declaration` can declare a struct,
+/// an interface or an alias and is `Other`, like wrappers such as
+/// `decorated_definition` and `export_statement`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{get_construct_kind, ConstructKind, Language};
+/// 
+/// assert_eq!(get_construct_kind(&Language::Python, "function_definition"), ConstructKind::Function);
+/// assert_eq!(get_construct_kind(&Language::Rust, "trait_item"), ConstructKind::Interface);
+/// ```
+pub fn get_construct_kind(language: &Language, node_type: &str) -> ConstructKind {
+    if matches!(language, Language::Markdown) {
+        return ConstructKind::Other;
+    }
+    
+    match node_type {
+        "function_definition" | "function_declaration" | "function_item" | "function_expression"
+        | "generator_function_declaration" | "arrow_function" | "lambda" | "function_signature" => {
+            ConstructKind::Function
+        }
+        "method_definition" | "method_declaration" | "method_signature" | "abstract_method_signature"
+        | "function_signature_item" | "constructor_declaration" | "init_declaration" => ConstructKind::Method,
+        "method" | "singleton_method" if *language == Language::Ruby => ConstructKind::Method,
+        "class" if *language == Language::Ruby => ConstructKind::Class,
+        "module" if *language == Language::Ruby => ConstructKind::Module,
+        "class_definition" | "class_declaration" | "class_specifier" | "object_definition" => ConstructKind::Class,
+        "struct_item" | "struct_specifier" | "union_specifier" => ConstructKind::Struct,
+        "trait_item" | "trait_definition" | "interface_declaration" | "protocol_declaration" => {
+            ConstructKind::Interface
+        }
+        "enum_item" | "enum_specifier" | "enum_definition" | "enum_declaration" => ConstructKind::Enum,
+        "import_statement" | "import_from_statement" | "import_declaration" | "use_declaration"
+        | "preproc_include" => ConstructKind::Import,
+        "assignment" | "variable_declaration" | "lexical_declaration" | "var_declaration" | "const_declaration"
+        | "const_item" | "static_item" | "field_declaration" | "property_declaration" | "val_definition"
+        | "var_definition" => ConstructKind::Variable,
+        // Only C and C++ extract bare declarations, which are mostly variables
+        "declaration" if matches!(language, Language::C | Language::Cpp) => ConstructKind::Variable,
+        "mod_item" | "namespace_definition" | "package_declaration" | "package_clause" => ConstructKind::Module,
+        _ => ConstructKind::Other,
+    }
+}
+
+/// Get the node kinds that extraction descends into when `ParseOptions::skip_non_containers` is set
+/// 
+/// Containers are the statement lists and declaration bodies where constructs
+/// usually live, such as Rust `declaration_list` or Python `block`, plus the
+/// statements and call arguments that commonly hold nested constructs such
+/// as macro invocations, lambdas and callbacks. Construct kinds are always
+/// descended into. Any other node is skipped along with its subtree.
+/// Languages without a table (an empty slice) are always fully traversed.
+pub fn get_container_node_types(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &[
+            "module",
+            "block",
+            "expression_statement", // Holds assignments
+            "return_statement",
+            "call",
+            "argument_list",
+            "keyword_argument",
+            "if_statement",
+            "elif_clause",
+            "else_clause",
+            "try_statement",
+            "except_clause",
+            "finally_clause",
+            "with_statement",
+        ],
+        Language::Rust => &[
+            "source_file",
+            "declaration_list",
+            "block",
+            "expression_statement",
+            "let_declaration",
+            "return_expression",
+            "call_expression",
+            "arguments",
+            "field_declaration_list",
+            "enum_variant_list",
+            "enum_variant",
+        ],
+        Language::JavaScript => &[
+            "program",
+            "statement_block",
+            "class_body",
+            "lexical_declaration",
+            "variable_declarator",
+            "expression_statement",
+            "return_statement",
+            "call_expression",
+            "arguments",
+        ],
+        Language::TypeScript => &[
+            "program",
+            "statement_block",
+            "class_body",
+            "lexical_declaration",
+            "variable_declarator",
+            "expression_statement",
+            "return_statement",
+            "call_expression",
+            "arguments",
+            "ambient_declaration",
+            "internal_module",
+            "module",
+        ],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert that every node type in a language's tables is a named node of its grammar
+    #[cfg(any(feature = "swift", feature = "ruby", feature = "scala", feature = "lua"))]
+    fn assert_tables_match_grammar(language: Language, node_types_json: &str) {
+        let grammar: Vec<serde_json::Value> = serde_json::from_str(node_types_json).unwrap();
+        let named: Vec<&str> = grammar
+            .iter()
+            .filter(|node| node["named"] == true)
+            .filter_map(|node| node["type"].as_str())
+            .collect();
+        
+        let supported = get_supported_node_types(&language);
+        for node_type in &supported {
+            assert!(named.contains(&node_type.as_str()), "{:?} {}", language, node_type);
+        }
+        // Function, class and alias tables only name extracted node types
+        for node_type in get_function_node_types(&language)
+            .iter()
+            .chain(get_class_node_types(&language))
+            .chain(get_type_alias_node_types(&language))
+        {
+            assert!(supported.contains(&node_type.to_string()), "{:?} {}", language, node_type);
+        }
+        for node_type in get_name_node_types(&language) {
+            assert!(named.contains(node_type), "{:?} {}", language, node_type);
+        }
+    }
+
+    #[test]
+    #[cfg(any(feature = "swift", feature = "ruby", feature = "scala", feature = "lua"))]
+    fn test_node_type_tables_match_grammars() {
+        #[cfg(feature = "swift")]
+        assert_tables_match_grammar(Language::Swift, tree_sitter_swift::NODE_TYPES);
+        #[cfg(feature = "ruby")]
+        assert_tables_match_grammar(Language::Ruby, tree_sitter_ruby::NODE_TYPES);
+        #[cfg(feature = "scala")]
+        assert_tables_match_grammar(Language::Scala, tree_sitter_scala::NODE_TYPES);
+        #[cfg(feature = "lua")]
+        assert_tables_match_grammar(Language::Lua, tree_sitter_lua::NODE_TYPES);
+    }
+
+    #[test]
+    fn test_name_node_types() {
+        assert!(get_name_node_types(&Language::Rust).contains(&"type_identifier"));
+        assert!(get_name_node_types(&Language::JavaScript).contains(&"property_identifier"));
+        assert!(get_name_node_types(&Language::Go).contains(&"field_identifier"));
+        
+        assert_eq!(get_name_node_types(&Language::Php), &["identifier", "name"]);
+    }
+    
+    #[test]
+    #[cfg(feature = "ruby")]
+    fn test_ruby_construct_names() {
+        // Classes and modules are named by a `constant` child, methods by an `identifier`
+        let source = "module Shapes\n  class Circle < Base\n    def area\n      3\n    end\n\n    def self.unit\n    end\n  end\nend\n";
+        let parsed = crate::parse_string_sync(source, Language::Ruby).unwrap();
+        
+        let names: Vec<_> = parsed
+            .all_constructs()
+            .into_iter()
+            .map(|c| (c.node_type.as_str(), c.name.as_deref(), c.kind))
+            .collect();
+        assert_eq!(names, vec![
+            ("module", Some("Shapes"), crate::ConstructKind::Module),
+            ("class", Some("Circle"), crate::ConstructKind::Class),
+            ("method", Some("area"), crate::ConstructKind::Method),
+            ("singleton_method", Some("unit"), crate::ConstructKind::Method),
+        ]);
+    }
+    
+    #[test]
+    #[cfg(feature = "swift")]
+    fn test_swift_construct_names() {
+        let source = "import Foundation\n\nstruct Point {\n    var x: Int\n    init(x: Int) { self.x = x }\n    func norm() -> Int { return x }\n}\n";
+        let parsed = crate::parse_string_sync(source, Language::Swift).unwrap();
+        
+        let names: Vec<_> = parsed.all_constructs().into_iter().map(|c| (c.node_type.as_str(), c.name.as_deref())).collect();
+        assert_eq!(names, vec![
+            ("import_declaration", None),
+            ("class_declaration", Some("Point")),
+            ("property_declaration", None),
+            ("init_declaration", None),
+            ("function_declaration", Some("norm")),
+        ]);
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "python", not(target_arch = "wasm32")))]
+    async fn test_register_custom_language() {
+        // Stand in for an external grammar with the bundled Python one
+        register_language("pyrules", &["PYRULES"], tree_sitter_python::LANGUAGE.into(), vec![
+            "function_definition".to_string(),
+        ]);
+        let custom = Language::Custom("pyrules".to_string());
+        
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tree_parser_custom_language.pyrules");
+        std::fs::write(&path, "class Ignored:\n    pass\n\ndef rule():\n    pass\n").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        
+        assert_eq!(detect_language_by_extension(&path_str), Some(custom.clone()));
+        assert_eq!(crate::language_from_string("pyrules"), Some(custom.clone()));
+        assert!(crate::get_supported_extensions().contains(&"pyrules".to_string()));
+        
+        let parsed = crate::parse_file(&path_str, custom.clone()).await.unwrap();
+        let names: Vec<_> = parsed.constructs.iter().map(|c| (c.node_type.as_str(), c.name.as_deref())).collect();
+        assert_eq!(names, vec![("function_definition", Some("rule"))]);
+        assert_eq!(parsed.language, custom);
+        
+        let unknown = Language::Custom("unregistered".to_string());
+        assert!(matches!(get_tree_sitter_language(&unknown), Err(Error::UnsupportedLanguage(_))));
+    }
+    
+    #[test]
+    fn test_extension_table_agrees_with_detection() {
+        for language in BUILTIN_LANGUAGES {
+            assert!(!language.all_extensions().is_empty(), "{:?}", language);
+            assert_eq!(language.primary_extension(), language.all_extensions()[0]);
+            for extension in language.all_extensions() {
+                let path = format!("file.{}", extension);
+                assert_eq!(detect_language_by_extension(&path).as_ref(), Some(language), "{}", path);
+            }
+        }
+        
+        let supported = crate::get_supported_extensions();
+        let table: Vec<&str> = BUILTIN_LANGUAGES.iter().flat_map(|l| l.all_extensions().iter().copied()).collect();
+        assert_eq!(&supported[..table.len()], &table[..]);
+        assert_eq!(Language::Custom("mydsl".to_string()).primary_extension(), "");
+    }
+}
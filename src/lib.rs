@@ -15,7 +15,7 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tree_parser::{parse_file, Language};
 //!
 //! #[tokio::main]
@@ -42,7 +42,7 @@
 //!
 //! ### 1. Search by Node Type
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tree_parser::{parse_file, search_by_node_type, Language};
 //!
 //! #[tokio::main]
@@ -62,7 +62,7 @@
 //!
 //! ### 2. Search by Multiple Node Types
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tree_parser::{parse_file, search_by_multiple_node_types, Language};
 //!
 //! #[tokio::main]
@@ -83,7 +83,7 @@
 //!
 //! ### 3. Advanced Search with Tree-sitter Queries
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tree_parser::{parse_file, search_by_query, Language};
 //!
 //! #[tokio::main]
@@ -143,7 +143,7 @@
 //!
 //! ### Discovering Node Types
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tree_parser::{parse_file, Language};
 //! use std::collections::HashSet;
 //!
@@ -174,6 +174,15 @@
 //! 3. Explore the generated syntax tree
 //! 4. Identify the exact node types you need
 //!
+//! ## WebAssembly
+//!
+//! Build for `wasm32-unknown-unknown` with `default-features = false` plus the grammars
+//! you need. On wasm32 targets, everything that touches the filesystem or needs tokio
+//! is compiled out: `parse_file`, `parse_file_sync`,
+//! `parse_file_with_options`, `parse_string`, `parse_reader`, `parse_directory` and
+//! `parse_directory_with_filter`. In-memory parsing with `parse_string_sync` and all
+//! search, extraction, query and utility functions remain available.
+//!
 //! ## Best Practices
 //!
 //! ### Performance Optimization
@@ -212,6 +221,7 @@ use tree_sitter::Tree;
 
 // Re-export commonly used types
 pub use tree_sitter::{Point, Range};
+pub use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
 
 // Language modules
 mod languages;
@@ -235,19 +245,53 @@ pub enum Error {
     PermissionDenied(String),
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
+    #[error("Syntax tree not available; parse with the syntax tree retained")]
+    SyntaxTreeUnavailable,
+    #[error("Parsing timed out after {0}ms")]
+    Timeout(u64),
+    #[error("Binary content in {0}")]
+    BinaryFile(String),
+}
+
+impl Error {
+    /// Get the broad category of this error
+    /// 
+    /// Used when recording per-file failures in `ParsedProject::error_files`.
+    /// Equivalent to `ErrorType::from(&error)`.
+    pub fn error_type(&self) -> ErrorType {
+        ErrorType::from(self)
+    }
+}
+
+impl From<&Error> for ErrorType {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::Io(_) => ErrorType::IoError,
+            Error::Parse(_) | Error::InvalidQuery(_) => ErrorType::ParseError,
+            Error::UnsupportedLanguage(_) => ErrorType::UnsupportedLanguage,
+            Error::FileTooLarge(_) => ErrorType::FileTooLarge,
+            Error::PermissionDenied(_) => ErrorType::PermissionDenied,
+            Error::SyntaxTreeUnavailable => ErrorType::SyntaxTreeUnavailable,
+            Error::Timeout(_) => ErrorType::Timeout,
+            Error::BinaryFile(_) => ErrorType::BinaryFile,
+        }
+    }
 }
 
 /// Categorizes different types of errors for easier handling
 /// 
 /// This enum is used to classify errors into broad categories, making it easier
 /// to implement different error handling strategies for different error types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ErrorType {
     ParseError,
     IoError,
     UnsupportedLanguage,
     FileTooLarge,
     PermissionDenied,
+    SyntaxTreeUnavailable,
+    Timeout,
+    BinaryFile,
 }
 
 /// Represents an error that occurred while processing a specific file
@@ -261,6 +305,32 @@ pub struct FileError {
     pub message: String,
 }
 
+/// Categorizes conditions that are reported as warnings rather than errors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarningReason {
+    /// The file exceeded `ParseOptions::max_file_size_mb` and was skipped
+    FileTooLarge,
+    /// The file's language was detected but has no grammar enabled
+    UnsupportedLanguage,
+    /// The file parsed, but tree-sitter recovered from syntax errors, so its
+    /// constructs may be incomplete
+    SyntaxErrorsRecovered,
+    /// The file parsed, but construct extraction stopped at
+    /// `ParseOptions::max_depth`, so deeper constructs are missing
+    DepthLimitReached,
+}
+
+/// Represents a non-fatal condition encountered while processing a specific file
+/// 
+/// Unlike a `FileError`, a warning does not mean the file failed: it was
+/// either skipped on purpose or parsed with reduced fidelity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileWarning {
+    pub file_path: String,
+    pub reason: WarningReason,
+    pub message: String,
+}
+
 /// Supported programming languages
 /// 
 /// This enum represents all programming languages that the tree parser can handle.
@@ -277,6 +347,11 @@ pub struct FileError {
 /// - `c` - C support
 /// - `cpp` - C++ support
 /// - `go` - Go support
+/// - `markdown` - Markdown support
+/// - `scala` - Scala support
+/// - `lua` - Lua support
+/// - `swift` - Swift support
+/// - `ruby` - Ruby support
 /// - `full` - All languages
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Language {
@@ -307,6 +382,9 @@ pub enum Language {
     Yaml,
     Toml,
     Xml,
+    Markdown,
+    /// A language registered at runtime with `register_language`, identified by name
+    Custom(String),
 }
 
 /// Methods for detecting the programming language of a file
@@ -333,6 +411,20 @@ pub struct Parameter {
     pub is_variadic: bool,
 }
 
+/// An attribute, decorator or annotation split into its name and arguments
+/// 
+/// `#[derive(Debug, Clone)]` has the name `derive` and the arguments `Debug`
+/// and `Clone`; `@RequestMapping(path = "/x", method = GET)` has the name
+/// `RequestMapping` and the arguments `path = "/x"` and `method = GET`.
+/// Arguments are the raw source text of each top-level, comma-separated
+/// argument; a `name = value` attribute without parentheses such as
+/// `#[doc = "..."]` has its value as the only argument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub name: String,
+    pub arguments: Vec<String>,
+}
+
 /// Metadata associated with a code construct
 /// 
 /// This struct contains additional information about code constructs such as
@@ -345,7 +437,41 @@ pub struct ConstructMetadata {
     pub return_type: Option<String>,
     pub inheritance: Vec<String>,
     pub annotations: Vec<String>,
+    /// `annotations` split into name and arguments, in the same order
+    #[serde(default)]
+    pub parsed_annotations: Vec<Annotation>,
     pub documentation: Option<String>,
+    /// Declared type of a field or annotated attribute (e.g. `i32`, `String`, `int`)
+    #[serde(default)]
+    pub field_type: Option<String>,
+    /// Generic parameters with their bounds (e.g. `T: Clone`, `E extends Comparable<E>`)
+    #[serde(default)]
+    pub type_parameters: Vec<String>,
+    /// Callee of the call a JavaScript or TypeScript callback is passed straight to
+    /// (e.g. `it` for `it('adds', () => {})`), which registers the callback
+    #[serde(default)]
+    pub registered_by: Option<String>,
+}
+
+/// A language-independent classification of a construct's node type
+/// 
+/// Derived from the language and `node_type` by `get_construct_kind`, so
+/// tooling can filter e.g. all functions without knowing that Python calls
+/// them `function_definition` and Rust `function_item`. Node types that don't
+/// fit a kind, such as wrappers, type aliases and macros, are `Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ConstructKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Interface,
+    Enum,
+    Import,
+    Variable,
+    Module,
+    #[default]
+    Other,
 }
 
 /// Represents a parsed code construct (function, class, struct, etc.)
@@ -356,17 +482,325 @@ pub struct ConstructMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeConstruct {
     pub node_type: String,
+    /// Language-independent kind of `node_type`
+    #[serde(default)]
+    pub kind: ConstructKind,
+    /// Language of the source the construct was extracted from
+    /// (None in JSON written before it was recorded)
+    #[serde(default)]
+    pub language: Option<Language>,
     pub name: Option<String>,
+    /// Positional stand-in for `name` on anonymous constructs (e.g. `<anonymous@3:14>`),
+    /// set when `ParseOptions::synthesize_anonymous_names` is enabled
+    #[serde(default)]
+    pub synthetic_name: Option<String>,
     pub source_code: String,
     pub start_line: usize,
     pub end_line: usize,
+    /// 0-based column (byte offset within the line) where the construct starts, matching `Point`
+    #[serde(default)]
+    pub start_column: usize,
+    /// 0-based column (byte offset within the line) where the construct ends, matching `Point`
+    #[serde(default)]
+    pub end_column: usize,
     pub start_byte: usize,
     pub end_byte: usize,
+    /// Byte range `(start, end)` of the body block (e.g. a function's `{ ... }`),
+    /// `None` for constructs without a body such as imports and variables
+    #[serde(default)]
+    pub body_range: Option<(usize, usize)>,
+    /// Snapshot of the enclosing construct, not serialized to avoid repeating every ancestor
+    /// 
+    /// Deserializing a `ParsedFile` restores it from the `children` hierarchy.
+    #[serde(skip)]
     pub parent: Option<Box<CodeConstruct>>,
+    /// Number of enclosing constructs, 0 for top-level ones
+    #[serde(default)]
+    pub depth: usize,
     pub children: Vec<CodeConstruct>,
     pub metadata: ConstructMetadata,
 }
 
+impl CodeConstruct {
+    /// Compare two constructs by structure, ignoring where they appear
+    /// 
+    /// Constructs are structurally equal when their `language`, `node_type` and
+    /// `name` match and their `source_code` is identical after `normalized_source`.
+    /// Positions, parents, children and metadata are ignored, which makes
+    /// this suitable for clone detection across files.
+    pub fn structural_eq(&self, other: &CodeConstruct) -> bool {
+        self.language == other.language
+            && self.node_type == other.node_type
+            && self.name == other.name
+            && normalized_source(self) == normalized_source(other)
+    }
+    
+    /// Hash consistent with `structural_eq`, for grouping structurally equal constructs
+    pub fn structural_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.language.hash(&mut hasher);
+        self.node_type.hash(&mut hasher);
+        self.name.hash(&mut hasher);
+        normalized_source(self).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A construct matched by `fuzzy_find_symbols`
+/// 
+/// Higher scores are better matches; scores are only meaningful relative to
+/// other matches for the same query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMatch {
+    pub name: String,
+    pub node_type: String,
+    pub file_path: String,
+    /// 1-based line where the construct starts
+    pub start_line: usize,
+    /// 0-based column where the construct starts
+    #[serde(default)]
+    pub start_column: usize,
+    pub score: i64,
+}
+
+/// The kind of a `DocumentSymbol`, numbered as in the LSP specification
+/// 
+/// It serializes as that number, and `kind as u8` gives the same value. See
+/// `document_symbols` for how node types map onto kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    File = 1,
+    Module = 2,
+    Namespace = 3,
+    Package = 4,
+    Class = 5,
+    Method = 6,
+    Property = 7,
+    Field = 8,
+    Constructor = 9,
+    Enum = 10,
+    Interface = 11,
+    Function = 12,
+    Variable = 13,
+    Constant = 14,
+    String = 15,
+    Number = 16,
+    Boolean = 17,
+    Array = 18,
+    Object = 19,
+    Key = 20,
+    Null = 21,
+    EnumMember = 22,
+    Struct = 23,
+    Event = 24,
+    Operator = 25,
+    TypeParameter = 26,
+}
+
+impl SymbolKind {
+    const ALL: [SymbolKind; 26] = [
+        SymbolKind::File,
+        SymbolKind::Module,
+        SymbolKind::Namespace,
+        SymbolKind::Package,
+        SymbolKind::Class,
+        SymbolKind::Method,
+        SymbolKind::Property,
+        SymbolKind::Field,
+        SymbolKind::Constructor,
+        SymbolKind::Enum,
+        SymbolKind::Interface,
+        SymbolKind::Function,
+        SymbolKind::Variable,
+        SymbolKind::Constant,
+        SymbolKind::String,
+        SymbolKind::Number,
+        SymbolKind::Boolean,
+        SymbolKind::Array,
+        SymbolKind::Object,
+        SymbolKind::Key,
+        SymbolKind::Null,
+        SymbolKind::EnumMember,
+        SymbolKind::Struct,
+        SymbolKind::Event,
+        SymbolKind::Operator,
+        SymbolKind::TypeParameter,
+    ];
+}
+
+impl Serialize for SymbolKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for SymbolKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        SymbolKind::ALL
+            .into_iter()
+            .find(|kind| *kind as u8 == value)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid LSP symbol kind {}", value)))
+    }
+}
+
+/// A 0-based position in a document, as in LSP
+/// 
+/// `character` counts UTF-16 code units from the start of the line, as LSP
+/// expects, unlike the byte-based `CodeConstruct::start_column`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentPosition {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// A range between two `DocumentPosition`s, end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentRange {
+    pub start: DocumentPosition,
+    pub end: DocumentPosition,
+}
+
+/// A symbol shaped like LSP's `DocumentSymbol`, produced by `document_symbols`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The full extent of the construct
+    pub range: DocumentRange,
+    /// The construct's name, or its full range when the name can't be located
+    pub selection_range: DocumentRange,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Represents a string literal found in source code
+/// 
+/// This struct contains the raw text of a string literal (including its
+/// delimiters) together with its location, and whether it is a template or
+/// format string such as a JavaScript template literal or a Python f-string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringLiteral {
+    pub text: String,
+    /// The source text between the delimiters, with prefixes, quotes, raw
+    /// string hashes and custom delimiters removed; escape sequences are kept
+    /// as written
+    #[serde(default)]
+    pub content: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 0-based column where the literal starts
+    #[serde(default)]
+    pub start_column: usize,
+    /// 0-based column where the literal ends
+    #[serde(default)]
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub is_template: bool,
+}
+
+/// A `TODO`-style annotation found in a comment
+/// 
+/// Parsed from comments such as `// TODO(alice): fix this` by
+/// `scan_annotations`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoItem {
+    /// The annotation tag: `TODO`, `FIXME`, `HACK`, `XXX` or `NOTE`
+    pub tag: String,
+    /// The name in parentheses after the tag, if any
+    pub assignee: Option<String>,
+    /// The text following the tag
+    pub message: String,
+    pub file_path: String,
+    /// 1-based line of the annotation
+    pub line: usize,
+}
+
+/// An import statement split into its module and imported names, from `imported_modules`
+/// 
+/// Statements that import from several modules, such as Rust `use a::{b::c, d}`
+/// or Python `import os, sys`, produce one `ImportInfo` per module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportInfo {
+    /// The module path as written, e.g. `os.path`, `..pkg`, `std::collections` or `./util`
+    pub module: String,
+    /// Names imported from the module; empty when the module itself is imported
+    pub symbols: Vec<ImportedSymbol>,
+    /// Local name for the module itself (`import numpy as np`, `import * as fs from 'fs'`)
+    pub alias: Option<String>,
+    /// 1-based line of the import statement
+    pub line: usize,
+}
+
+/// A name imported from a module, with its local alias if renamed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedSymbol {
+    /// The imported name, `*` for wildcard imports and `default` for JavaScript default imports
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// Source line counts of a file, computed by `sloc`
+/// 
+/// Every line is counted once: `blank + comment + code == total`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlocStats {
+    pub total: usize,
+    /// Lines containing only code, or code and a comment
+    pub code: usize,
+    /// Lines containing only comments
+    pub comment: usize,
+    /// Lines containing only whitespace
+    pub blank: usize,
+}
+
+/// A directed graph of function calls across a project
+/// 
+/// Nodes are indices into `nodes`; each edge `(caller, callee)` points from
+/// a project function to a function it calls. Built with `build_call_graph`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallGraph {
+    pub nodes: Vec<CallGraphNode>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// A function in a `CallGraph`
+/// 
+/// Project functions are identified by a qualified name of the form
+/// `file_path::Parent.name`. Calls that could not be resolved to a project
+/// function become external nodes named after the call text (e.g. `print`
+/// or `self.client.send`) with no file path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallGraphNode {
+    pub qualified_name: String,
+    pub name: String,
+    pub file_path: Option<String>,
+    pub external: bool,
+}
+
+/// An index of Rust `impl` blocks across a project
+/// 
+/// Built with `build_impl_index`. Each entry records the implemented trait
+/// (for `impl Trait for Type`) and the target type, as written in the source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImplIndex {
+    pub impls: Vec<ImplEntry>,
+}
+
+/// A single `impl` block in an `ImplIndex`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImplEntry {
+    /// The implemented trait as written (e.g. `fmt::Display`), `None` for inherent impls
+    pub trait_name: Option<String>,
+    /// The target type as written (e.g. `Wrapper<T>`)
+    pub type_name: String,
+    pub file_path: String,
+    /// 1-based line where the `impl` block starts
+    pub start_line: usize,
+}
+
 /// Represents a successfully parsed source code file
 /// 
 /// This struct contains all information extracted from a single file,
@@ -374,12 +808,165 @@ pub struct CodeConstruct {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedFile {
     pub file_path: String,
+    /// Path below the root for directory parses (e.g. `src/net/mod.rs`),
+    /// the file name alone for single-file parses
     pub relative_path: String,
     pub language: Language,
+    /// Top-level constructs, or every construct when `ParseOptions::flatten_constructs` is set
+    #[serde(deserialize_with = "deserialize_constructs")]
     pub constructs: Vec<CodeConstruct>,
     #[serde(skip)]
     pub syntax_tree: Option<Tree>,
+    /// Source text the syntax tree was built from, retained alongside `syntax_tree`
+    /// 
+    /// Always kept for sources that exist only in memory (strings, readers, git
+    /// blobs). Files read from disk keep it only with `ParseOptions::retain_source`;
+    /// otherwise functions that need the text read the file again.
+    #[serde(skip)]
+    pub source: Option<String>,
     pub file_size_bytes: usize,
+    /// Whether construct extraction stopped early because of `ParseOptions::max_depth`
+    #[serde(default)]
+    pub depth_limit_reached: bool,
+    /// Time spent in tree-sitter parsing and construct extraction, in milliseconds
+    /// 
+    /// Always 0 on wasm32 targets, where no monotonic clock is available.
+    #[serde(default)]
+    pub parse_duration_ms: u64,
+    /// XXH3 hash of the source text, stable across runs for detecting unchanged files
+    #[serde(default)]
+    pub content_hash: u64,
+    /// Whether CRLF line endings were converted to LF before parsing, so that
+    /// offsets refer to the normalized text rather than the file on disk
+    #[serde(default)]
+    pub line_endings_normalized: bool,
+    /// Locations of `ERROR` and `MISSING` nodes, collected when
+    /// `ParseOptions::report_syntax_errors` is set (empty otherwise)
+    #[serde(default, serialize_with = "serialize_ranges", deserialize_with = "deserialize_ranges")]
+    pub syntax_errors: Vec<Range>,
+}
+
+impl ParsedFile {
+    /// Get every construct in the file exactly once, in source order
+    /// 
+    /// This works regardless of `ParseOptions::flatten_constructs`: nested
+    /// constructs are reached through `children` starting from the top-level
+    /// ones, so flattened files don't yield duplicates.
+    pub fn all_constructs(&self) -> Vec<&CodeConstruct> {
+        fn collect<'a>(construct: &'a CodeConstruct, all: &mut Vec<&'a CodeConstruct>) {
+            all.push(construct);
+            for child in &construct.children {
+                collect(child, all);
+            }
+        }
+        
+        let mut all = Vec::new();
+        for construct in self.constructs.iter().filter(|construct| construct.depth == 0) {
+            collect(construct, &mut all);
+        }
+        all
+    }
+}
+
+/// Serde mirror of `tree_sitter::Point`, which does not implement serde itself
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Point")]
+struct PointDef {
+    row: usize,
+    column: usize,
+}
+
+/// Serde mirror of `tree_sitter::Range`, which does not implement serde itself
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Range")]
+struct RangeDef {
+    start_byte: usize,
+    end_byte: usize,
+    #[serde(with = "PointDef")]
+    start_point: Point,
+    #[serde(with = "PointDef")]
+    end_point: Point,
+}
+
+/// A `Range` that serializes through `RangeDef`
+#[derive(Serialize, Deserialize)]
+struct SerdeRange(#[serde(with = "RangeDef")] Range);
+
+fn serialize_ranges<S>(ranges: &[Range], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(ranges.iter().map(|range| SerdeRange(*range)))
+}
+
+fn deserialize_ranges<'de, D>(deserializer: D) -> Result<Vec<Range>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let ranges = Vec::<SerdeRange>::deserialize(deserializer)?;
+    Ok(ranges.into_iter().map(|range| range.0).collect())
+}
+
+/// Deserialize a file's constructs, restoring the `parent` links that serialization drops
+fn deserialize_constructs<'de, D>(deserializer: D) -> Result<Vec<CodeConstruct>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut constructs = Vec::<CodeConstruct>::deserialize(deserializer)?;
+    relink_constructs(&mut constructs);
+    Ok(constructs)
+}
+
+/// Restore `parent` and `depth` of a file's constructs from their `children`
+/// 
+/// A listed construct is top-level unless it also appears below another
+/// listed construct, as nested constructs of flattened files do. Deciding
+/// this from the hierarchy rather than `depth` keeps JSON written before
+/// `depth` was serialized working.
+fn relink_constructs(constructs: &mut [CodeConstruct]) {
+    type Key = (usize, usize, String);
+    fn key(construct: &CodeConstruct) -> Key {
+        (construct.start_byte, construct.end_byte, construct.node_type.clone())
+    }
+    fn collect_descendants(construct: &CodeConstruct, found: &mut HashMap<Key, (Option<Box<CodeConstruct>>, usize)>) {
+        for child in &construct.children {
+            found.insert(key(child), (child.parent.clone(), child.depth));
+            collect_descendants(child, found);
+        }
+    }
+    fn link_children(construct: &mut CodeConstruct) {
+        // Snapshots have no children, as during parsing
+        let mut children = std::mem::take(&mut construct.children);
+        let snapshot = Box::new(construct.clone());
+        for child in &mut children {
+            child.parent = Some(snapshot.clone());
+            child.depth = construct.depth + 1;
+            link_children(child);
+        }
+        construct.children = children;
+    }
+    
+    let mut nested = HashMap::new();
+    for construct in constructs.iter() {
+        collect_descendants(construct, &mut nested);
+    }
+    let is_root: Vec<bool> = constructs.iter().map(|construct| !nested.contains_key(&key(construct))).collect();
+    
+    // Link the top-level trees first, then copy the links onto their flattened descendants
+    let mut linked = HashMap::new();
+    for (construct, _) in constructs.iter_mut().zip(&is_root).filter(|(_, root)| **root) {
+        construct.parent = None;
+        construct.depth = 0;
+        link_children(construct);
+        collect_descendants(construct, &mut linked);
+    }
+    for (construct, _) in constructs.iter_mut().zip(&is_root).filter(|(_, root)| !**root) {
+        if let Some((parent, depth)) = linked.get(&key(construct)) {
+            construct.parent = parent.clone();
+            construct.depth = *depth;
+        }
+        link_children(construct);
+    }
 }
 
 /// Represents the results of parsing an entire project or directory
@@ -392,7 +979,36 @@ pub struct ParsedProject {
     pub files: Vec<ParsedFile>,
     pub total_files_processed: usize,
     pub language_distribution: HashMap<Language, usize>,
+    /// Files that failed to parse
     pub error_files: Vec<FileError>,
+    /// Files that were skipped or parsed with reduced fidelity, without failing
+    #[serde(default)]
+    pub warnings: Vec<FileWarning>,
+}
+
+impl ParsedProject {
+    /// Get the `n` files that took longest to parse, slowest first
+    /// 
+    /// Useful for spotting pathological (e.g. generated or minified) files.
+    pub fn slowest_files(&self, n: usize) -> Vec<&ParsedFile> {
+        let mut files: Vec<&ParsedFile> = self.files.iter().collect();
+        files.sort_by_key(|file| std::cmp::Reverse(file.parse_duration_ms));
+        files.truncate(n);
+        files
+    }
+    
+    /// Build a project around already parsed files, for tests
+    #[cfg(test)]
+    pub(crate) fn from_files(files: Vec<ParsedFile>) -> Self {
+        ParsedProject {
+            root_path: ".".to_string(),
+            total_files_processed: files.len(),
+            files,
+            language_distribution: HashMap::new(),
+            error_files: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
 }
 
 /// Filter criteria for selecting which files to parse
@@ -438,9 +1054,29 @@ pub struct FileFilter {
     /// Maximum file size in bytes. Files larger than this are excluded.
     pub max_size_bytes: Option<usize>,
     /// Custom predicate function for advanced filtering logic
+    #[allow(clippy::type_complexity)]
     pub custom_predicate: Option<Arc<dyn Fn(&Path) -> bool + Send + Sync>>,
 }
 
+/// Options controlling how search functions match construct names
+/// 
+/// Used by the `*_with_options` search variants. The default options match
+/// names exactly as the plain search functions do.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::SearchOptions;
+/// 
+/// // Match `get_user`, `GetUser` and `GETUSER` alike with "^get_?user$"
+/// let options = SearchOptions { case_insensitive: true };
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// Whether name patterns ignore case, without needing the inline `(?i)` flag
+    pub case_insensitive: bool,
+}
+
 /// Configuration options for parsing operations
 /// 
 /// This struct provides extensive configuration options for controlling
@@ -465,11 +1101,29 @@ pub struct FileFilter {
 ///     language_detection: LanguageDetection::Combined,
 ///     enable_caching: true,
 ///     thread_pool_size: Some(4),
+///     node_type_allowlist: None,
+///     max_depth: Some(512),
+///     per_file_timeout_ms: Some(5_000),
+///     flatten_constructs: true,
+///     follow_symlinks: false,
+///     normalize_line_endings: false,
+///     report_syntax_errors: false,
+///     skip_minified: true,
+///     dedupe_identical: true,
+///     synthesize_anonymous_names: false,
+///     batch_size: Some(10_000),
+///     strict: false,
+///     skip_non_containers: false,
+///     only_languages: None,
+///     retain_source: false,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParseOptions {
     /// Maximum number of files to parse concurrently (default: 2 * CPU cores)
+    /// 
+    /// This also bounds how many files are open at once, since every read
+    /// of a file's content happens while its parse slot is held.
     pub max_concurrent_files: usize,
     /// Whether to include hidden files (files starting with '.') in parsing
     pub include_hidden_files: bool,
@@ -483,14 +1137,69 @@ pub struct ParseOptions {
     pub language_detection: LanguageDetection,
     /// Whether to enable internal caching for improved performance
     pub enable_caching: bool,
-    /// Optional thread pool size (None uses system default)
+    /// Number of threads to parse directories on (None uses the caller's runtime).
+    /// When set, files are parsed on a dedicated runtime of this many threads.
     pub thread_pool_size: Option<usize>,
+    /// Node types to extract as constructs (None extracts every supported type).
+    /// Traversal still descends through other nodes to find nested allowed ones.
+    pub node_type_allowlist: Option<Vec<String>>,
+    /// Maximum syntax tree depth to descend during extraction (None means unlimited).
+    /// Deeper nodes are skipped and the file is flagged with a warning.
+    pub max_depth: Option<usize>,
+    /// Maximum time tree-sitter may spend parsing a single file (None means unlimited).
+    /// Files over budget fail with `Error::Timeout`; ignored on wasm32 targets.
+    pub per_file_timeout_ms: Option<u64>,
+    /// Whether `ParsedFile::constructs` lists every construct (default) or only top-level
+    /// ones, with nested constructs reachable solely through `children`.
+    pub flatten_constructs: bool,
+    /// Whether directory traversal follows symbolic links (symlink loops are skipped).
+    /// When unset, symlinked files are skipped as well as symlinked directories.
+    pub follow_symlinks: bool,
+    /// Whether to convert CRLF line endings to LF before parsing. Construct offsets
+    /// and columns then refer to the normalized text. A UTF-8 BOM is always stripped.
+    pub normalize_line_endings: bool,
+    /// Whether to record the locations of syntax errors in `ParsedFile::syntax_errors`.
+    /// tree-sitter recovers from errors, so malformed files still parse either way.
+    pub report_syntax_errors: bool,
+    /// Whether directory traversal skips minified or bundled files: `*.min.*` names, or
+    /// files whose opening bytes have a very long average line length
+    pub skip_minified: bool,
+    /// Whether byte-identical files are parsed once, with the result reused for every
+    /// path sharing that content. Every path is still listed in `ParsedProject::files`.
+    pub dedupe_identical: bool,
+    /// Whether constructs without a name get a `synthetic_name` of the form
+    /// `<anonymous@line:column>` (from `start_line` and `start_column`)
+    pub synthesize_anonymous_names: bool,
+    /// Number of files to collect before parsing them, alternating collection and
+    /// parsing in directory functions (None collects every file up front). Bounds
+    /// the memory used by the file list in very large directories.
+    pub batch_size: Option<usize>,
+    /// Whether a file containing syntax errors fails with `Error::Parse` (naming the
+    /// first error's location) instead of being returned with recovered constructs
+    pub strict: bool,
+    /// Whether extraction only descends into the language's container node kinds (see
+    /// `get_container_node_types`), skipping e.g. expression subtrees. Faster on large
+    /// files, but misses constructs buried in other expressions, such as a closure
+    /// stored in a struct literal.
+    pub skip_non_containers: bool,
+    /// Languages to parse in directory functions (None parses every supported language).
+    /// Other files are dropped by extension during traversal and never opened.
+    pub only_languages: Option<Vec<Language>>,
+    /// Whether files parsed from disk keep their text in `ParsedFile::source`. This
+    /// roughly doubles the memory held per file, so by default the text is dropped
+    /// after parsing and read from disk again by the functions that need it.
+    pub retain_source: bool,
 }
 
 impl Default for ParseOptions {
     fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let max_concurrent_files = num_cpus::get() * 2;
+        #[cfg(target_arch = "wasm32")]
+        let max_concurrent_files = 1;
+        
         Self {
-            max_concurrent_files: num_cpus::get() * 2,
+            max_concurrent_files,
             include_hidden_files: false,
             max_file_size_mb: 10,
             recursive: true,
@@ -503,16 +1212,41 @@ impl Default for ParseOptions {
             language_detection: LanguageDetection::ByExtension,
             enable_caching: true,
             thread_pool_size: None, // Uses system default
+            node_type_allowlist: None,
+            max_depth: None,
+            per_file_timeout_ms: None,
+            flatten_constructs: true,
+            follow_symlinks: false,
+            normalize_line_endings: false,
+            report_syntax_errors: false,
+            skip_minified: false,
+            dedupe_identical: false,
+            synthesize_anonymous_names: false,
+            batch_size: None,
+            strict: false,
+            skip_non_containers: false,
+            only_languages: None,
+            retain_source: false,
         }
     }
 }
 
 // Core API functions will be implemented in separate modules
+mod call_graph;
+mod export;
+mod extract;
+mod impl_index;
 mod parser;
+pub mod queries;
 mod search;
 mod utils;
 
+pub use call_graph::*;
+pub use export::*;
+pub use extract::*;
+pub use impl_index::*;
 pub use parser::*;
+pub use queries::{presets_for, run_preset, Preset};
 pub use search::*;
 pub use utils::*;
 // pub use test_compile::*; // Commented out as not currently used
@@ -521,10 +1255,171 @@ pub use utils::*;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_type_from_error() {
+        let cases = [
+            (Error::Io("missing".to_string()), ErrorType::IoError),
+            (Error::Parse("bad".to_string()), ErrorType::ParseError),
+            (Error::UnsupportedLanguage("Cobol".to_string()), ErrorType::UnsupportedLanguage),
+            (Error::FileTooLarge(1 << 30), ErrorType::FileTooLarge),
+            (Error::PermissionDenied("secret.py".to_string()), ErrorType::PermissionDenied),
+            (Error::InvalidQuery("(".to_string()), ErrorType::ParseError),
+            (Error::SyntaxTreeUnavailable, ErrorType::SyntaxTreeUnavailable),
+            (Error::Timeout(100), ErrorType::Timeout),
+            (Error::BinaryFile("data.c".to_string()), ErrorType::BinaryFile),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(ErrorType::from(&error), expected, "{:?}", error);
+            assert_eq!(error.error_type(), expected);
+        }
+    }
+
     #[test]
     fn test_language_detection() {
         assert_eq!(detect_language_by_extension("test.py"), Some(Language::Python));
         assert_eq!(detect_language_by_extension("test.rs"), Some(Language::Rust));
         assert_eq!(detect_language_by_extension("test.js"), Some(Language::JavaScript));
     }
+
+    #[test]
+    fn test_slowest_files() {
+        let file = |path: &str, parse_duration_ms: u64| ParsedFile {
+            file_path: path.to_string(),
+            relative_path: path.to_string(),
+            language: Language::Python,
+            constructs: Vec::new(),
+            syntax_tree: None,
+            source: None,
+            file_size_bytes: 0,
+            depth_limit_reached: false,
+            parse_duration_ms,
+            content_hash: 0,
+            line_endings_normalized: false,
+            syntax_errors: Vec::new(),
+        };
+        let project = ParsedProject::from_files(vec![file("a.py", 5), file("b.py", 40), file("c.py", 12)]);
+        
+        let slowest: Vec<_> = project.slowest_files(2).iter().map(|f| f.file_path.as_str()).collect();
+        assert_eq!(slowest, vec!["b.py", "c.py"]);
+        assert_eq!(project.slowest_files(10).len(), 3);
+    }
+
+    #[test]
+    fn test_structural_eq_ignores_position() {
+        let source = "def area(w, h):\n    return w * h\n\nclass Shape:\n    pass\n\n\ndef area(w, h):\n    return w  *  h\n\ndef area(w, h):\n    return w + h\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        let functions: Vec<_> = parsed.constructs.iter().filter(|c| c.node_type == "function_definition").collect();
+        assert_eq!(functions.len(), 3);
+        
+        assert_ne!(functions[0].start_line, functions[1].start_line);
+        assert!(functions[0].structural_eq(functions[1]));
+        assert_eq!(functions[0].structural_hash(), functions[1].structural_hash());
+        assert!(!functions[0].structural_eq(functions[2]));
+        assert!(!functions[0].structural_eq(&parsed.constructs[1]));
+    }
+
+    #[test]
+    fn test_serialized_construct_omits_parent() {
+        let mut source = String::new();
+        for level in 0..20 {
+            source.push_str(&format!("{}def level_{}():\n", "    ".repeat(level), level));
+        }
+        source.push_str(&format!("{}pass\n", "    ".repeat(20)));
+        let parsed = parse_string_sync(&source, Language::Python).unwrap();
+        
+        let innermost = parsed.constructs.last().unwrap();
+        assert!(innermost.parent.is_some());
+        let json = serde_json::to_string(innermost).unwrap();
+        assert!(!json.contains("\"parent\""));
+        // Only the construct itself is serialized, not its ancestors' source
+        assert!(json.len() < innermost.source_code.len() + 1024, "serialized {} bytes", json.len());
+        
+        let restored: CodeConstruct = serde_json::from_str(&json).unwrap();
+        assert!(restored.parent.is_none());
+        assert_eq!(restored.name, innermost.name);
+    }
+
+    #[test]
+    fn test_deserialized_file_keeps_hierarchy() {
+        let source = "class A:\n    def m(self):\n        def inner():\n            pass\n\ndef f():\n    pass\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        let restored: ParsedFile = serde_json::from_str(&json).unwrap();
+        
+        let names = |file: &ParsedFile| {
+            search_by_node_type(file, "function_definition", None).into_iter().map(|c| c.name).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&restored), names(&parsed));
+        assert_eq!(search_functions(&restored, None).len(), 3);
+        assert_eq!(restored.all_constructs().len(), parsed.all_constructs().len());
+        
+        let inner = restored.constructs.iter().find(|c| c.name.as_deref() == Some("inner")).unwrap();
+        assert_eq!(inner.depth, 2);
+        let parent = inner.parent.as_deref().unwrap();
+        assert_eq!(parent.name.as_deref(), Some("m"));
+        assert_eq!(parent.parent.as_deref().and_then(|p| p.name.as_deref()), Some("A"));
+        
+        // JSON written before `depth` was serialized
+        let mut legacy: serde_json::Value = serde_json::to_value(&parsed).unwrap();
+        fn strip_depth(value: &mut serde_json::Value) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    map.remove("depth");
+                    map.values_mut().for_each(strip_depth);
+                }
+                serde_json::Value::Array(items) => items.iter_mut().for_each(strip_depth),
+                _ => {}
+            }
+        }
+        strip_depth(&mut legacy);
+        let legacy: ParsedFile = serde_json::from_value(legacy).unwrap();
+        assert_eq!(names(&legacy), names(&parsed));
+    }
+    
+    #[test]
+    fn test_deserialize_without_columns_or_type_metadata() {
+        let parsed = parse_string_sync("class Box:\n    size: int = 0\n", Language::Python).unwrap();
+        let mut construct = serde_json::to_value(&parsed.constructs[0]).unwrap();
+        let map = construct.as_object_mut().unwrap();
+        map.remove("start_column");
+        map.remove("end_column");
+        let metadata = map["metadata"].as_object_mut().unwrap();
+        metadata.remove("field_type");
+        metadata.remove("type_parameters");
+        
+        let restored: CodeConstruct = serde_json::from_value(construct).unwrap();
+        assert_eq!(restored.name.as_deref(), Some("Box"));
+        assert_eq!((restored.start_column, restored.end_column), (0, 0));
+        assert!(restored.metadata.field_type.is_none());
+        assert!(restored.metadata.type_parameters.is_empty());
+        
+        let literal: StringLiteral = serde_json::from_value(serde_json::json!({
+            "text": "'hi'",
+            "start_line": 1,
+            "end_line": 1,
+            "start_byte": 0,
+            "end_byte": 4,
+            "is_template": false,
+        }))
+        .unwrap();
+        assert_eq!((literal.start_column, literal.end_column), (0, 0));
+    }
+    
+    #[test]
+    fn test_deserialize_baseline_parsed_file() {
+        // The fields ParsedFile had before depth limits, timings and hashes were recorded
+        let restored: ParsedFile = serde_json::from_value(serde_json::json!({
+            "file_path": "src/main.rs",
+            "relative_path": "main.rs",
+            "language": "Rust",
+            "constructs": [],
+            "file_size_bytes": 12,
+        }))
+        .unwrap();
+        assert_eq!(restored.file_path, "src/main.rs");
+        assert!(!restored.depth_limit_reached);
+        assert_eq!((restored.parse_duration_ms, restored.content_hash), (0, 0));
+        assert!(!restored.line_endings_normalized);
+        assert!(restored.syntax_errors.is_empty());
+    }
 }
@@ -1,14 +1,21 @@
 //! Core parsing functionality
 
 use crate::{
-    languages::*, CodeConstruct, ConstructMetadata, Error, ErrorType, FileError, Language,
-    LanguageDetection, ParseOptions, ParsedFile, ParsedProject,
+    languages::*, Annotation, CodeConstruct, ConstructMetadata, Error, Language, Parameter, ParseOptions,
+    ParsedFile,
 };
-use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{ErrorType, FileError, FileWarning, LanguageDetection, ParsedProject, WarningReason};
+use std::borrow::Cow;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::{Path, PathBuf};
 
-use tokio::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tree_sitter::{Node, Parser, Tree};
+#[cfg(not(target_arch = "wasm32"))]
 use walkdir::WalkDir;
 
 /// Parse a single source code file and extract code constructs
@@ -28,7 +35,7 @@ use walkdir::WalkDir;
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use tree_parser::{parse_file, Language};
 /// 
 /// #[tokio::main]
@@ -45,17 +52,449 @@ use walkdir::WalkDir;
 /// - The file cannot be read (I/O error)
 /// - The file content cannot be parsed (syntax error)
 /// - The specified language is not supported
+#[cfg(not(target_arch = "wasm32"))]
 pub async fn parse_file(file_path: &str, language: Language) -> Result<ParsedFile, Error> {
-    // Read file content
-    let content = fs::read_to_string(file_path)
+    parse_file_with_options(file_path, language, &ParseOptions::default()).await
+}
+
+/// Parse a single source code file using the given parsing options
+/// 
+/// This behaves like `parse_file` but honors the per-file settings in
+/// `ParseOptions`, such as `node_type_allowlist`. Directory parsing uses
+/// this function for every collected file.
+/// 
+/// # Arguments
+/// 
+/// * `file_path` - Path to the source code file to parse
+/// * `language` - The programming language of the file
+/// * `options` - Configuration options controlling extraction
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file_with_options, Language, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let options = ParseOptions {
+///         node_type_allowlist: Some(vec!["function_item".to_string()]),
+///         ..Default::default()
+///     };
+///     let result = parse_file_with_options("src/main.rs", Language::Rust, &options).await?;
+///     println!("Found {} functions", result.constructs.len());
+///     Ok(())
+/// }
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns the same errors as `parse_file`.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn parse_file_with_options(
+    file_path: &str,
+    language: Language,
+    options: &ParseOptions,
+) -> Result<ParsedFile, Error> {
+    let file_path = file_path.to_string();
+    let options = options.clone();
+    
+    // Reading and parsing are blocking and CPU-bound, so keep them off the async workers
+    tokio::task::spawn_blocking(move || read_and_parse_file(&file_path, language, &options))
+        .await
+        .map_err(|e| Error::Parse(e.to_string()))?
+}
+
+/// Parse a single source code file without an async runtime
+/// 
+/// This is the blocking counterpart of `parse_file`. It performs blocking I/O
+/// and parsing on the calling thread, so it can be used from synchronous code
+/// such as build scripts or simple CLIs without starting tokio.
+/// 
+/// # Arguments
+/// 
+/// * `file_path` - Path to the source code file to parse
+/// * `language` - The programming language of the file
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file_sync, Language};
+/// 
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let result = parse_file_sync("src/main.rs", Language::Rust)?;
+///     println!("Found {} constructs", result.constructs.len());
+///     Ok(())
+/// }
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns the same errors as `parse_file`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_file_sync(file_path: &str, language: Language) -> Result<ParsedFile, Error> {
+    read_and_parse_file(file_path, language, &ParseOptions::default())
+}
+
+/// Parse source code held in memory
+/// 
+/// The parsing itself runs on tokio's blocking thread pool. The resulting
+/// `ParsedFile` uses `"<string>"` as its path and retains the syntax tree and
+/// source text, so `search_by_query` works on it.
+/// 
+/// # Arguments
+/// 
+/// * `source` - The source code to parse
+/// * `language` - The programming language of the source
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_string("def hello():\n    pass\n", Language::Python).await?;
+///     assert_eq!(parsed.constructs.len(), 1);
+///     Ok(())
+/// }
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns an error if the language is not supported or parsing fails.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn parse_string(source: &str, language: Language) -> Result<ParsedFile, Error> {
+    let source = source.to_string();
+    
+    tokio::task::spawn_blocking(move || {
+        parse_source(
+            source,
+            STRING_PATH,
+            STRING_PATH.to_string(),
+            language,
+            true,
+            &ParseOptions::default(),
+        )
+    })
+    .await
+    .map_err(|e| Error::Parse(e.to_string()))?
+}
+
+/// Parse source code held in memory without an async runtime
+/// 
+/// This is the blocking counterpart of `parse_string` and does not require
+/// a tokio runtime.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string_sync, Language};
+/// 
+/// let parsed = parse_string_sync("fn main() {}", Language::Rust).unwrap();
+/// assert_eq!(parsed.constructs[0].name.as_deref(), Some("main"));
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns an error if the language is not supported or parsing fails.
+pub fn parse_string_sync(source: &str, language: Language) -> Result<ParsedFile, Error> {
+    parse_source(
+        source.to_string(),
+        STRING_PATH,
+        STRING_PATH.to_string(),
+        language,
+        true,
+        &ParseOptions::default(),
+    )
+}
+
+/// Parse source code held in memory as bytes in a known text encoding
+/// 
+/// The bytes are transcoded to UTF-8 with `encoding_rs` before parsing, so
+/// that sources such as UTF-16LE PowerShell scripts or XML can be parsed. A
+/// leading byte order mark is removed; if it names a different encoding than
+/// `encoding`, the BOM wins. All byte offsets in the result (`start_byte`,
+/// `end_byte`, ...) refer to the transcoded UTF-8 text, which is what
+/// `source` holds when the syntax tree is retained.
+/// 
+/// # Arguments
+/// 
+/// * `bytes` - The encoded source
+/// * `encoding` - The encoding of `bytes`, e.g. `UTF_16LE`
+/// * `language` - The language of the source
+/// * `include_syntax_tree` - Whether to retain the syntax tree and transcoded source
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_bytes, Language, UTF_16LE};
+/// 
+/// let bytes: Vec<u8> = "def main():\n    pass\n".encode_utf16().flat_map(u16::to_le_bytes).collect();
+/// let parsed = parse_bytes(&bytes, UTF_16LE, Language::Python, true).unwrap();
+/// assert_eq!(parsed.constructs[0].name.as_deref(), Some("main"));
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns `Error::Parse` if `bytes` is not valid in `encoding`, or an error
+/// if the language is not supported or parsing fails.
+pub fn parse_bytes(
+    bytes: &[u8],
+    encoding: &'static crate::Encoding,
+    language: Language,
+    include_syntax_tree: bool,
+) -> Result<ParsedFile, Error> {
+    let (content, had_errors) = encoding.decode_with_bom_removal(bytes);
+    if had_errors {
+        return Err(Error::Parse(format!("Source is not valid {}", encoding.name())));
+    }
+    
+    parse_source(
+        content.into_owned(),
+        STRING_PATH,
+        STRING_PATH.to_string(),
+        language,
+        include_syntax_tree,
+        &ParseOptions::default(),
+    )
+}
+
+/// Read a file with blocking I/O and parse it
+#[cfg(not(target_arch = "wasm32"))]
+fn read_and_parse_file(
+    file_path: &str,
+    language: Language,
+    options: &ParseOptions,
+) -> Result<ParsedFile, Error> {
+    let content = read_source_file(file_path)?;
+    parse_file_content(file_path, content, language, options)
+}
+
+/// Parse contents already read from a file
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_file_content(
+    file_path: &str,
+    content: String,
+    language: Language,
+    options: &ParseOptions,
+) -> Result<ParsedFile, Error> {
+    let path = Path::new(file_path);
+    let relative_path = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    
+    #[cfg(test)]
+    let _parsing = probe::parsing(file_path);
+    
+    let mut parsed = parse_source(content, file_path, relative_path, language, true, options)?;
+    // The file can be read again, so its text is only kept on request
+    if !options.retain_source {
+        parsed.source = None;
+    }
+    Ok(parsed)
+}
+
+/// Read a source file into a string, decompressing `.gz` files when the `gzip` feature is enabled
+/// 
+/// Files with a null byte near the start are rejected with `Error::BinaryFile`
+/// before any parsing happens, whatever their extension.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_source_file(file_path: &str) -> Result<String, Error> {
+    let bytes = read_source_bytes(file_path)?;
+    if looks_binary(&bytes) {
+        return Err(Error::BinaryFile(file_path.to_string()));
+    }
+    String::from_utf8(bytes).map_err(|e| Error::Io(e.to_string()))
+}
+
+/// Read a source file's raw bytes, decompressing `.gz` files when the `gzip` feature is enabled
+#[cfg(not(target_arch = "wasm32"))]
+fn read_source_bytes(file_path: &str) -> Result<Vec<u8>, Error> {
+    #[cfg(test)]
+    let _reading = probe::reading(file_path);
+    
+    #[cfg(feature = "gzip")]
+    if file_path.to_lowercase().ends_with(".gz") {
+        use std::io::Read;
+        
+        let file = std::fs::File::open(file_path).map_err(|e| Error::Io(e.to_string()))?;
+        let mut content = Vec::new();
+        flate2::read::GzDecoder::new(file)
+            .read_to_end(&mut content)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        return Ok(content);
+    }
+    
+    std::fs::read(file_path).map_err(|e| Error::Io(e.to_string()))
+}
+
+/// Number of leading bytes inspected when sniffing for binary content
+#[cfg(not(target_arch = "wasm32"))]
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Check whether content looks binary, using the same null-byte heuristic as git
+#[cfg(not(target_arch = "wasm32"))]
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+/// Parse source code read from an asynchronous reader
+/// 
+/// This function reads the reader to the end and then parses the collected
+/// text, which makes it suitable for stdin or pipes where no file path is
+/// available. The resulting `ParsedFile` uses `"<stdin>"` as its path.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Any `AsyncRead` source, such as `tokio::io::stdin()`
+/// * `language` - The programming language of the source
+/// * `include_syntax_tree` - Whether to retain the syntax tree and source text
+///   (required for `search_by_query`)
+/// 
+/// # Returns
+/// 
+/// Returns a `ParsedFile` containing all extracted constructs and metadata,
+/// or an `Error` if reading or parsing fails.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_reader, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_reader(tokio::io::stdin(), Language::Rust, true).await?;
+///     println!("Found {} constructs", parsed.constructs.len());
+///     Ok(())
+/// }
+/// ```
+/// 
+/// # Errors
+/// 
+/// This function will return an error if:
+/// - The reader fails or yields invalid UTF-8 (I/O error)
+/// - The content cannot be parsed
+/// - The specified language is not supported
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn parse_reader<R: AsyncRead + Unpin>(
+    mut reader: R,
+    language: Language,
+    include_syntax_tree: bool,
+) -> Result<ParsedFile, Error> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
         .await
         .map_err(|e| Error::Io(e.to_string()))?;
     
+    parse_source(
+        content,
+        STDIN_PATH,
+        STDIN_PATH.to_string(),
+        language,
+        include_syntax_tree,
+        &ParseOptions::default(),
+    )
+}
+
+/// Parse a file as it exists at a git revision, without checking it out
+/// 
+/// The blob is read straight from the repository's object database, so the
+/// working tree is left untouched. The resulting `ParsedFile` records the
+/// in-repo `file_path` and retains its syntax tree.
+/// 
+/// # Arguments
+/// 
+/// * `repo_path` - Path to the repository (its working directory or `.git` directory)
+/// * `rev` - Any revision git understands, such as `HEAD~1`, a branch or a commit hash
+/// * `file_path` - Path of the file relative to the repository root
+/// * `language` - The programming language of the file
+/// 
+/// # Errors
+/// 
+/// Returns `Error::Io` if the repository cannot be opened or the revision or
+/// path does not exist, and `Error::Parse` if the blob is not valid UTF-8.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_git_blob, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let previous = parse_git_blob(".", "HEAD~1", "src/lib.rs", Language::Rust).await?;
+///     println!("{} constructs one commit ago", previous.constructs.len());
+///     Ok(())
+/// }
+/// ```
+#[cfg(all(feature = "git", not(target_arch = "wasm32")))]
+pub async fn parse_git_blob(
+    repo_path: &str,
+    rev: &str,
+    file_path: &str,
+    language: Language,
+) -> Result<ParsedFile, Error> {
+    let repo_path = repo_path.to_string();
+    let rev = rev.to_string();
+    let file_path = file_path.to_string();
+    
+    tokio::task::spawn_blocking(move || {
+        let content = read_git_blob(&repo_path, &rev, &file_path)?;
+        let relative_path = Path::new(&file_path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        
+        parse_source(content, &file_path, relative_path, language, true, &ParseOptions::default())
+    })
+    .await
+    .map_err(|e| Error::Parse(e.to_string()))?
+}
+
+/// Read the content of `file_path` at `rev` from a git repository
+#[cfg(all(feature = "git", not(target_arch = "wasm32")))]
+fn read_git_blob(repo_path: &str, rev: &str, file_path: &str) -> Result<String, Error> {
+    let repository = git2::Repository::open(repo_path)
+        .map_err(|e| Error::Io(e.message().to_string()))?;
+    let blob = repository
+        .revparse_single(&format!("{}:{}", rev, file_path))
+        .and_then(|object| object.peel_to_blob())
+        .map_err(|e| Error::Io(e.message().to_string()))?;
+    
+    String::from_utf8(blob.content().to_vec())
+        .map_err(|e| Error::Parse(format!("{} at {} is not valid UTF-8: {}", file_path, rev, e)))
+}
+
+/// Path recorded for sources read from a reader
+#[cfg(not(target_arch = "wasm32"))]
+const STDIN_PATH: &str = "<stdin>";
+
+/// Path recorded for sources parsed from an in-memory string
+const STRING_PATH: &str = "<string>";
+
+/// Parse in-memory source text into a `ParsedFile`
+fn parse_source(
+    content: String,
+    file_path: &str,
+    relative_path: String,
+    language: Language,
+    include_syntax_tree: bool,
+    options: &ParseOptions,
+) -> Result<ParsedFile, Error> {
     let file_size_bytes = content.len();
+    let content_hash = xxhash_rust::xxh3::xxh3_64(content.as_bytes());
+    let line_endings_normalized = options.normalize_line_endings && content.contains("\r\n");
+    let content = normalize_source(content, options.normalize_line_endings);
     
     // Get tree-sitter language
     let ts_language = get_tree_sitter_language(&language)?;
     
+    #[cfg(not(target_arch = "wasm32"))]
+    let started = std::time::Instant::now();
+    
     // Create parser
     let mut parser = Parser::new();
     parser
@@ -63,31 +502,136 @@ pub async fn parse_file(file_path: &str, language: Language) -> Result<ParsedFil
         .map_err(|e| Error::Parse(e.to_string()))?;
     
     // Parse the content
-    let tree = parser
-        .parse(&content, None)
-        .ok_or_else(|| Error::Parse("Failed to parse file".to_string()))?;
+    let tree = parse_with_timeout(&mut parser, &content, options.per_file_timeout_ms)?;
+    
+    if options.strict
+        && let Some(error) = collect_syntax_errors(&tree).first() {
+        return Err(Error::Parse(format!(
+            "Syntax error in {} at line {}, column {}",
+            file_path,
+            error.start_point.row + 1,
+            error.start_point.column,
+        )));
+    }
     
     // Extract code constructs
-    let constructs = extract_constructs(&tree, &content, &language);
+    let mut context = ExtractionContext::new(&content, &language, options);
+    let constructs = extract_constructs_with_context(&tree, &mut context);
+    let depth_limit_reached = context.depth_limit_reached;
+    let syntax_errors = if options.report_syntax_errors {
+        collect_syntax_errors(&tree)
+    } else {
+        Vec::new()
+    };
     
-    let path = Path::new(file_path);
-    let relative_path = path
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
+    #[cfg(not(target_arch = "wasm32"))]
+    let parse_duration_ms = started.elapsed().as_millis() as u64;
+    #[cfg(target_arch = "wasm32")]
+    let parse_duration_ms = 0;
+    
+    let (syntax_tree, source) = if include_syntax_tree {
+        (Some(tree), Some(content))
+    } else {
+        (None, None)
+    };
     
     Ok(ParsedFile {
         file_path: file_path.to_string(),
         relative_path,
         language,
         constructs,
-        syntax_tree: Some(tree),
+        syntax_tree,
+        source,
         file_size_bytes,
+        depth_limit_reached,
+        parse_duration_ms,
+        content_hash,
+        line_endings_normalized,
+        syntax_errors,
+    })
+}
+
+/// Collect the ranges of `ERROR` and `MISSING` nodes, outermost errors only
+fn collect_syntax_errors(tree: &Tree) -> Vec<tree_sitter::Range> {
+    let mut errors = Vec::new();
+    if !tree.root_node().has_error() {
+        return errors;
+    }
+    
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if node.is_error() || node.is_missing() {
+            errors.push(node.range());
+        } else if node.has_error() && cursor.goto_first_child() {
+            continue;
+        }
+        
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+    
+    errors
+}
+
+/// Strip a leading UTF-8 BOM and optionally convert CRLF line endings to LF
+/// 
+/// Applied before parsing so that construct offsets, names and the retained
+/// source all agree on the same text.
+pub(crate) fn normalize_source(mut content: String, normalize_line_endings: bool) -> String {
+    if content.starts_with('\u{FEFF}') {
+        content.drain(..'\u{FEFF}'.len_utf8());
+    }
+    if normalize_line_endings && content.contains("\r\n") {
+        content = content.replace("\r\n", "\n");
+    }
+    content
+}
 
+/// Run the parser, halting with `Error::Timeout` once `timeout_ms` has elapsed
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_with_timeout(parser: &mut Parser, content: &str, timeout_ms: Option<u64>) -> Result<Tree, Error> {
+    let Some(timeout_ms) = timeout_ms else {
+        return parser
+            .parse(content, None)
+            .ok_or_else(|| Error::Parse("Failed to parse file".to_string()));
+    };
+    
+    let started = std::time::Instant::now();
+    let budget = std::time::Duration::from_millis(timeout_ms);
+    let mut timed_out = false;
+    let mut over_budget = |_: &tree_sitter::ParseState| {
+        timed_out = started.elapsed() >= budget;
+        timed_out
+    };
+    let bytes = content.as_bytes();
+    
+    let tree = parser.parse_with_options(
+        &mut |offset, _| bytes.get(offset..).unwrap_or_default(),
+        None,
+        Some(tree_sitter::ParseOptions::new().progress_callback(&mut over_budget)),
+    );
+    // Parsing also fails without halting, e.g. when no language is set
+    tree.ok_or_else(|| {
+        if timed_out {
+            Error::Timeout(timeout_ms)
+        } else {
+            Error::Parse("Failed to parse file".to_string())
+        }
     })
 }
 
+/// Run the parser; timeouts are unsupported without a monotonic clock
+#[cfg(target_arch = "wasm32")]
+fn parse_with_timeout(parser: &mut Parser, content: &str, _timeout_ms: Option<u64>) -> Result<Tree, Error> {
+    parser
+        .parse(content, None)
+        .ok_or_else(|| Error::Parse("Failed to parse file".to_string()))
+}
+
 /// Parse an entire project directory recursively
 /// 
 /// This function traverses a directory structure, identifies source code files,
@@ -101,7 +645,8 @@ pub async fn parse_file(file_path: &str, language: Language) -> Result<ParsedFil
 /// # Returns
 /// 
 /// Returns a `ParsedProject` containing results from all parsed files,
-/// including statistics and error information.
+/// including statistics and error information. Files, errors and warnings
+/// are sorted by file path, so repeated runs produce the same order.
 /// 
 /// # Examples
 /// 
@@ -125,6 +670,7 @@ pub async fn parse_file(file_path: &str, language: Language) -> Result<ParsedFil
 /// 
 /// This function uses concurrent processing to parse multiple files simultaneously.
 /// The concurrency level is controlled by `options.max_concurrent_files`.
+#[cfg(not(target_arch = "wasm32"))]
 pub async fn parse_directory(
     dir_path: &str,
     options: ParseOptions,
@@ -135,11 +681,23 @@ pub async fn parse_directory(
         return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
     }
     
-    // Collect files to parse
-    let files_to_parse = collect_files(&root_path, &options)?;
-    
-    // Parse files in parallel
-    let (parsed_files, error_files) = parse_files_parallel(files_to_parse, &options).await;
+    let mut warnings = Vec::new();
+    let (mut parsed_files, error_files) = match options.batch_size {
+        Some(batch_size) => {
+            let files = walk_files(&root_path, &options, Some(&mut warnings));
+            parse_in_batches(files, batch_size, &options).await?
+        }
+        None => {
+            // Collect files to parse
+            let files_to_parse = walk_files(&root_path, &options, Some(&mut warnings)).collect::<Result<Vec<_>, _>>()?;
+            
+            // Parse files in parallel
+            parse_files_parallel(files_to_parse, &options).await
+        }
+    };
+    let mut error_files = separate_warnings(&parsed_files, error_files, &mut warnings, &options);
+    relativize_paths(&mut parsed_files, &root_path);
+    sort_by_file_path(&mut parsed_files, &mut error_files, &mut warnings);
     
     // Calculate statistics
     let total_files_processed = parsed_files.len();
@@ -154,6 +712,7 @@ pub async fn parse_directory(
         total_files_processed,
         language_distribution,
         error_files,
+        warnings,
     })
 }
 
@@ -172,7 +731,7 @@ pub async fn parse_directory(
 /// # Returns
 /// 
 /// Returns a `ParsedProject` containing results from all files that match
-/// the filter criteria.
+/// the filter criteria, sorted by file path.
 /// 
 /// # Examples
 /// 
@@ -199,10 +758,11 @@ pub async fn parse_directory(
 ///     Ok(())
 /// }
 /// ```
+#[cfg(not(target_arch = "wasm32"))]
 pub async fn parse_directory_with_filter(
     dir_path: &str,
     file_filter: &crate::FileFilter,
-    options: ParseOptions,
+    mut options: ParseOptions,
 ) -> Result<ParsedProject, Error> {
     let root_path = PathBuf::from(dir_path);
     
@@ -210,11 +770,28 @@ pub async fn parse_directory_with_filter(
         return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
     }
     
-    // Collect files to parse with custom filter
-    let files_to_parse = collect_files_with_filter(&root_path, &options, file_filter)?;
+    // Drop files of other languages during traversal, before they are read
+    if let Some(ref languages) = file_filter.languages {
+        let only_languages = options.only_languages.get_or_insert_with(|| languages.clone());
+        only_languages.retain(|language| languages.contains(language));
+    }
     
-    // Parse files in parallel
-    let (parsed_files, error_files) = parse_files_parallel(files_to_parse, &options).await;
+    let mut warnings = Vec::new();
+    let files = walk_files(&root_path, &options, Some(&mut warnings))
+        .filter(|file| file.as_ref().map_or(true, |path| matches_filter(path, file_filter)));
+    let (mut parsed_files, error_files) = match options.batch_size {
+        Some(batch_size) => parse_in_batches(files, batch_size, &options).await?,
+        None => {
+            // Collect files to parse with custom filter
+            let files_to_parse = files.collect::<Result<Vec<_>, _>>()?;
+            
+            // Parse files in parallel
+            parse_files_parallel(files_to_parse, &options).await
+        }
+    };
+    let mut error_files = separate_warnings(&parsed_files, error_files, &mut warnings, &options);
+    relativize_paths(&mut parsed_files, &root_path);
+    sort_by_file_path(&mut parsed_files, &mut error_files, &mut warnings);
     
     // Calculate statistics
     let total_files_processed = parsed_files.len();
@@ -227,69 +804,467 @@ pub async fn parse_directory_with_filter(
         root_path: dir_path.to_string(),
         files: parsed_files,
         total_files_processed,
-
         language_distribution,
         error_files,
+        warnings,
     })
 }
 
-/// Collect files to parse from directory based on parsing options
+/// Re-parse a project directory, reusing results for files that have not changed
 /// 
-/// This internal function traverses a directory structure and collects all files
-/// that should be parsed according to the provided options.
+/// Files are selected exactly as `parse_directory` selects them. A file whose
+/// content hash matches its entry in `previous` (looked up by `file_path`) is
+/// taken from `previous` without being parsed again; new and modified files
+/// are parsed, and files that no longer exist are dropped. Every file is
+/// read once to compute its hash; changed files are parsed from those same
+/// contents, and tree-sitter is not run on unchanged ones. Since each file
+/// is checked on its own, `dedupe_identical` does not apply here.
+/// 
+/// `previous` should come from a run with the same `options`, since reused
+/// entries are not re-extracted under new settings.
 /// 
 /// # Arguments
 /// 
-/// * `root_path` - Root directory path to start collection from
-/// * `options` - Parsing options that control file selection
+/// * `dir_path` - Path to the root directory to parse
+/// * `options` - Configuration options controlling parsing behavior
+/// * `previous` - The result of an earlier run over the same directory
 /// 
 /// # Returns
 /// 
-/// A vector of file paths that should be parsed, or an error if directory
-/// traversal fails.
-fn collect_files(root_path: &Path, options: &ParseOptions) -> Result<Vec<PathBuf>, Error> {
-    let mut files = Vec::new();
+/// A complete `ParsedProject` covering every selected file, sorted by path
+/// like `parse_directory`.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_directory, parse_directory_incremental, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     // ... files change on disk ...
+///     project = parse_directory_incremental("./src", ParseOptions::default(), &project).await?;
+///     println!("Parsed {} files", project.total_files_processed);
+///     Ok(())
+/// }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn parse_directory_incremental(
+    dir_path: &str,
+    options: ParseOptions,
+    previous: &ParsedProject,
+) -> Result<ParsedProject, Error> {
+    let root_path = PathBuf::from(dir_path);
     
+    if !root_path.exists() {
+        return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
+    }
+    
+    let mut warnings = Vec::new();
+    let files = walk_files(&root_path, &options, Some(&mut warnings)).collect::<Result<Vec<_>, _>>()?;
+    
+    let previous_files: HashMap<&str, &ParsedFile> = previous
+        .files
+        .iter()
+        .map(|file| (file.file_path.as_str(), file))
+        .collect();
+    let previous_hashes = previous_files
+        .iter()
+        .map(|(file_path, file)| (file_path.to_string(), file.content_hash))
+        .collect();
+    let outcomes = reparse_changed_files(files.clone(), previous_hashes, &options).await;
+    
+    // Reassemble the selected files from reused and re-parsed entries
+    let results = files.iter().zip(outcomes).filter_map(|(path, outcome)| match outcome {
+        Some(result) => Some(result),
+        None => previous_files.get(path.to_string_lossy().as_ref()).map(|file| Ok((*file).clone())),
+    });
+    let (mut parsed_files, error_files) = collect_parse_results(results);
+    let mut error_files = separate_warnings(&parsed_files, error_files, &mut warnings, &options);
+    relativize_paths(&mut parsed_files, &root_path);
+    sort_by_file_path(&mut parsed_files, &mut error_files, &mut warnings);
+    
+    // Calculate statistics
+    let total_files_processed = parsed_files.len();
+    let mut language_distribution = HashMap::new();
+    for file in &parsed_files {
+        *language_distribution.entry(file.language.clone()).or_insert(0) += 1;
+    }
+    
+    Ok(ParsedProject {
+        root_path: dir_path.to_string(),
+        files: parsed_files,
+        total_files_processed,
+        language_distribution,
+        error_files,
+        warnings,
+    })
+}
+
+/// Count constructs by node type across a directory without building constructs
+/// 
+/// Files are selected and parsed like `parse_directory`, but each syntax tree
+/// is only walked to tally the requested node types and then dropped, so no
+/// `CodeConstruct`s or trees are kept in memory. A node type is only counted
+/// in languages that extract it as a construct (narrowed by
+/// `node_type_allowlist`), and the tree is descended the way extraction
+/// descends it, honouring `max_depth` and `skip_non_containers`, so counts
+/// match searching a full parse. Files are walked in batches of
+/// `batch_size` and counted on a dedicated runtime with `thread_pool_size`,
+/// as in `parse_directory`.
+/// 
+/// # Arguments
+/// 
+/// * `dir_path` - Path to the root directory to scan
+/// * `options` - Configuration options controlling file selection and parsing
+/// * `node_types` - The node types to count
+/// 
+/// # Returns
+/// 
+/// A map from node type to the number of matching nodes, and the files that
+/// failed to read or parse and so were not counted. Requested node types
+/// that never occur are absent from the map.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{count_constructs, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let (counts, error_files) = count_constructs("./src", ParseOptions::default(), &["function_item", "struct_item"]).await?;
+///     println!("{} functions", counts.get("function_item").unwrap_or(&0));
+///     for error in error_files {
+///         eprintln!("Not counted: {}: {}", error.file_path, error.message);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn count_constructs(
+    dir_path: &str,
+    options: ParseOptions,
+    node_types: &[&str],
+) -> Result<(HashMap<String, usize>, Vec<FileError>), Error> {
+    let root_path = PathBuf::from(dir_path);
+    
+    if !root_path.exists() {
+        return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
+    }
+    
+    let node_types: Vec<String> = node_types.iter().map(|node_type| node_type.to_string()).collect();
+    let mut files = walk_files(&root_path, &options, None);
+    // Without `batch_size`, every file is counted in a single batch
+    let batch_size = options.batch_size.unwrap_or(usize::MAX).max(1);
+    let pool = options.thread_pool_size.and_then(ThreadPool::new);
+    
+    let mut counts = HashMap::new();
+    let mut error_files = Vec::new();
+    loop {
+        let batch = files.by_ref().take(batch_size).collect::<Result<Vec<_>, _>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        let tallies = count_files_on(pool.as_ref(), batch, &options, &node_types).await;
+        for tally in tallies {
+            match tally {
+                Ok(file_counts) => {
+                    for (node_type, count) in file_counts {
+                        *counts.entry(node_type).or_insert(0) += count;
+                    }
+                }
+                Err(error) => error_files.push(error),
+            }
+        }
+    }
+    error_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    Ok((counts, error_files))
+}
+
+/// Count constructs in files in parallel on `pool`, or on the current runtime without one
+#[cfg(not(target_arch = "wasm32"))]
+async fn count_files_on(
+    pool: Option<&ThreadPool>,
+    files: Vec<PathBuf>,
+    options: &ParseOptions,
+    node_types: &[String],
+) -> Vec<Result<HashMap<String, usize>, FileError>> {
+    match pool {
+        Some(pool) => {
+            let (options, node_types) = (options.clone(), node_types.to_vec());
+            pool.run(async move { count_files_concurrently(files, &options, &node_types).await }).await
+        }
+        None => count_files_concurrently(files, options, node_types).await,
+    }
+}
+
+/// Count constructs in files concurrently on the current runtime, in input order
+/// 
+/// Files whose language cannot be detected have nothing to count.
+#[cfg(not(target_arch = "wasm32"))]
+async fn count_files_concurrently(
+    files: Vec<PathBuf>,
+    options: &ParseOptions,
+    node_types: &[String],
+) -> Vec<Result<HashMap<String, usize>, FileError>> {
+    join_bounded(files, options.max_concurrent_files, |path| async move {
+        let path_str = path.to_string_lossy().to_string();
+        let Some(language) = detect_file_language(&path, options).await else {
+            return Ok(HashMap::new());
+        };
+        let (options, node_types) = (options.clone(), node_types.to_vec());
+        tokio::task::spawn_blocking(move || count_file_constructs(&path, &language, &options, &node_types))
+            .await
+            .map_err(|e| Error::Parse(e.to_string()))
+            .and_then(|counts| counts)
+            .map_err(|e| FileError {
+                file_path: path_str,
+                error_type: ErrorType::from(&e),
+                message: e.to_string(),
+            })
+    })
+    .await
+}
+
+/// Parse one file and tally the requested node types it extracts as constructs
+#[cfg(not(target_arch = "wasm32"))]
+fn count_file_constructs(
+    path: &Path,
+    language: &Language,
+    options: &ParseOptions,
+    node_types: &[String],
+) -> Result<HashMap<String, usize>, Error> {
+    let extracted = extracted_node_types(language, options);
+    let counted: Vec<&str> = node_types
+        .iter()
+        .filter(|node_type| extracted.contains(node_type))
+        .map(String::as_str)
+        .collect();
+    if counted.is_empty() {
+        return Ok(HashMap::new());
+    }
+    
+    let file_path = path.to_string_lossy();
+    let content = normalize_source(read_source_file(&file_path)?, options.normalize_line_endings);
+    #[cfg(test)]
+    let _parsing = probe::parsing(&file_path);
+    let mut parser = Parser::new();
+    parser
+        .set_language(&get_tree_sitter_language(language)?)
+        .map_err(|e| Error::Parse(e.to_string()))?;
+    let tree = parse_with_timeout(&mut parser, &content, options.per_file_timeout_ms)?;
+    if options.strict
+        && let Some(error) = collect_syntax_errors(&tree).first() {
+        return Err(Error::Parse(format!(
+            "Syntax error in {} at line {}, column {}",
+            file_path,
+            error.start_point.row + 1,
+            error.start_point.column,
+        )));
+    }
+    
+    let mut counts = HashMap::new();
+    let mut context = ExtractionContext::new(&content, language, options);
+    walk_construct_nodes(tree.root_node(), &mut context, |_, event| {
+        if let ConstructEvent::Enter(node) = event
+            && counted.contains(&node.kind()) {
+            *counts.entry(node.kind().to_string()).or_insert(0) += 1;
+        }
+    });
+    Ok(counts)
+}
+
+/// List the files `parse_directory` would parse, without parsing them
+/// 
+/// This is a dry run of file selection: it applies `recursive`,
+/// `include_hidden_files`, `ignore_patterns`, `max_file_size_mb` and the
+/// other selection options exactly as `parse_directory` does, which makes it
+/// useful for checking why a file is or isn't picked up.
+/// 
+/// # Arguments
+/// 
+/// * `dir_path` - Path to the root directory to scan
+/// * `options` - Configuration options controlling file selection
+/// 
+/// # Returns
+/// 
+/// The selected file paths, or an error if the directory does not exist or
+/// cannot be traversed.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{list_parseable_files, ParseOptions};
+/// 
+/// let files = list_parseable_files("./src", &ParseOptions::default())?;
+/// for path in &files {
+///     println!("{}", path.display());
+/// }
+/// # Ok::<(), tree_parser::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_parseable_files(dir_path: &str, options: &ParseOptions) -> Result<Vec<PathBuf>, Error> {
+    let root_path = PathBuf::from(dir_path);
+    
+    if !root_path.exists() {
+        return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
+    }
+    
+    collect_files(&root_path, options)
+}
+
+/// List the files `parse_directory_with_filter` would parse, without parsing them
+/// 
+/// Like `list_parseable_files`, with the custom filter applied on top of
+/// the parsing options.
+/// 
+/// # Arguments
+/// 
+/// * `dir_path` - Path to the root directory to scan
+/// * `file_filter` - Custom filter criteria for file selection
+/// * `options` - Configuration options controlling file selection
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_parseable_files_with_filter(
+    dir_path: &str,
+    file_filter: &crate::FileFilter,
+    options: &ParseOptions,
+) -> Result<Vec<PathBuf>, Error> {
+    let root_path = PathBuf::from(dir_path);
+    
+    if !root_path.exists() {
+        return Err(Error::Io(format!("Directory does not exist: {}", dir_path)));
+    }
+    
+    collect_files_with_filter(&root_path, options, file_filter)
+}
+
+/// Collect files to parse from directory based on parsing options
+/// 
+/// This internal function traverses a directory structure and collects all files
+/// that should be parsed according to the provided options.
+/// 
+/// # Arguments
+/// 
+/// * `root_path` - Root directory path to start collection from
+/// * `options` - Parsing options that control file selection
+/// 
+/// # Returns
+/// 
+/// A vector of file paths that should be parsed, or an error if directory
+/// traversal fails.
+#[cfg(not(target_arch = "wasm32"))]
+fn collect_files(root_path: &Path, options: &ParseOptions) -> Result<Vec<PathBuf>, Error> {
+    walk_files(root_path, options, None).collect()
+}
+
+/// Lazily walk a directory, yielding the files selected by the parsing options
+/// 
+/// Selection is the same as `collect_files`, but paths are produced one at a
+/// time as the directory is traversed, so they can be consumed in batches.
+/// Source files skipped for exceeding `max_file_size_mb` are recorded in
+/// `warnings` when it is given.
+#[cfg(not(target_arch = "wasm32"))]
+fn walk_files<'a>(
+    root_path: &Path,
+    options: &'a ParseOptions,
+    mut warnings: Option<&'a mut Vec<FileWarning>>,
+) -> impl Iterator<Item = Result<PathBuf, Error>> + Send + 'a {
     let walker = if options.recursive {
         WalkDir::new(root_path)
     } else {
         WalkDir::new(root_path).max_depth(1)
     };
     
-    for entry in walker {
-        let entry = entry.map_err(|e| Error::Io(e.to_string()))?;
+    walker.follow_links(options.follow_symlinks).into_iter().filter_map(move |entry| {
+        let entry = match entry {
+            Ok(entry) => entry,
+            // A symlink pointing back at an ancestor; skip it instead of failing the walk
+            Err(e) if e.loop_ancestor().is_some() => return None,
+            Err(e) => return Some(Err(Error::Io(e.to_string()))),
+        };
         let path = entry.path();
         
         // Skip directories
         if path.is_dir() {
-            continue;
+            return None;
+        }
+        
+        // Symlinked files are skipped too unless links are followed
+        if !options.follow_symlinks && entry.depth() > 0 && entry.path_is_symlink() {
+            return None;
         }
         
         // Skip hidden files if not included
         if !options.include_hidden_files && is_hidden_file(path) {
-            continue;
+            return None;
         }
         
         // Check ignore patterns
         if should_ignore_file(path, &options.ignore_patterns) {
-            continue;
+            return None;
+        }
+        
+        // Check if we can detect the language, before anything reads the file
+        let language = detect_language_by_extension(&path.to_string_lossy())?;
+        if let Some(ref only_languages) = options.only_languages
+            && !only_languages.contains(&language) {
+            return None;
         }
         
         // Check file size
         if let Ok(metadata) = path.metadata() {
             let size_mb = metadata.len() as usize / (1024 * 1024);
             if size_mb > options.max_file_size_mb {
-                continue;
+                if let Some(warnings) = warnings.as_deref_mut() {
+                    warnings.push(FileWarning {
+                        file_path: path.to_string_lossy().to_string(),
+                        reason: WarningReason::FileTooLarge,
+                        message: format!("File is {} bytes, over the {} MB limit", metadata.len(), options.max_file_size_mb),
+                    });
+                }
+                return None;
             }
         }
         
-        // Check if we can detect the language
-        if detect_language_by_extension(&path.to_string_lossy()).is_some() {
-            files.push(path.to_path_buf());
+        if options.skip_minified && looks_minified(path) {
+            return None;
         }
+        
+        Some(Ok(path.to_path_buf()))
+    })
+}
+
+/// Number of leading bytes read when checking whether a file is minified
+#[cfg(not(target_arch = "wasm32"))]
+const MINIFIED_PREFIX_BYTES: usize = 4096;
+
+/// Average line length, in bytes, above which a file is treated as minified
+#[cfg(not(target_arch = "wasm32"))]
+const MINIFIED_AVERAGE_LINE_LENGTH: usize = 300;
+
+/// Check whether a file looks minified or bundled, by name or by a cheap read of its prefix
+#[cfg(not(target_arch = "wasm32"))]
+fn looks_minified(path: &Path) -> bool {
+    let is_min_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.split('.').skip(1).any(|segment| segment == "min"));
+    if is_min_name {
+        return true;
     }
     
-    Ok(files)
+    use std::io::Read;
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut prefix = Vec::with_capacity(MINIFIED_PREFIX_BYTES);
+    if file.take(MINIFIED_PREFIX_BYTES as u64).read_to_end(&mut prefix).is_err() || prefix.is_empty() {
+        return false;
+    }
+    
+    let lines = prefix.iter().filter(|&&byte| byte == b'\n').count() + 1;
+    prefix.len() / lines > MINIFIED_AVERAGE_LINE_LENGTH
 }
 
 /// Collect files with custom filter criteria
@@ -307,185 +1282,696 @@ fn collect_files(root_path: &Path, options: &ParseOptions) -> Result<Vec<PathBuf
 /// 
 /// A vector of file paths that match both the parsing options and the custom
 /// filter criteria.
+#[cfg(not(target_arch = "wasm32"))]
 fn collect_files_with_filter(
     root_path: &Path,
     options: &ParseOptions,
     filter: &crate::FileFilter,
 ) -> Result<Vec<PathBuf>, Error> {
     let mut files = collect_files(root_path, options)?;
-    
-    // Apply custom filter
-    files.retain(|path| {
-        // Check extensions
-        if let Some(ref extensions) = filter.extensions {
-            if let Some(ext) = path.extension() {
-                if !extensions.contains(&ext.to_string_lossy().to_lowercase()) {
-                    return false;
-                }
-            } else {
+    files.retain(|path| matches_filter(path, filter));
+    Ok(files)
+}
+
+/// Check whether a file passes the criteria of a `FileFilter`
+#[cfg(not(target_arch = "wasm32"))]
+fn matches_filter(path: &Path, filter: &crate::FileFilter) -> bool {
+    // Check extensions
+    if let Some(ref extensions) = filter.extensions {
+        if let Some(ext) = path.extension() {
+            if !extensions.contains(&ext.to_string_lossy().to_lowercase()) {
                 return false;
             }
+        } else {
+            return false;
         }
-        
-        // Check languages
-        if let Some(ref languages) = filter.languages {
-            if let Some(detected_lang) = detect_language_by_extension(&path.to_string_lossy()) {
-                if !languages.contains(&detected_lang) {
-                    return false;
-                }
-            } else {
+    }
+    
+    // Check languages
+    if let Some(ref languages) = filter.languages {
+        if let Some(detected_lang) = detect_language_by_extension(&path.to_string_lossy()) {
+            if !languages.contains(&detected_lang) {
                 return false;
             }
+        } else {
+            return false;
         }
+    }
+    
+    // Check file size
+    if let Ok(metadata) = path.metadata() {
+        let size = metadata.len() as usize;
         
-        // Check file size
-        if let Ok(metadata) = path.metadata() {
-            let size = metadata.len() as usize;
-            
-            if let Some(min_size) = filter.min_size_bytes {
-                if size < min_size {
-                    return false;
-                }
-            }
-            
-            if let Some(max_size) = filter.max_size_bytes {
-                if size > max_size {
-                    return false;
-                }
-            }
+        if let Some(min_size) = filter.min_size_bytes
+            && size < min_size {
+            return false;
         }
         
-        // Apply custom predicate
-        if let Some(ref predicate) = filter.custom_predicate {
-            if !predicate(path) {
-                return false;
-            }
+        if let Some(max_size) = filter.max_size_bytes
+            && size > max_size {
+            return false;
         }
-        
-        true
-    });
+    }
     
-    Ok(files)
+    // Apply custom predicate
+    if let Some(ref predicate) = filter.custom_predicate
+        && !predicate(path) {
+        return false;
+    }
+    
+    true
 }
 
-/// Parse files in parallel
-async fn parse_files_parallel(
-    files: Vec<PathBuf>,
+/// Move soft failures out of `error_files` into `warnings`, and warn about
+/// recovered syntax errors and files cut short by `max_depth`
+/// 
+/// Returns the remaining hard failures.
+#[cfg(not(target_arch = "wasm32"))]
+fn separate_warnings(
+    parsed_files: &[ParsedFile],
+    error_files: Vec<FileError>,
+    warnings: &mut Vec<FileWarning>,
     options: &ParseOptions,
-) -> (Vec<ParsedFile>, Vec<FileError>) {
-    let chunk_size = std::cmp::max(1, files.len() / options.max_concurrent_files);
-    let mut parsed_files = Vec::new();
-    let mut error_files = Vec::new();
-    
-    for chunk in files.chunks(chunk_size) {
-        let chunk_results: Vec<_> = chunk
-            .iter()
-            .map(|path| async move {
-                let path_str = path.to_string_lossy().to_string();
-                
-                // Detect language
-                let language = match options.language_detection {
-                    LanguageDetection::ByExtension => detect_language_by_extension(&path_str),
-                    LanguageDetection::Combined => {
-                        // Try to read content for better detection
-                        if let Ok(content) = tokio::fs::read_to_string(path).await {
-                            detect_language(&path_str, Some(&content))
-                        } else {
-                            detect_language_by_extension(&path_str)
-                        }
-                    }
-                    _ => detect_language_by_extension(&path_str), // Fallback
-                };
-                
-                if let Some(lang) = language {
-                    match parse_file(&path_str, lang).await {
-                        Ok(parsed) => Ok(parsed),
-                        Err(e) => Err(FileError {
-                            file_path: path_str,
-                            error_type: ErrorType::ParseError,
-                            message: e.to_string(),
-                        }),
-                    }
-                } else {
-                    Err(FileError {
-                        file_path: path_str,
-                        error_type: ErrorType::UnsupportedLanguage,
-                        message: "Could not detect language".to_string(),
-                    })
-                }
-            })
-            .collect();
-        
-        // Await all tasks in this chunk
-        for result in futures::future::join_all(chunk_results).await {
-            match result {
-                Ok(parsed_file) => parsed_files.push(parsed_file),
-                Err(error) => error_files.push(error),
+) -> Vec<FileError> {
+    let mut failures = Vec::new();
+    for error in error_files {
+        let reason = match error.error_type {
+            ErrorType::UnsupportedLanguage => WarningReason::UnsupportedLanguage,
+            ErrorType::FileTooLarge => WarningReason::FileTooLarge,
+            _ => {
+                failures.push(error);
+                continue;
             }
-        }
+        };
+        warnings.push(FileWarning {
+            file_path: error.file_path,
+            reason,
+            message: error.message,
+        });
     }
     
-    (parsed_files, error_files)
-}
-
-/// Extract code constructs from syntax tree
-fn extract_constructs(tree: &Tree, source: &str, language: &Language) -> Vec<CodeConstruct> {
-    let root_node = tree.root_node();
-    let mut root_constructs = Vec::new();
-    
-    // Extract constructs with proper parent-child relationships
-    extract_constructs_hierarchical(root_node, source, language, &mut root_constructs, None);
-    
-    // Flatten the hierarchy for the final result while preserving relationships
-    let mut all_constructs = Vec::new();
-    flatten_constructs(&root_constructs, &mut all_constructs);
+    for file in parsed_files {
+        if file.depth_limit_reached {
+            warnings.push(FileWarning {
+                file_path: file.file_path.clone(),
+                reason: WarningReason::DepthLimitReached,
+                message: format!(
+                    "Construct extraction stopped at max depth {}",
+                    options.max_depth.unwrap_or_default()
+                ),
+            });
+        }
+        let Some(tree) = &file.syntax_tree else {
+            continue;
+        };
+        if let Some(error) = collect_syntax_errors(tree).first() {
+            warnings.push(FileWarning {
+                file_path: file.file_path.clone(),
+                reason: WarningReason::SyntaxErrorsRecovered,
+                message: format!(
+                    "Recovered from syntax errors, first at line {}, column {}",
+                    error.start_point.row + 1,
+                    error.start_point.column,
+                ),
+            });
+        }
+    }
     
-    all_constructs
+    failures
 }
 
-/// Recursively extract constructs from nodes with proper hierarchy
-fn extract_constructs_hierarchical(
-    node: Node,
-    source: &str,
-    language: &Language,
-    constructs: &mut Vec<CodeConstruct>,
-    parent_construct: Option<&CodeConstruct>,
-) {
-    let node_type = node.kind();
-    let supported_types = get_supported_node_types(language);
-    
-    if supported_types.contains(&node_type.to_string()) {
-        let mut construct = create_code_construct_with_parent(node, source, language, parent_construct);
-        
-        // Recursively process children and add them to this construct
-        let mut child_constructs = Vec::new();
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                extract_constructs_hierarchical(child, source, language, &mut child_constructs, Some(&construct));
-            }
-        }
-        
-        construct.children = child_constructs;
-        constructs.push(construct);
-    } else {
-        // If this node is not a supported construct, continue searching in its children
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                extract_constructs_hierarchical(child, source, language, constructs, parent_construct);
-            }
+/// Set each file's `relative_path` to its path below the parse root
+/// 
+/// Single-file parses only know the file name, so directory parses fill in
+/// the intermediate directories here. Paths that somehow fall outside the
+/// root keep their file name.
+#[cfg(not(target_arch = "wasm32"))]
+fn relativize_paths(parsed_files: &mut [ParsedFile], root: &Path) {
+    for file in parsed_files {
+        if let Ok(relative) = Path::new(&file.file_path).strip_prefix(root) {
+            file.relative_path = relative.to_string_lossy().to_string();
         }
     }
 }
 
-/// Flatten hierarchical constructs into a single vector while preserving relationships
-fn flatten_constructs(constructs: &[CodeConstruct], flattened: &mut Vec<CodeConstruct>) {
-    for construct in constructs {
-        flattened.push(construct.clone());
-        flatten_constructs(&construct.children, flattened);
+/// Order directory results by file path
+/// 
+/// Files finish parsing in whatever order their tasks complete, so results
+/// are sorted before returning to keep output reproducible across runs.
+#[cfg(not(target_arch = "wasm32"))]
+fn sort_by_file_path(parsed_files: &mut [ParsedFile], error_files: &mut [FileError], warnings: &mut [FileWarning]) {
+    parsed_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    error_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    warnings.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+}
+
+/// Alternate between taking `batch_size` files from a lazy walk and parsing them
+/// 
+/// Only one batch of paths is held at a time, so memory for the file list
+/// stays bounded however large the directory is. With
+/// `options.thread_pool_size` set, every batch runs on the same dedicated
+/// runtime.
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_in_batches(
+    mut files: impl Iterator<Item = Result<PathBuf, Error>>,
+    batch_size: usize,
+    options: &ParseOptions,
+) -> Result<(Vec<ParsedFile>, Vec<FileError>), Error> {
+    let mut parsed_files = Vec::new();
+    let mut error_files = Vec::new();
+    let pool = options.thread_pool_size.and_then(ThreadPool::new);
+    
+    loop {
+        let batch = files.by_ref().take(batch_size.max(1)).collect::<Result<Vec<_>, _>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        let (parsed, errors) = parse_files_on(pool.as_ref(), batch, options).await;
+        parsed_files.extend(parsed);
+        error_files.extend(errors);
     }
+    
+    Ok((parsed_files, error_files))
 }
 
-/// Create a CodeConstruct from a tree-sitter node with proper parent relationship
+/// Parse files in parallel
+/// 
+/// At most `options.max_concurrent_files` files are read and parsed at once,
+/// however many files there are in total. With `options.thread_pool_size`
+/// set, the work runs on a dedicated runtime of that many threads instead
+/// of the caller's.
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_files_parallel(
+    files: Vec<PathBuf>,
+    options: &ParseOptions,
+) -> (Vec<ParsedFile>, Vec<FileError>) {
+    let pool = options.thread_pool_size.and_then(ThreadPool::new);
+    parse_files_on(pool.as_ref(), files, options).await
+}
+
+/// Parse files in parallel on `pool`, or on the current runtime without one
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_files_on(
+    pool: Option<&ThreadPool>,
+    files: Vec<PathBuf>,
+    options: &ParseOptions,
+) -> (Vec<ParsedFile>, Vec<FileError>) {
+    match pool {
+        Some(pool) => {
+            let options = options.clone();
+            pool.run(async move { parse_files_concurrently(files, &options).await }).await
+        }
+        None => parse_files_concurrently(files, options).await,
+    }
+}
+
+/// A dedicated runtime with a fixed number of worker and blocking threads
+/// 
+/// Parsing happens in `spawn_blocking`, so capping the blocking pool bounds
+/// how many files are parsed at the same time.
+#[cfg(not(target_arch = "wasm32"))]
+struct ThreadPool {
+    /// Always `Some` until dropped
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ThreadPool {
+    /// Build a pool of `threads` threads, or None if the runtime cannot be
+    /// built, in which case callers fall back to the current runtime
+    fn new(threads: usize) -> Option<Self> {
+        let threads = threads.max(1);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(threads)
+            .max_blocking_threads(threads)
+            .thread_name("tree-parser-worker")
+            .enable_all()
+            .build()
+            .ok()?;
+        Some(Self { runtime: Some(runtime) })
+    }
+    
+    /// Run a future on the pool and wait for its output
+    async fn run<F>(&self, future: F) -> F::Output
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let runtime = self.runtime.as_ref().expect("runtime is only taken on drop");
+        match runtime.spawn(future).await {
+            Ok(output) => output,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which is not allowed inside async code
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Parse files concurrently on the current runtime
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_files_concurrently(
+    files: Vec<PathBuf>,
+    options: &ParseOptions,
+) -> (Vec<ParsedFile>, Vec<FileError>) {
+    if options.dedupe_identical {
+        let results = parse_distinct_files(files, options).await;
+        return collect_parse_results(results);
+    }
+    
+    let results = join_bounded(files, options.max_concurrent_files, |path| async move {
+        let path_str = path.to_string_lossy().to_string();
+        
+        if let Some(lang) = detect_file_language(&path, options).await {
+            match parse_file_with_options(&path_str, lang, options).await {
+                Ok(parsed) => Ok(parsed),
+                Err(e) => Err(FileError {
+                    file_path: path_str,
+                    error_type: ErrorType::from(&e),
+                    message: e.to_string(),
+                }),
+            }
+        } else {
+            Err(FileError {
+                file_path: path_str,
+                error_type: ErrorType::UnsupportedLanguage,
+                message: "Could not detect language".to_string(),
+            })
+        }
+    })
+    .await;
+    
+    collect_parse_results(results)
+}
+
+/// Parse files concurrently, parsing each distinct content only once
+/// 
+/// Every file is read and hashed under the `max_concurrent_files` limit, then
+/// parsed from the same contents unless a file with identical contents was
+/// read first, in which case it gets a copy of that file's result. Files that
+/// cannot be read report their own error. Results are in input order.
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_distinct_files(files: Vec<PathBuf>, options: &ParseOptions) -> Vec<Result<ParsedFile, FileError>> {
+    // The first path read with each content hash
+    let first_paths: std::sync::Mutex<HashMap<u128, String>> = Default::default();
+    let first_paths = &first_paths;
+    
+    let outcomes = join_bounded(files, options.max_concurrent_files, |path| async move {
+        let path_str = path.to_string_lossy().to_string();
+        let content = read_source_file_blocking(&path_str).await;
+        if let Ok(content) = &content {
+            let hash = xxhash_rust::xxh3::xxh3_128(content.as_bytes());
+            let mut first_paths = first_paths.lock().expect("no panics while holding the lock");
+            match first_paths.entry(hash) {
+                std::collections::hash_map::Entry::Occupied(first) => return Err((path, first.get().clone())),
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert(path_str.clone());
+                }
+            }
+        }
+        Ok(parse_read_file(path_str, content, options).await)
+    })
+    .await;
+    
+    // Give every duplicate path its own copy of the first path's result
+    let duplicated: HashSet<&String> = outcomes.iter().filter_map(|outcome| outcome.as_ref().err()).map(|(_, first)| first).collect();
+    let firsts: HashMap<String, Result<ParsedFile, FileError>> = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.as_ref().ok())
+        .filter_map(|result| {
+            let file_path = match result {
+                Ok(parsed_file) => &parsed_file.file_path,
+                Err(error) => &error.file_path,
+            };
+            duplicated.contains(file_path).then(|| (file_path.clone(), result.clone()))
+        })
+        .collect();
+    outcomes
+        .into_iter()
+        .map(|outcome| match outcome {
+            Ok(result) => result,
+            Err((path, first)) => with_file_path(&firsts[&first], &path),
+        })
+        .collect()
+}
+
+/// Split parse results into parsed files and errors
+#[cfg(not(target_arch = "wasm32"))]
+fn collect_parse_results(
+    results: impl IntoIterator<Item = Result<ParsedFile, FileError>>,
+) -> (Vec<ParsedFile>, Vec<FileError>) {
+    let mut parsed_files = Vec::new();
+    let mut error_files = Vec::new();
+    for result in results {
+        match result {
+            Ok(parsed_file) => parsed_files.push(parsed_file),
+            Err(error) => error_files.push(error),
+        }
+    }
+    
+    (parsed_files, error_files)
+}
+
+/// Parse the files whose content hash differs from `previous_hashes`
+/// 
+/// Returns one entry per file in input order: `None` if the file is
+/// unchanged, otherwise the result of parsing it.
+#[cfg(not(target_arch = "wasm32"))]
+async fn reparse_changed_files(
+    files: Vec<PathBuf>,
+    previous_hashes: HashMap<String, u64>,
+    options: &ParseOptions,
+) -> Vec<Option<Result<ParsedFile, FileError>>> {
+    match options.thread_pool_size.and_then(ThreadPool::new) {
+        Some(pool) => {
+            let options = options.clone();
+            pool.run(async move { reparse_changed_files_concurrently(files, &previous_hashes, &options).await })
+                .await
+        }
+        None => reparse_changed_files_concurrently(files, &previous_hashes, options).await,
+    }
+}
+
+/// Read and hash files concurrently on the current runtime, parsing changed ones from the same contents
+#[cfg(not(target_arch = "wasm32"))]
+async fn reparse_changed_files_concurrently(
+    files: Vec<PathBuf>,
+    previous_hashes: &HashMap<String, u64>,
+    options: &ParseOptions,
+) -> Vec<Option<Result<ParsedFile, FileError>>> {
+    join_bounded(files, options.max_concurrent_files, |path| async move {
+        let path_str = path.to_string_lossy().to_string();
+        let content = read_source_file_blocking(&path_str).await;
+        if let Ok(content) = &content
+            && previous_hashes.get(&path_str) == Some(&xxhash_rust::xxh3::xxh3_64(content.as_bytes()))
+        {
+            return None;
+        }
+        
+        Some(parse_read_file(path_str, content, options).await)
+    })
+    .await
+}
+
+/// Read a source file on the blocking thread pool
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_source_file_blocking(file_path: &str) -> Result<String, Error> {
+    let file_path = file_path.to_string();
+    tokio::task::spawn_blocking(move || read_source_file(&file_path))
+        .await
+        .map_err(|e| Error::Io(e.to_string()))
+        .and_then(|content| content)
+}
+
+/// Detect the language of a file already read and parse it from those contents
+/// 
+/// Content-based detection uses the contents instead of reading the file
+/// again. A failed read is reported as the file's error.
+#[cfg(not(target_arch = "wasm32"))]
+async fn parse_read_file(
+    path_str: String,
+    content: Result<String, Error>,
+    options: &ParseOptions,
+) -> Result<ParsedFile, FileError> {
+    let language = match (&content, &options.language_detection) {
+        (Ok(content), LanguageDetection::Combined) => detect_language(&path_str, Some(content)),
+        _ => detect_language_by_extension(&path_str),
+    };
+    let Some(language) = language else {
+        return Err(FileError {
+            file_path: path_str,
+            error_type: ErrorType::UnsupportedLanguage,
+            message: "Could not detect language".to_string(),
+        });
+    };
+    
+    let options = options.clone();
+    let file_path = path_str.clone();
+    let parsed = tokio::task::spawn_blocking(move || parse_file_content(&file_path, content?, language, &options))
+        .await
+        .map_err(|e| Error::Parse(e.to_string()))
+        .and_then(|parsed| parsed);
+    parsed.map_err(|e| FileError {
+        file_path: path_str,
+        error_type: ErrorType::from(&e),
+        message: e.to_string(),
+    })
+}
+
+/// Copy a parse result over to another path with the same content
+#[cfg(not(target_arch = "wasm32"))]
+fn with_file_path(result: &Result<ParsedFile, FileError>, path: &Path) -> Result<ParsedFile, FileError> {
+    let file_path = path.to_string_lossy().to_string();
+    match result {
+        Ok(parsed_file) => {
+            let mut copy = parsed_file.clone();
+            copy.relative_path = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            copy.file_path = file_path;
+            Ok(copy)
+        }
+        Err(error) => Err(FileError { file_path, ..error.clone() }),
+    }
+}
+
+/// Detect a file's language using the strategy selected by `options.language_detection`
+#[cfg(not(target_arch = "wasm32"))]
+async fn detect_file_language(path: &Path, options: &ParseOptions) -> Option<Language> {
+    let path_str = path.to_string_lossy();
+    match options.language_detection {
+        LanguageDetection::ByExtension => detect_language_by_extension(&path_str),
+        LanguageDetection::Combined => {
+            #[cfg(test)]
+            let _reading = probe::reading(&path_str);
+            
+            // Try to read content for better detection
+            if let Ok(content) = tokio::fs::read_to_string(path).await {
+                detect_language(&path_str, Some(&content))
+            } else {
+                detect_language_by_extension(&path_str)
+            }
+        }
+        _ => detect_language_by_extension(&path_str), // Fallback
+    }
+}
+
+/// Run `task` over every item with at most `limit` tasks in flight, returning results in input order
+/// 
+/// A task holds its permit until it completes, including any blocking work it
+/// awaits, so file reads done inside `task` never exceed `limit` open files.
+#[cfg(not(target_arch = "wasm32"))]
+async fn join_bounded<T, F, Fut>(items: Vec<T>, limit: usize, task: F) -> Vec<Fut::Output>
+where
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future,
+{
+    let semaphore = tokio::sync::Semaphore::new(limit.max(1));
+    let semaphore = &semaphore;
+    let task = &task;
+    
+    futures::future::join_all(items.into_iter().map(|item| async move {
+        // The semaphore is never closed, so acquiring only waits
+        let _permit = semaphore.acquire().await.expect("semaphore is never closed");
+        task(item).await
+    }))
+    .await
+}
+
+/// State shared by the recursive construct extraction
+struct ExtractionContext<'a> {
+    source: &'a str,
+    language: &'a Language,
+    /// Node types to extract, already narrowed by the allowlist
+    node_types: Vec<String>,
+    max_depth: Option<usize>,
+    /// Set when descent was cut short by `max_depth`
+    depth_limit_reached: bool,
+    /// Whether to return every construct or only the top-level ones
+    flatten: bool,
+    /// Whether anonymous constructs get a positional `synthetic_name`
+    synthesize_anonymous_names: bool,
+    /// Node kinds to descend into when skipping non-containers, `None` to descend everywhere
+    containers: Option<Vec<String>>,
+    /// Number of nodes visited, to measure pruning in tests
+    #[cfg(test)]
+    visited_nodes: usize,
+}
+
+impl<'a> ExtractionContext<'a> {
+    fn new(source: &'a str, language: &'a Language, options: &ParseOptions) -> Self {
+        Self {
+            source,
+            language,
+            node_types: extracted_node_types(language, options),
+            max_depth: options.max_depth,
+            depth_limit_reached: false,
+            flatten: options.flatten_constructs,
+            synthesize_anonymous_names: options.synthesize_anonymous_names,
+            containers: container_node_types(language).filter(|_| options.skip_non_containers),
+            #[cfg(test)]
+            visited_nodes: 0,
+        }
+    }
+}
+
+/// Get the node kinds to descend into when skipping non-containers, or None for languages without a table
+/// 
+/// Every construct kind counts as a container, so constructs left out by the
+/// allowlist (e.g. a Python `assignment` holding a `lambda`) are still searched.
+fn container_node_types(language: &Language) -> Option<Vec<String>> {
+    let containers = get_container_node_types(language);
+    if containers.is_empty() {
+        return None;
+    }
+    let mut kinds = get_supported_node_types(language);
+    kinds.extend(containers.iter().map(|kind| kind.to_string()));
+    Some(kinds)
+}
+
+/// Get the node types extracted as constructs for a language, narrowed by the allowlist if any
+fn extracted_node_types(language: &Language, options: &ParseOptions) -> Vec<String> {
+    let mut node_types = get_supported_node_types(language);
+    if let Some(ref allowlist) = options.node_type_allowlist {
+        node_types.retain(|node_type| allowlist.contains(node_type));
+    }
+    node_types
+}
+
+/// Extract code constructs from syntax tree
+#[cfg(test)]
+fn extract_constructs(
+    tree: &Tree,
+    source: &str,
+    language: &Language,
+    options: &ParseOptions,
+) -> Vec<CodeConstruct> {
+    let mut context = ExtractionContext::new(source, language, options);
+    extract_constructs_with_context(tree, &mut context)
+}
+
+/// Extract code constructs from syntax tree using a prepared context
+fn extract_constructs_with_context(tree: &Tree, context: &mut ExtractionContext) -> Vec<CodeConstruct> {
+    // Constructs whose subtrees are still being walked, each with the children found so far
+    let mut open: Vec<(CodeConstruct, Vec<CodeConstruct>)> = Vec::new();
+    let mut root_constructs = Vec::new();
+    
+    // Extract constructs with proper parent-child relationships
+    walk_construct_nodes(tree.root_node(), context, |context, event| match event {
+        ConstructEvent::Enter(node) => {
+            let parent = open.last().map(|(construct, _)| construct);
+            let mut construct = create_code_construct_with_parent(node, context.source, context.language, parent);
+            if context.synthesize_anonymous_names && construct.name.is_none() {
+                construct.synthetic_name = Some(format!("<anonymous@{}:{}>", construct.start_line, construct.start_column));
+            }
+            open.push((construct, Vec::new()));
+        }
+        ConstructEvent::Leave => {
+            let (mut construct, children) = open.pop().expect("every construct is entered before it is left");
+            construct.children = children;
+            match open.last_mut() {
+                Some((_, siblings)) => siblings.push(construct),
+                None => root_constructs.push(construct),
+            }
+        }
+    });
+    
+    if !context.flatten {
+        return root_constructs;
+    }
+    
+    // Flatten the hierarchy for the final result while preserving relationships
+    let mut all_constructs = Vec::new();
+    flatten_constructs(&root_constructs, &mut all_constructs);
+    
+    all_constructs
+}
+
+/// A step of `walk_construct_nodes`
+enum ConstructEvent<'tree> {
+    /// A construct node, before any of its descendants
+    Enter(Node<'tree>),
+    /// The end of the most recently entered construct that is still open
+    Leave,
+}
+
+/// Walk the nodes construct extraction descends into, without recursing
+/// 
+/// Construct nodes are reported in pre-order, each followed by a `Leave` once
+/// its subtree has been walked. Descent honours `max_depth` and
+/// `skip_non_containers`, so anything built from these events sees the same
+/// nodes as extraction.
+fn walk_construct_nodes<'tree>(
+    root: Node<'tree>,
+    context: &mut ExtractionContext,
+    mut visit: impl FnMut(&ExtractionContext, ConstructEvent<'tree>),
+) {
+    // Depths of the construct nodes that have been entered but not left
+    let mut open_depths: Vec<usize> = Vec::new();
+    let mut cursor = root.walk();
+    let mut depth = 0;
+    
+    loop {
+        let node = cursor.node();
+        let node_type = node.kind();
+        
+        let descend = if context.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            // Stop descending instead of spending unbounded work on pathological trees
+            context.depth_limit_reached = true;
+            false
+        } else {
+            #[cfg(test)]
+            {
+                context.visited_nodes += 1;
+            }
+            
+            // Only named nodes are constructs; keyword tokens can share a kind (e.g. Python `lambda`)
+            if node.is_named() && context.node_types.iter().any(|t| t == node_type) {
+                visit(context, ConstructEvent::Enter(node));
+                open_depths.push(depth);
+                true
+            } else {
+                // If this node is not a supported construct, continue searching in its children
+                depth == 0 || context.containers.as_ref().is_none_or(|containers| containers.iter().any(|kind| kind == node_type))
+            }
+        };
+        
+        if descend && cursor.goto_first_child() {
+            depth += 1;
+            continue;
+        }
+        
+        // The node at the cursor is done; close it and any ancestors without further siblings
+        loop {
+            if open_depths.last() == Some(&depth) {
+                open_depths.pop();
+                visit(context, ConstructEvent::Leave);
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return;
+            }
+            depth -= 1;
+        }
+    }
+}
+
+/// Flatten hierarchical constructs into a single vector while preserving relationships
+fn flatten_constructs(constructs: &[CodeConstruct], flattened: &mut Vec<CodeConstruct>) {
+    for construct in constructs {
+        flattened.push(construct.clone());
+        flatten_constructs(&construct.children, flattened);
+    }
+}
+
+/// Create a CodeConstruct from a tree-sitter node with proper parent relationship
 fn create_code_construct_with_parent(
     node: Node, 
     source: &str, 
@@ -494,13 +1980,13 @@ fn create_code_construct_with_parent(
 ) -> CodeConstruct {
     let start_byte = node.start_byte();
     let end_byte = node.end_byte();
-    let source_code = source[start_byte..end_byte].to_string();
+    let source_code = slice_source(source, start_byte, end_byte);
     
     let start_point = node.start_position();
     let end_point = node.end_position();
     
     // Extract name if possible
-    let name = extract_construct_name(node, source);
+    let name = extract_construct_name(node, source, language);
     
     // Create metadata
     let metadata = extract_metadata(node, source, language);
@@ -510,12 +1996,19 @@ fn create_code_construct_with_parent(
     
     CodeConstruct {
         node_type: node.kind().to_string(),
+        kind: get_construct_kind(language, node.kind()),
+        language: Some(language.clone()),
         name,
+        synthetic_name: None,
         source_code,
         start_line: start_point.row + 1, // Convert to 1-based
         end_line: end_point.row + 1,
+        start_column: start_point.column,
+        end_column: end_point.column,
         start_byte,
         end_byte,
+        body_range: node.child_by_field_name("body").map(|body| (body.start_byte(), body.end_byte())),
+        depth: parent_construct.map_or(0, |parent| parent.depth + 1),
         parent,
         children: Vec::new(), // Will be populated by the caller
         metadata,
@@ -523,85 +2016,680 @@ fn create_code_construct_with_parent(
 }
 
 /// Extract construct name from node
-fn extract_construct_name(node: Node, source: &str) -> Option<String> {
-    // Try to find identifier child
-    for i in 0..node.child_count() {
-        if let Some(child) = node.child(i) {
-            if child.kind() == "identifier" || child.kind() == "name" {
-                let start = child.start_byte();
-                let end = child.end_byte();
-                return Some(source[start..end].to_string());
+fn extract_construct_name(node: Node, source: &str, language: &Language) -> Option<String> {
+    // Rust macro invocations may name a path (`std::vec!`), kept whole
+    if node.kind() == "macro_invocation"
+        && let Some(name) = node.child_by_field_name("macro") {
+        return Some(slice_source(source, name.start_byte(), name.end_byte()));
+    }
+    
+    // Java fields name the first variable declarator (`int a, b;` -> `a`)
+    if node.kind() == "field_declaration"
+        && let Some(name) = node
+            .child_by_field_name("declarator")
+            .and_then(|declarator| declarator.child_by_field_name("name"))
+            .or_else(|| node.child_by_field_name("name")) {
+        return Some(slice_source(source, name.start_byte(), name.end_byte()));
+    }
+    
+    // C/C++ macros are named by their `name` field, ahead of any parameter identifiers
+    if matches!(node.kind(), "preproc_def" | "preproc_function_def")
+        && let Some(name) = node.child_by_field_name("name") {
+        return Some(slice_source(source, name.start_byte(), name.end_byte()));
+    }
+    
+    // Python lambdas are anonymous; use the variable they are assigned to
+    if node.kind() == "lambda" {
+        return node
+            .parent()
+            .filter(|parent| parent.kind() == "assignment")
+            .and_then(|assignment| assignment.child_by_field_name("left"))
+            .filter(|target| target.kind() == "identifier")
+            .map(|target| slice_source(source, target.start_byte(), target.end_byte()));
+    }
+    
+    // Lua functions assigned to variables (`M.add = function() end`) take the matching target
+    if *language == Language::Lua && node.kind() == "function_definition" {
+        let values = node.parent().filter(|parent| parent.kind() == "expression_list")?;
+        let assignment = values.parent().filter(|parent| parent.kind() == "assignment_statement")?;
+        let index = (0..values.named_child_count())
+            .position(|i| values.named_child(i).is_some_and(|value| value.id() == node.id()))?;
+        return assignment
+            .named_child(0)
+            .and_then(|targets| targets.named_child(index))
+            .map(|target| slice_source(source, target.start_byte(), target.end_byte()));
+    }
+    
+    // Lua locals (`local M = {}`) name their first variable
+    if *language == Language::Lua && node.kind() == "variable_declaration" {
+        let mut cursor = node.walk();
+        let targets = node.named_children(&mut cursor).find_map(|child| match child.kind() {
+            "variable_list" => Some(child),
+            "assignment_statement" => child.named_child(0),
+            _ => None,
+        })?;
+        return targets
+            .named_child(0)
+            .map(|target| slice_source(source, target.start_byte(), target.end_byte()));
+    }
+    
+    // JS/TS exports take the exported declaration's name, or `default` for `export default <expr>`
+    if node.kind() == "export_statement" {
+        if let Some(mut declaration) = node.child_by_field_name("declaration") {
+            // `export declare function f()` names the ambient declaration's inner declaration
+            if declaration.kind() == "ambient_declaration"
+                && let Some(inner) = declaration.named_child(0) {
+                declaration = inner;
             }
+            // `export const a = 1` names its first declarator
+            let named = match declaration.named_child(0) {
+                Some(declarator) if declarator.kind() == "variable_declarator" => declarator,
+                _ => declaration,
+            };
+            return named
+                .child_by_field_name("name")
+                .map(|name| slice_source(source, name.start_byte(), name.end_byte()));
+        }
+        let is_default = (0..node.child_count()).any(|i| node.child(i).is_some_and(|child| child.kind() == "default"));
+        return is_default.then(|| "default".to_string());
+    }
+    
+    // C/C++ functions are named inside their (possibly nested) declarator
+    if node.kind() == "function_definition"
+        && let Some(mut declarator) = node.child_by_field_name("declarator") {
+        while let Some(inner) = declarator.child_by_field_name("declarator") {
+            declarator = inner;
+        }
+        return Some(slice_source(source, declarator.start_byte(), declarator.end_byte()));
+    }
+    
+    // C++ namespaces keep nested names (`namespace a::b`) whole
+    if node.kind() == "namespace_definition" {
+        return node
+            .child_by_field_name("name")
+            .map(|name| slice_source(source, name.start_byte(), name.end_byte()));
+    }
+    
+    // Rust impl blocks are named after their target type, not the trait
+    if node.kind() == "impl_item"
+        && let Some(target) = node.child_by_field_name("type") {
+        return Some(slice_source(source, target.start_byte(), target.end_byte()));
+    }
+    
+    // Otherwise use the first child of a name-bearing kind for the language
+    let name_kinds = get_name_node_types(language);
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i)
+            && name_kinds.contains(&child.kind()) {
+            let start = child.start_byte();
+            let end = child.end_byte();
+            return Some(slice_source(source, start, end));
         }
     }
     None
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::Language;
+/// Visit `node` and its descendants in pre-order without recursing, so deep trees can't overflow the stack
+/// 
+/// `visit` returns whether to descend into the children of the node it was given.
+pub(crate) fn walk_tree(node: Node, mut visit: impl FnMut(Node) -> bool) {
+    let mut cursor = node.walk();
+    loop {
+        if visit(cursor.node()) && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
 
-    #[test]
-    fn test_parent_child_relationships() {
-        // Simple Python code with nested structure
-        let source = "class TestClass:\n    def test_method(self):\n        pass";
-        
-        // Create a simple tree-sitter parser for testing
-        let mut parser = Parser::new();
-        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
-        parser.set_language(&language).unwrap();
-        
-        let tree = parser.parse(source, None).unwrap();
-        let constructs = extract_constructs(&tree, source, &Language::Python);
-        
-        // Find class and method constructs
-        let class_construct = constructs.iter().find(|c| c.node_type == "class_definition");
-        let method_construct = constructs.iter().find(|c| c.node_type == "function_definition");
-        
-        assert!(class_construct.is_some(), "Should find class construct");
-        assert!(method_construct.is_some(), "Should find method construct");
-        
-        let method = method_construct.unwrap();
-        
-        // Check that method has a parent
-        assert!(method.parent.is_some(), "Method should have a parent");
-        
-        if let Some(parent) = &method.parent {
-            assert_eq!(parent.node_type, "class_definition", "Method's parent should be the class");
+/// Slice `source` by byte offsets without panicking
+/// 
+/// tree-sitter offsets normally fall on character boundaries, but a desync
+/// (e.g. from mismatched encodings) must not crash extraction. Out-of-range
+/// offsets are clamped and non-boundary slices are decoded lossily.
+pub(crate) fn slice_source(source: &str, start: usize, end: usize) -> String {
+    if let Some(text) = source.get(start..end) {
+        return text.to_string();
+    }
+    
+    let bytes = source.as_bytes();
+    let end = end.min(bytes.len());
+    let start = start.min(end);
+    String::from_utf8_lossy(&bytes[start..end]).into_owned()
+}
+
+/// Get the source text of a parsed file
+/// 
+/// Uses the text retained alongside the syntax tree when available and
+/// falls back to reading the file from disk, normalized the same way it was
+/// for parsing so that construct offsets still line up.
+pub(crate) fn retained_source(parsed_file: &ParsedFile) -> Result<Cow<'_, str>, Error> {
+    match &parsed_file.source {
+        Some(source) => Ok(Cow::Borrowed(source.as_str())),
+        None => {
+            #[cfg(not(target_arch = "wasm32"))]
+            let content = read_source_file(&parsed_file.file_path)?;
+            #[cfg(target_arch = "wasm32")]
+            let content = std::fs::read_to_string(&parsed_file.file_path).map_err(|e| Error::Io(e.to_string()))?;
+            Ok(Cow::Owned(normalize_source(content, parsed_file.line_endings_normalized)))
         }
-        
-        // Check that class has children
-        let class = class_construct.unwrap();
-        assert!(!class.children.is_empty(), "Class should have children");
-        
-        let child_method = class.children.iter().find(|c| c.node_type == "function_definition");
-        assert!(child_method.is_some(), "Class should contain the method as a child");
     }
 }
 
+/// Use the retained syntax tree, or re-parse the source if it was dropped
+pub(crate) fn syntax_tree_for(file: &ParsedFile, source: &str) -> Option<Tree> {
+    if let Some(tree) = &file.syntax_tree {
+        return Some(tree.clone());
+    }
+    
+    let ts_language = get_tree_sitter_language(&file.language).ok()?;
+    let mut parser = Parser::new();
+    parser.set_language(&ts_language).ok()?;
+    parser.parse(source, None)
+}
+
 /// Extract metadata from node
-fn extract_metadata(_node: Node, _source: &str, _language: &Language) -> ConstructMetadata {
+fn extract_metadata(node: Node, source: &str, language: &Language) -> ConstructMetadata {
+    let annotations = extract_annotations(node, source, language);
+    let parsed_annotations = annotations.iter().map(|annotation| parse_annotation(annotation)).collect();
     ConstructMetadata {
-        visibility: None,
-        modifiers: Vec::new(),
-        parameters: Vec::new(),
+        visibility: extract_visibility(node, source),
+        modifiers: extract_modifiers(node, language),
+        parameters: extract_parameters(node, source),
         return_type: None,
         inheritance: Vec::new(),
-        annotations: Vec::new(),
-        documentation: None,
+        annotations,
+        parsed_annotations,
+        documentation: extract_leading_comments(node, source),
+        field_type: extract_field_type(node, source),
+        type_parameters: extract_type_parameters(node, source),
+        registered_by: extract_registering_call(node, source, language),
     }
 }
 
-/// Check if file is hidden
-fn is_hidden_file(path: &Path) -> bool {
-    path.file_name()
-        .and_then(|name| name.to_str())
-        .map(|name| name.starts_with('.'))
-        .unwrap_or(false)
-}
-
-/// Check if file should be ignored based on patterns
+/// Extract the block of line comments directly above a construct
+/// 
+/// Comments must be contiguous and end on the line before the construct (or
+/// before its attributes); a blank line ends the block. Comment markers are
+/// stripped from each line.
+fn extract_leading_comments(node: Node, source: &str) -> Option<String> {
+    // Comments precede the wrapper of decorated, exported or statement-level constructs
+    let mut anchor = node;
+    while let Some(parent) = anchor.parent()
+        && matches!(parent.kind(), "decorated_definition" | "export_statement" | "expression_statement") {
+        anchor = parent;
+    }
+    
+    let mut lines = Vec::new();
+    let mut next_row = anchor.start_position().row;
+    let mut sibling = anchor.prev_sibling();
+    while let Some(current) = sibling {
+        let start_row = current.start_position().row;
+        let end = current.end_position();
+        // Some grammars include the trailing newline in line comments
+        let end_row = if end.column == 0 && end.row > start_row { end.row - 1 } else { end.row };
+        if end_row + 1 < next_row {
+            break;
+        }
+        
+        match current.kind() {
+            // Rust attributes sit between a comment block and its item
+            "attribute_item" => {}
+            "comment" | "line_comment" | "block_comment" => {
+                // A trailing comment belongs to the code on its own line
+                if let Some(previous) = current.prev_sibling()
+                    && previous.end_position().row == start_row
+                    && !previous.kind().contains("comment") {
+                    break;
+                }
+                let text = slice_source(source, current.start_byte(), current.end_byte());
+                lines.extend(text.lines().rev().map(strip_comment_markers));
+            }
+            _ => break,
+        }
+        
+        next_row = start_row;
+        sibling = current.prev_sibling();
+    }
+    
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n").trim().to_string())
+}
+
+/// Strip comment syntax (`//`, `///`, `#`, `/*`, `*`, `*/`) from one comment line
+pub(crate) fn strip_comment_markers(line: &str) -> String {
+    let line = line.trim();
+    let line = line.strip_suffix("*/").unwrap_or(line);
+    let stripped = ["///", "//!", "//", "/**", "/*", "#", "*"]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .unwrap_or(line);
+    stripped.strip_prefix(' ').unwrap_or(stripped).trim_end().to_string()
+}
+
+/// Extract the declared type of a struct/class field or annotated Python attribute
+fn extract_field_type(node: Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "field_declaration" | "assignment" => node
+            .child_by_field_name("type")
+            .map(|field_type| slice_source(source, field_type.start_byte(), field_type.end_byte())),
+        _ => None,
+    }
+}
+
+/// Parameter node kinds that collect any number of arguments
+const VARIADIC_PARAMETER_KINDS: &[&str] = &[
+    "spread_parameter",
+    "variadic_parameter",
+    "variadic_parameter_declaration",
+    "list_splat_pattern",
+    "dictionary_splat_pattern",
+    "rest_pattern",
+];
+
+/// Extract the parameters of a function-like node
+/// 
+/// The list is the `parameters` field, which C and C++ keep on the
+/// (possibly nested) function declarator instead of the definition.
+fn extract_parameters(node: Node, source: &str) -> Vec<Parameter> {
+    let mut list = node.child_by_field_name("parameters");
+    let mut declarator = node.child_by_field_name("declarator");
+    while list.is_none() {
+        let Some(current) = declarator else { break };
+        list = current.child_by_field_name("parameters");
+        declarator = current.child_by_field_name("declarator");
+    }
+    let Some(list) = list else {
+        return Vec::new();
+    };
+    
+    let text = |n: Node| slice_source(source, n.start_byte(), n.end_byte());
+    let mut parameters = Vec::new();
+    for i in 0..list.child_count() {
+        let Some(child) = list.child(i) else { continue };
+        // C-style `...` is an anonymous token rather than a parameter node
+        if child.kind() == "..." {
+            parameters.push(Parameter {
+                name: "...".to_string(),
+                param_type: None,
+                default_value: None,
+                is_variadic: true,
+            });
+            continue;
+        }
+        if !child.is_named() || child.kind().contains("comment") {
+            continue;
+        }
+        
+        let name_node = ["name", "declarator", "pattern"]
+            .iter()
+            .find_map(|field| child.child_by_field_name(field))
+            .or_else(|| {
+                (0..child.named_child_count())
+                    .filter_map(|j| child.named_child(j))
+                    .find(|named| named.kind() == "identifier" || named.kind().ends_with("declarator"))
+            });
+        let name = match name_node {
+            Some(name_node) => innermost_identifier(name_node).map_or_else(|| text(name_node), text),
+            None => text(child),
+        };
+        
+        // C and C++ split a parameter's type between the specifiers and the
+        // declarator (`const char *label`), so the type is everything up to
+        // the end of the declarator with the name cut out
+        let c_declarator = if C_PARAMETER_KINDS.contains(&child.kind()) {
+            child.child_by_field_name("declarator")
+        } else {
+            None
+        };
+        let param_type = match c_declarator {
+            Some(declarator) => Some(c_parameter_type(child, declarator, source)),
+            // Java spread parameters carry their type as an unlabelled child
+            None => child
+                .child_by_field_name("type")
+                .or_else(|| {
+                    (0..child.named_child_count())
+                        .filter_map(|j| child.named_child(j))
+                        .find(|named| named.kind().ends_with("_type") || named.kind() == "type_identifier")
+                })
+                .map(text),
+        };
+        
+        parameters.push(Parameter {
+            name,
+            param_type,
+            default_value: child
+                .child_by_field_name("default_value")
+                .or_else(|| child.child_by_field_name("value"))
+                .map(text),
+            is_variadic: VARIADIC_PARAMETER_KINDS.contains(&child.kind())
+                || (0..child.child_count()).any(|j| child.child(j).is_some_and(|token| token.kind() == "...")),
+        });
+    }
+    
+    parameters
+}
+
+/// C and C++ parameter kinds whose `declarator` field carries part of the type
+const C_PARAMETER_KINDS: &[&str] = &[
+    "parameter_declaration",
+    "optional_parameter_declaration",
+    "variadic_parameter_declaration",
+];
+
+/// Build a C or C++ parameter type from its specifiers through its declarator
+/// 
+/// Qualifiers and pointer, reference and array markers are kept and the
+/// parameter name is removed, so `const char *label` gives `const char *`.
+/// Runs of whitespace collapse to a single space.
+fn c_parameter_type(parameter: Node, declarator: Node, source: &str) -> String {
+    let start = parameter.start_byte();
+    let end = declarator.end_byte();
+    let text = match innermost_identifier(declarator) {
+        Some(name) => format!(
+            "{}{}",
+            slice_source(source, start, name.start_byte()),
+            slice_source(source, name.end_byte(), end)
+        ),
+        None => slice_source(source, start, end),
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find the first identifier at or below a node (`*buffer` -> `buffer`)
+fn innermost_identifier(node: Node) -> Option<Node> {
+    if node.kind() == "identifier" {
+        return Some(node);
+    }
+    (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .find_map(innermost_identifier)
+}
+
+/// Extract generic parameters from the `type_parameters` child, keeping bounds and defaults
+fn extract_type_parameters(node: Node, source: &str) -> Vec<String> {
+    let mut parameters = Vec::new();
+    
+    for i in 0..node.child_count() {
+        let Some(child) = node.child(i) else { continue };
+        // Go names the list `type_parameter_list`; the other grammars use `type_parameters`
+        if matches!(child.kind(), "type_parameters" | "type_parameter_list") {
+            for j in 0..child.named_child_count() {
+                if let Some(parameter) = child.named_child(j)
+                    && !parameter.kind().contains("comment") {
+                    parameters.push(slice_source(source, parameter.start_byte(), parameter.end_byte()));
+                }
+            }
+            break;
+        }
+    }
+    
+    parameters
+}
+
+/// Extract an explicit visibility modifier (`pub`, `public`, `private`, ...)
+fn extract_visibility(node: Node, source: &str) -> Option<String> {
+    for i in 0..node.child_count() {
+        let Some(child) = node.child(i) else { continue };
+        match child.kind() {
+            // Rust `pub`/`pub(crate)` and TypeScript `public`/`private`/`protected`
+            "visibility_modifier" | "accessibility_modifier" => {
+                return Some(slice_source(source, child.start_byte(), child.end_byte()));
+            }
+            // Java keeps access keywords inside `modifiers`
+            "modifiers" => {
+                for j in 0..child.child_count() {
+                    if let Some(keyword) = child.child(j)
+                        && matches!(keyword.kind(), "public" | "private" | "protected") {
+                        return Some(keyword.kind().to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Extract attributes, decorators and annotations as raw source text
+fn extract_annotations(node: Node, source: &str, language: &Language) -> Vec<String> {
+    let mut annotations = Vec::new();
+    
+    match language {
+        // Rust attributes are preceding siblings, possibly interleaved with comments
+        Language::Rust => {
+            let mut sibling = node.prev_sibling();
+            while let Some(current) = sibling {
+                match current.kind() {
+                    "attribute_item" => annotations.push(slice_source(source, current.start_byte(), current.end_byte())),
+                    "line_comment" | "block_comment" => {}
+                    _ => break,
+                }
+                sibling = current.prev_sibling();
+            }
+            annotations.reverse();
+        }
+        // Python decorators live on the wrapping `decorated_definition`
+        Language::Python => {
+            let decorated = if node.kind() == "decorated_definition" {
+                Some(node)
+            } else {
+                node.parent().filter(|parent| parent.kind() == "decorated_definition")
+            };
+            if let Some(decorated) = decorated {
+                for i in 0..decorated.child_count() {
+                    if let Some(child) = decorated.child(i)
+                        && child.kind() == "decorator" {
+                        annotations.push(slice_source(source, child.start_byte(), child.end_byte()));
+                    }
+                }
+            }
+        }
+        // Java annotations are part of the `modifiers` node
+        Language::Java => {
+            for i in 0..node.child_count() {
+                if let Some(child) = node.child(i)
+                    && child.kind() == "modifiers" {
+                    for j in 0..child.child_count() {
+                        if let Some(annotation) = child.child(j)
+                            && matches!(annotation.kind(), "marker_annotation" | "annotation") {
+                            annotations.push(slice_source(source, annotation.start_byte(), annotation.end_byte()));
+                        }
+                    }
+                }
+            }
+        }
+        // Decorators are children of the construct or of a wrapping `export`;
+        // TypeScript puts method decorators before the method in the class body
+        Language::JavaScript | Language::TypeScript => {
+            let mut sibling = node.prev_sibling();
+            while let Some(current) = sibling {
+                match current.kind() {
+                    "decorator" => annotations.push(slice_source(source, current.start_byte(), current.end_byte())),
+                    "comment" => {}
+                    _ => break,
+                }
+                sibling = current.prev_sibling();
+            }
+            annotations.reverse();
+            
+            let export = node.parent().filter(|parent| parent.kind() == "export_statement");
+            for owner in export.into_iter().chain([node]) {
+                for i in 0..owner.child_count() {
+                    if let Some(child) = owner.child(i)
+                        && child.kind() == "decorator" {
+                        annotations.push(slice_source(source, child.start_byte(), child.end_byte()));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    
+    annotations
+}
+
+/// Get the callee of the call a JavaScript or TypeScript callback is passed straight to
+fn extract_registering_call(node: Node, source: &str, language: &Language) -> Option<String> {
+    if !matches!(language, Language::JavaScript | Language::TypeScript) {
+        return None;
+    }
+    let arguments = node.parent().filter(|parent| parent.kind() == "arguments")?;
+    let callee = arguments
+        .parent()
+        .filter(|call| call.kind() == "call_expression")?
+        .child_by_field_name("function")
+        .filter(|callee| matches!(callee.kind(), "identifier" | "member_expression"))?;
+    Some(slice_source(source, callee.start_byte(), callee.end_byte()))
+}
+
+/// Get the bare name of a raw attribute, decorator or annotation
+pub(crate) fn annotation_name(annotation: &str) -> &str {
+    let trimmed = strip_annotation_delimiters(annotation);
+    let end = trimmed
+        .find(|c: char| c == '(' || c == '=' || c.is_whitespace())
+        .unwrap_or(trimmed.len());
+    &trimmed[..end]
+}
+
+/// Strip the sigil and brackets around an annotation (`#[...]`, `#![...]`, `@...`)
+fn strip_annotation_delimiters(annotation: &str) -> &str {
+    annotation
+        .trim()
+        .trim_start_matches("#!")
+        .trim_start_matches('#')
+        .trim_start_matches('[')
+        .trim_start_matches('@')
+        .trim_end_matches(']')
+}
+
+/// Split a raw attribute, decorator or annotation into its name and arguments
+fn parse_annotation(annotation: &str) -> Annotation {
+    let name = annotation_name(annotation);
+    let trimmed = strip_annotation_delimiters(annotation);
+    let rest = trimmed[name.len()..].trim();
+    
+    let arguments = if let Some(inner) = rest.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')) {
+        split_top_level_commas(inner)
+    } else if let Some(value) = rest.strip_prefix('=') {
+        vec![value.trim().to_string()]
+    } else {
+        Vec::new()
+    };
+    
+    Annotation { name: name.to_string(), arguments }
+}
+
+/// Split on commas that are not nested in brackets or string literals, trimming each part
+fn split_top_level_commas(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    
+    for (i, c) in text.char_indices() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(text[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim().to_string());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Keyword tokens reported as construct modifiers
+const MODIFIER_KEYWORDS: &[&str] = &["async", "static", "unsafe", "const"];
+
+/// Extract modifiers such as `async`, `generator`, `static`, `unsafe`, `const` and `declare`
+fn extract_modifiers(node: Node, language: &Language) -> Vec<String> {
+    let mut modifiers = Vec::new();
+    
+    for i in 0..node.child_count() {
+        let Some(child) = node.child(i) else { continue };
+        match child.kind() {
+            kind if MODIFIER_KEYWORDS.contains(&kind) => modifiers.push(kind.to_string()),
+            // `*` marks generator methods in JavaScript/TypeScript
+            "*" => modifiers.push("generator".to_string()),
+            // Rust `function_modifiers` and Java `modifiers` wrap the keywords
+            "function_modifiers" | "modifiers" => {
+                for j in 0..child.child_count() {
+                    if let Some(keyword) = child.child(j)
+                        && MODIFIER_KEYWORDS.contains(&keyword.kind()) {
+                        modifiers.push(keyword.kind().to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    // TypeScript `declare` wraps the declaration in an `ambient_declaration`
+    if *language == Language::TypeScript
+        && node.parent().is_some_and(|parent| parent.kind() == "ambient_declaration") {
+        modifiers.push("declare".to_string());
+    }
+    
+    let is_generator = match language {
+        Language::JavaScript | Language::TypeScript => node.kind().starts_with("generator_function"),
+        Language::Python => {
+            node.kind() == "function_definition"
+                && node.child_by_field_name("body").is_some_and(contains_yield)
+        }
+        _ => false,
+    };
+    if is_generator && !modifiers.iter().any(|m| m == "generator") {
+        modifiers.push("generator".to_string());
+    }
+    
+    modifiers
+}
+
+/// Check whether a Python function body yields, ignoring nested scopes
+fn contains_yield(body: Node) -> bool {
+    let mut found = false;
+    walk_tree(body, |node| {
+        found |= node.kind() == "yield";
+        !found && (node == body || !matches!(node.kind(), "function_definition" | "class_definition" | "lambda"))
+    });
+    found
+}
+
+/// Check if file is hidden
+#[cfg(not(target_arch = "wasm32"))]
+fn is_hidden_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// Check if file should be ignored based on patterns
+#[cfg(not(target_arch = "wasm32"))]
 fn should_ignore_file(path: &Path, ignore_patterns: &[String]) -> bool {
     let path_str = path.to_string_lossy();
     
@@ -612,4 +2700,1512 @@ fn should_ignore_file(path: &Path, ignore_patterns: &[String]) -> bool {
     }
     
     false
-}
\ No newline at end of file
+}
+
+/// Test-only tracking of how many files below a directory are being read or parsed at once
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod probe {
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    
+    /// Number of operations in flight, the most seen at once and the total started
+    #[derive(Default)]
+    pub(super) struct Concurrency {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+        total: AtomicUsize,
+    }
+    
+    impl Concurrency {
+        pub(super) fn peak(&self) -> usize {
+            self.peak.load(Ordering::SeqCst)
+        }
+        
+        pub(super) fn total(&self) -> usize {
+            self.total.load(Ordering::SeqCst)
+        }
+    }
+    
+    /// Counters for the files below one watched directory
+    #[derive(Default)]
+    pub(super) struct Probe {
+        pub(super) reads: Concurrency,
+        pub(super) parses: Concurrency,
+    }
+    
+    static WATCHED: Mutex<Vec<(PathBuf, Arc<Probe>)>> = Mutex::new(Vec::new());
+    
+    /// Start counting operations on files below `root`, replacing earlier probes of it
+    pub(super) fn watch(root: &Path) -> Arc<Probe> {
+        let probe = Arc::new(Probe::default());
+        WATCHED.lock().unwrap().push((root.to_path_buf(), probe.clone()));
+        probe
+    }
+    
+    /// An operation on a watched file, counted as in flight until dropped
+    pub(super) struct Active {
+        probe: Arc<Probe>,
+        counter: fn(&Probe) -> &Concurrency,
+    }
+    
+    impl Drop for Active {
+        fn drop(&mut self) {
+            (self.counter)(&self.probe).active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+    
+    fn enter(file_path: &str, counter: fn(&Probe) -> &Concurrency) -> Option<Active> {
+        let probe = WATCHED
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(root, _)| Path::new(file_path).starts_with(root))
+            .map(|(_, probe)| probe.clone())?;
+        let concurrency = counter(&probe);
+        concurrency.total.fetch_add(1, Ordering::SeqCst);
+        let now = concurrency.active.fetch_add(1, Ordering::SeqCst) + 1;
+        concurrency.peak.fetch_max(now, Ordering::SeqCst);
+        // Stay in flight long enough for operations that are allowed to overlap to do so
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        Some(Active { probe, counter })
+    }
+    
+    pub(super) fn reading(file_path: &str) -> Option<Active> {
+        enter(file_path, |probe| &probe.reads)
+    }
+    
+    pub(super) fn parsing(file_path: &str) -> Option<Active> {
+        enter(file_path, |probe| &probe.parses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstructKind, Language};
+
+    #[test]
+    fn test_parent_child_relationships() {
+        // Simple Python code with nested structure
+        let source = "class TestClass:\n    def test_method(self):\n        pass";
+        
+        // Create a simple tree-sitter parser for testing
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        
+        let tree = parser.parse(source, None).unwrap();
+        let constructs = extract_constructs(&tree, source, &Language::Python, &ParseOptions::default());
+        
+        // Find class and method constructs
+        let class_construct = constructs.iter().find(|c| c.node_type == "class_definition");
+        let method_construct = constructs.iter().find(|c| c.node_type == "function_definition");
+        
+        assert!(class_construct.is_some(), "Should find class construct");
+        assert!(method_construct.is_some(), "Should find method construct");
+        
+        let method = method_construct.unwrap();
+        
+        // Check that method has a parent
+        assert!(method.parent.is_some(), "Method should have a parent");
+        
+        if let Some(parent) = &method.parent {
+            assert_eq!(parent.node_type, "class_definition", "Method's parent should be the class");
+        }
+        
+        // Check that class has children
+        let class = class_construct.unwrap();
+        assert!(!class.children.is_empty(), "Class should have children");
+        
+        let child_method = class.children.iter().find(|c| c.node_type == "function_definition");
+        assert!(child_method.is_some(), "Class should contain the method as a child");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_parse_reader() {
+        let source = "struct Point {\n    x: i32,\n}\n\nfn origin() -> Point {\n    Point { x: 0 }\n}\n";
+        let reader = std::io::Cursor::new(source.as_bytes().to_vec());
+        
+        let parsed = parse_reader(reader, Language::Rust, true).await.unwrap();
+        
+        assert_eq!(parsed.file_path, "<stdin>");
+        assert_eq!(parsed.file_size_bytes, source.len());
+        assert!(parsed.syntax_tree.is_some());
+        assert!(parsed.constructs.iter().any(|c| c.node_type == "struct_item"));
+        assert!(parsed.constructs.iter().any(|c| c.node_type == "function_item"));
+        
+        // The retained source lets queries run without a file on disk
+        let matches = crate::search_by_query(&parsed, "(function_item name: (identifier) @name)").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].source_code, "origin");
+    }
+
+    #[test]
+    fn test_multibyte_source_slicing() {
+        let source = "def 你好_🎉(名前):\n    return \"こんにちは 🌍\"\n\nclass 猫:\n    pass\n";
+        
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        
+        let tree = parser.parse(source, None).unwrap();
+        let constructs = extract_constructs(&tree, source, &Language::Python, &ParseOptions::default());
+        
+        let function = constructs.iter().find(|c| c.node_type == "function_definition").unwrap();
+        assert!(function.source_code.contains("こんにちは 🌍"));
+        
+        let class = constructs.iter().find(|c| c.node_type == "class_definition").unwrap();
+        assert_eq!(class.name.as_deref(), Some("猫"));
+        
+        // Offsets inside a multibyte character or past the end must not panic
+        let emoji_start = source.find('🎉').unwrap();
+        assert!(!slice_source(source, emoji_start + 1, emoji_start + 3).is_empty());
+        assert_eq!(slice_source(source, source.len() - 5, source.len() + 10), "pass\n");
+        assert_eq!(slice_source(source, source.len() + 1, source.len() + 2), "");
+    }
+
+    #[test]
+    fn test_node_type_allowlist() {
+        let source = "import os\n\nclass Greeter:\n    def greet(self):\n        message = 'hi'\n        return message\n\ndef main():\n    pass\n";
+        
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        
+        let options = ParseOptions {
+            node_type_allowlist: Some(vec!["function_definition".to_string()]),
+            ..Default::default()
+        };
+        let constructs = extract_constructs(&tree, source, &Language::Python, &options);
+        
+        // Nested methods are still found even though their class is not extracted
+        assert_eq!(constructs.len(), 2);
+        assert!(constructs.iter().all(|c| c.node_type == "function_definition"));
+        
+        let all = extract_constructs(&tree, source, &Language::Python, &ParseOptions::default());
+        assert!(all.iter().any(|c| c.node_type == "class_definition"));
+        assert!(all.iter().any(|c| c.node_type == "import_statement"));
+    }
+
+    #[test]
+    fn test_skip_non_containers() {
+        let source = "use std::fmt;\n\nmod geometry {\n    pub struct Point { x: i32, y: i32 }\n\n    impl Point {\n        pub fn norm(&self) -> i32 {\n            let squares = [self.x * self.x, self.y * self.y];\n            squares.iter().map(|v| v + 1 - 1).fold(0, |acc, v| acc + v * (1 + 0) - 0)\n        }\n    }\n}\n\nfn main() {\n    fn helper() {}\n    let total = (1..100).map(|i| i * 2).filter(|i| i % 3 == 0).sum::<i32>();\n    helper();\n}\n";
+        
+        let extract = |source: &str, language: Language, allowlist: Option<&[&str]>| {
+            let mut parser = Parser::new();
+            parser.set_language(&crate::languages::get_tree_sitter_language(&language).unwrap()).unwrap();
+            let tree = parser.parse(source, None).unwrap();
+            
+            let run = |skip_non_containers: bool| {
+                let options = ParseOptions {
+                    skip_non_containers,
+                    node_type_allowlist: allowlist.map(|kinds| kinds.iter().map(|kind| kind.to_string()).collect()),
+                    ..ParseOptions::default()
+                };
+                let mut context = ExtractionContext::new(source, &language, &options);
+                let constructs = extract_constructs_with_context(&tree, &mut context);
+                let summary: Vec<_> = constructs.into_iter().map(|c| (c.node_type, c.name, c.start_byte, c.end_byte)).collect();
+                (summary, context.visited_nodes)
+            };
+            let (full, full_visited) = run(false);
+            let (pruned, pruned_visited) = run(true);
+            assert_eq!(pruned, full, "{:?}", language);
+            (full, full_visited, pruned_visited)
+        };
+        
+        let (full, full_visited, pruned_visited) = extract(source, Language::Rust, None);
+        assert!(full.iter().any(|(_, name, _, _)| name.as_deref() == Some("helper")));
+        assert!(pruned_visited < full_visited / 2, "{} vs {}", pruned_visited, full_visited);
+        
+        // Macros invoked as statements, bound by `let`, passed as arguments or returned
+        let macros = "fn report(items: &[i32]) -> Vec<i32> {\n    println!(\"{}\", items.len());\n    let copy = vec![1, 2];\n    consume(format!(\"{:?}\", copy));\n    return vec![3];\n}\n";
+        let (full, _, _) = extract(macros, Language::Rust, None);
+        let names: Vec<_> = full.iter().filter(|c| c.0 == "macro_invocation").filter_map(|c| c.1.as_deref()).collect();
+        assert_eq!(names, ["println", "vec", "format", "vec"]);
+        
+        // Lambdas that are assigned, passed as keyword arguments or returned, also when assignments aren't extracted
+        let lambdas = "square = lambda x: x * x\nitems.sort(key=lambda item: item.name)\n\ndef make():\n    return lambda: 0\n";
+        let (full, _, _) = extract(lambdas, Language::Python, None);
+        assert_eq!(full.iter().filter(|c| c.0 == "lambda").count(), 3);
+        let (full, _, _) = extract(lambdas, Language::Python, Some(&["lambda"]));
+        assert_eq!(full.iter().filter_map(|c| c.1.as_deref()).collect::<Vec<_>>(), ["square"]);
+        assert_eq!(full.len(), 3);
+        
+        // Callbacks passed to calls, which `registered_by` is built from
+        let callbacks = "describe('parser', () => {\n    it('parses', function () {\n        return run(() => 1);\n    });\n});\n";
+        for language in [Language::JavaScript, Language::TypeScript] {
+            let (full, _, _) = extract(callbacks, language, None);
+            let kinds: Vec<_> = full.iter().map(|c| c.0.as_str()).collect();
+            assert_eq!(kinds, ["arrow_function", "function_expression", "arrow_function"]);
+        }
+    }
+    
+    #[test]
+    fn test_construct_columns() {
+        let source = "class Shape:\n    def area(self):\n        return 0\n";
+        
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let constructs = extract_constructs(&tree, source, &Language::Python, &ParseOptions::default());
+        
+        let class = constructs.iter().find(|c| c.node_type == "class_definition").unwrap();
+        assert_eq!(class.start_column, 0);
+        
+        let method = constructs.iter().find(|c| c.node_type == "function_definition").unwrap();
+        assert_eq!((method.start_line, method.start_column), (2, 4));
+        assert_eq!((method.end_line, method.end_column), (3, "        return 0".len()));
+    }
+
+    #[test]
+    fn test_sync_parsing_without_runtime() {
+        assert!(tokio::runtime::Handle::try_current().is_err(), "No tokio runtime should be present");
+        
+        let parsed = parse_string_sync("def greet(name):\n    return name\n", Language::Python).unwrap();
+        assert_eq!(parsed.file_path, "<string>");
+        assert_eq!(parsed.constructs.len(), 1);
+        assert_eq!(parsed.constructs[0].name.as_deref(), Some("greet"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_parse_file_sync_without_runtime() {
+        assert!(tokio::runtime::Handle::try_current().is_err(), "No tokio runtime should be present");
+        
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("tree_parser_sync_test.rs");
+        std::fs::write(&file_path, "pub struct Config;\n\nfn load() -> Config { Config }\n").unwrap();
+        
+        let parsed = parse_file_sync(&file_path.to_string_lossy(), Language::Rust).unwrap();
+        assert_eq!(parsed.relative_path, "tree_parser_sync_test.rs");
+        assert!(parsed.constructs.iter().any(|c| c.node_type == "struct_item"));
+        assert!(parsed.constructs.iter().any(|c| c.name.as_deref() == Some("load")));
+        
+        std::fs::remove_file(&file_path).unwrap();
+        assert!(matches!(parse_file_sync(&file_path.to_string_lossy(), Language::Rust), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_max_depth_stops_descent() {
+        // Deeply nested lists followed by deeply nested functions
+        let depth = 2000;
+        let mut source = format!("value = {}{}\n", "[".repeat(depth), "]".repeat(depth));
+        for level in 0..10 {
+            source.push_str(&format!("{}def level_{}():\n", "    ".repeat(level), level));
+        }
+        source.push_str(&format!("{}pass\n", "    ".repeat(10)));
+        
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(&source, None).unwrap();
+        
+        let options = ParseOptions {
+            max_depth: Some(12),
+            ..Default::default()
+        };
+        let mut context = ExtractionContext::new(&source, &Language::Python, &options);
+        let constructs = extract_constructs_with_context(&tree, &mut context);
+        
+        assert!(context.depth_limit_reached);
+        assert!(constructs.iter().any(|c| c.name.as_deref() == Some("level_0")));
+        assert!(!constructs.iter().any(|c| c.name.as_deref() == Some("level_9")));
+        
+        // A generous limit extracts every nested function without flagging
+        let functions_only = &source[source.find("def level_0").unwrap()..];
+        let tree = parser.parse(functions_only, None).unwrap();
+        let options = ParseOptions {
+            max_depth: Some(100),
+            ..Default::default()
+        };
+        let mut context = ExtractionContext::new(functions_only, &Language::Python, &options);
+        let constructs = extract_constructs_with_context(&tree, &mut context);
+        
+        assert!(!context.depth_limit_reached);
+        assert_eq!(constructs.iter().filter(|c| c.node_type == "function_definition").count(), 10);
+    }
+
+    #[test]
+    fn test_extraction_handles_deep_nesting() {
+        // Deep enough to overflow a test thread's stack with one frame per level
+        let depth = 50_000;
+        let source = format!(
+            "def numbers():\n    value = {}lambda: 1{}\n    yield value\n",
+            "[".repeat(depth),
+            "]".repeat(depth)
+        );
+        
+        let mut parser = Parser::new();
+        let language = crate::languages::get_tree_sitter_language(&Language::Python).unwrap();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(&source, None).unwrap();
+        
+        let constructs = extract_constructs(&tree, &source, &Language::Python, &ParseOptions::default());
+        let kinds: Vec<&str> = constructs.iter().map(|c| c.node_type.as_str()).collect();
+        assert_eq!(kinds, ["function_definition", "assignment", "lambda"]);
+        assert_eq!(constructs[2].depth, 2);
+        
+        let body = tree.root_node().child(0).and_then(|function| function.child_by_field_name("body")).unwrap();
+        assert!(contains_yield(body));
+    }
+    
+    #[test]
+    fn test_function_modifiers() {
+        let python = "async def fetch():\n    pass\n\ndef numbers():\n    def inner():\n        pass\n    yield 1\n\ndef plain():\n    return lambda: (yield)\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let modifiers = |name: &str| {
+            parsed.constructs.iter()
+                .find(|c| c.name.as_deref() == Some(name))
+                .map(|c| c.metadata.modifiers.clone())
+                .unwrap()
+        };
+        assert_eq!(modifiers("fetch"), vec!["async"]);
+        assert_eq!(modifiers("numbers"), vec!["generator"]);
+        assert!(modifiers("inner").is_empty());
+        assert!(modifiers("plain").is_empty());
+        
+        let rust = "pub async fn load() {}\nunsafe fn raw() {}\nconst fn zero() -> u8 { 0 }\nfn normal() {}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let modifiers: Vec<_> = parsed.constructs.iter().map(|c| c.metadata.modifiers.clone()).collect();
+        assert_eq!(modifiers, vec![vec!["async"], vec!["unsafe"], vec!["const"], vec![]]);
+        
+        let javascript = "function* ids() { yield 1; }\nclass Api { static async get() {} *pages() {} }\n";
+        let parsed = parse_string_sync(javascript, Language::JavaScript).unwrap();
+        let modifiers: Vec<_> = parsed.constructs.iter()
+            .filter(|c| c.node_type != "class_declaration")
+            .map(|c| c.metadata.modifiers.clone())
+            .collect();
+        assert_eq!(modifiers, vec![vec!["generator"], vec!["static", "async"], vec!["generator"]]);
+    }
+
+    #[test]
+    fn test_visibility_and_annotations() {
+        let rust = "#[derive(Debug)]\n/// Docs\n#[repr(C)]\npub struct Point;\n\n#[test]\npub(crate) fn check() {}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let point = &parsed.constructs[0];
+        assert_eq!(point.metadata.visibility.as_deref(), Some("pub"));
+        assert_eq!(point.metadata.annotations, vec!["#[derive(Debug)]", "#[repr(C)]"]);
+        let check = &parsed.constructs[1];
+        assert_eq!(check.metadata.visibility.as_deref(), Some("pub(crate)"));
+        assert_eq!(check.metadata.annotations, vec!["#[test]"]);
+        
+        let python = "class Api:\n    @staticmethod\n    @app.route('/x')\n    def handler():\n        pass\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let handler = parsed.constructs.iter().find(|c| c.node_type == "function_definition").unwrap();
+        assert_eq!(handler.metadata.annotations, vec!["@staticmethod", "@app.route('/x')"]);
+        assert_eq!(handler.metadata.visibility, None);
+        
+        // Only decorators count; callbacks just record the call they are passed to
+        let typescript = "@Component({})\nclass View {\n  @HostListener('click') onClick() {\n    [1].map(x => x);\n  }\n}\n@sealed export class Base {}\n";
+        let parsed = parse_string_sync(typescript, Language::TypeScript).unwrap();
+        let annotations = |name: &str| {
+            parsed.constructs.iter().find(|c| c.name.as_deref() == Some(name)).unwrap().metadata.annotations.clone()
+        };
+        assert_eq!(annotations("View"), vec!["@Component({})"]);
+        assert_eq!(annotations("onClick"), vec!["@HostListener('click')"]);
+        assert_eq!(annotations("Base"), vec!["@sealed"]);
+        let callback = parsed.constructs.iter().find(|c| c.node_type == "arrow_function").unwrap();
+        assert!(callback.metadata.annotations.is_empty());
+        assert_eq!(callback.metadata.registered_by.as_deref(), Some("[1].map"));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_parse_duration_recorded() {
+        let source: String = (0..5000)
+            .map(|i| format!("def function_{i}(a, b):\n    return [a * b for _ in range({i})]\n\n"))
+            .collect();
+        let started = std::time::Instant::now();
+        let parsed = parse_string_sync(&source, Language::Python).unwrap();
+        // The parse can finish within a millisecond, so bound it by the time measured around it
+        assert!(parsed.parse_duration_ms <= started.elapsed().as_millis() as u64);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_per_file_timeout() {
+        let source: String = (0..50_000)
+            .map(|i| format!("def function_{i}(a, b):\n    return [a * b for _ in range({i})]\n\n"))
+            .collect();
+        let options = ParseOptions {
+            per_file_timeout_ms: Some(1),
+            ..ParseOptions::default()
+        };
+        
+        let error = parse_source(source, STRING_PATH, STRING_PATH.to_string(), Language::Python, false, &options).unwrap_err();
+        assert!(matches!(error, Error::Timeout(1)));
+        assert!(matches!(error.error_type(), crate::ErrorType::Timeout));
+        
+        let options = ParseOptions {
+            per_file_timeout_ms: Some(60_000),
+            ..ParseOptions::default()
+        };
+        let parsed = parse_source("def quick():\n    pass\n".to_string(), STRING_PATH, STRING_PATH.to_string(), Language::Python, false, &options).unwrap();
+        assert_eq!(parsed.constructs.len(), 1);
+        
+        // A parse that fails before the deadline is not a timeout
+        let mut parser = Parser::new();
+        assert!(matches!(parse_with_timeout(&mut parser, "def quick(): pass", Some(60_000)), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    #[cfg(all(feature = "gzip", not(target_arch = "wasm32")))]
+    fn test_parse_gzip_file() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+        
+        let source = "class Greeter:\n    def greet(self, name):\n        return f'Hello {name}'\n\ndef main():\n    Greeter().greet('world')\n";
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plain_path = temp_dir.path().join("tree_parser_gzip_test.py");
+        let gz_path = temp_dir.path().join("tree_parser_gzip_test.py.gz");
+        std::fs::write(&plain_path, source).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(source.as_bytes()).unwrap();
+        std::fs::write(&gz_path, encoder.finish().unwrap()).unwrap();
+        
+        let gz_path_str = gz_path.to_string_lossy().to_string();
+        assert_eq!(detect_language_by_extension(&gz_path_str), Some(Language::Python));
+        
+        let plain = parse_file_sync(&plain_path.to_string_lossy(), Language::Python).unwrap();
+        let compressed = parse_file_sync(&gz_path_str, Language::Python).unwrap();
+        let summary = |parsed: &ParsedFile| {
+            parsed.constructs.iter()
+                .map(|c| (c.node_type.clone(), c.name.clone(), c.start_line, c.end_line))
+                .collect::<Vec<_>>()
+        };
+        assert!(!plain.constructs.is_empty());
+        assert_eq!(summary(&plain), summary(&compressed));
+        assert_eq!(compressed.file_size_bytes, source.len());
+    }
+
+    #[test]
+    fn test_rust_macros() {
+        let source = "macro_rules! my_macro {\n    ($x:expr) => { $x };\n}\n\nfn main() {\n    my_macro!(1);\n    std::println!(\"done\");\n}\n";
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        
+        let definition = parsed.constructs.iter().find(|c| c.node_type == "macro_definition").unwrap();
+        assert_eq!(definition.name.as_deref(), Some("my_macro"));
+        
+        let invocations: Vec<_> = parsed.constructs.iter()
+            .filter(|c| c.node_type == "macro_invocation")
+            .map(|c| c.name.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(invocations, vec!["my_macro", "std::println"]);
+    }
+
+    #[test]
+    fn test_python_async_and_lambda_functions() {
+        let source = "async def fetch(url):\n    return await get(url)\n\nsquare = lambda x: x * x\nitems.sort(key=lambda item: item.name)\n";
+        let parsed = parse_string_sync(source, Language::Python).unwrap();
+        
+        let functions = crate::search_functions(&parsed, None);
+        let names: Vec<_> = functions.iter().map(|c| (c.node_type.as_str(), c.name.as_deref())).collect();
+        assert_eq!(names, vec![
+            ("function_definition", Some("fetch")),
+            ("lambda", Some("square")),
+            ("lambda", None),
+        ]);
+        assert!(crate::is_async(&functions[0]));
+    }
+
+    #[test]
+    fn test_flatten_constructs_option() {
+        let source = "class Outer:\n    def method(self):\n        pass\n\n    class Inner:\n        def nested(self):\n            pass\n\ndef top():\n    pass\n";
+        let parse = |options: &ParseOptions| {
+            parse_source(source.to_string(), STRING_PATH, STRING_PATH.to_string(), Language::Python, false, options).unwrap()
+        };
+        
+        let flat = parse(&ParseOptions::default());
+        let names: Vec<_> = flat.constructs.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["Outer", "method", "Inner", "nested", "top"]);
+        
+        let nested = parse(&ParseOptions {
+            flatten_constructs: false,
+            ..ParseOptions::default()
+        });
+        let names: Vec<_> = nested.constructs.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["Outer", "top"]);
+        assert_eq!(nested.constructs[0].children.len(), 2);
+        assert_eq!(nested.constructs[0].children[1].children[0].name.as_deref(), Some("nested"));
+        
+        // Searches see each construct once in either mode
+        for parsed in [flat, nested] {
+            assert_eq!(parsed.all_constructs().len(), 5);
+            let functions = crate::search_by_node_type(&parsed, "function_definition", None);
+            let names: Vec<_> = functions.iter().filter_map(|c| c.name.as_deref()).collect();
+            assert_eq!(names, vec!["method", "nested", "top"]);
+        }
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_arch = "wasm32")))]
+    fn test_follow_symlinks_option() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path();
+        let root = base.join("project");
+        let shared = base.join("shared");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(root.join("main.py"), "def main():\n    pass\n").unwrap();
+        std::fs::write(shared.join("util.py"), "def util():\n    pass\n").unwrap();
+        std::os::unix::fs::symlink(&shared, root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(root.join("main.py"), root.join("alias.py")).unwrap();
+        // A loop back to the project root must not break or hang the walk
+        std::os::unix::fs::symlink(&root, root.join("loop")).unwrap();
+        
+        let file_names = |follow_symlinks: bool| {
+            let options = ParseOptions {
+                follow_symlinks,
+                ..ParseOptions::default()
+            };
+            let mut names: Vec<_> = collect_files(&root, &options).unwrap()
+                .iter()
+                .map(|path| path.strip_prefix(&root).unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        
+        assert_eq!(file_names(false), vec!["main.py"]);
+        assert_eq!(file_names(true), vec!["alias.py", "linked/util.py", "main.py"]);
+    }
+
+    #[test]
+    fn test_struct_fields_as_children() {
+        let rust = "pub struct Point {\n    pub x: i32,\n    label: Option<String>,\n}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let fields: Vec<_> = parsed.constructs[0].children.iter()
+            .map(|f| (f.name.as_deref().unwrap(), f.metadata.field_type.as_deref().unwrap(), f.metadata.visibility.as_deref()))
+            .collect();
+        assert_eq!(fields, vec![("x", "i32", Some("pub")), ("label", "Option<String>", None)]);
+        
+        let python = "class Config:\n    retries: int = 3\n    name = 'default'\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let fields: Vec<_> = parsed.constructs[0].children.iter()
+            .map(|f| (f.name.as_deref().unwrap(), f.metadata.field_type.as_deref()))
+            .collect();
+        assert_eq!(fields, vec![("retries", Some("int")), ("name", None)]);
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_java_fields_as_children() {
+        let java = "class Account {\n    private long balance = 0;\n    String owner, nickname;\n}\n";
+        let parsed = parse_string_sync(java, Language::Java).unwrap();
+        let fields: Vec<_> = parsed.constructs[0].children.iter()
+            .map(|f| (f.name.as_deref().unwrap(), f.metadata.field_type.as_deref().unwrap(), f.metadata.visibility.as_deref()))
+            .collect();
+        assert_eq!(fields, vec![("balance", "long", Some("private")), ("owner", "String", None)]);
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "git", not(target_arch = "wasm32")))]
+    async fn test_parse_git_blob() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path();
+        let repository = git2::Repository::init(repo_dir).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        
+        // Two commits changing the same file
+        let mut parent = None;
+        for source in ["def old_name():\n    pass\n", "def new_name():\n    pass\n"] {
+            std::fs::create_dir_all(repo_dir.join("pkg")).unwrap();
+            std::fs::write(repo_dir.join("pkg/app.py"), source).unwrap();
+            let mut index = repository.index().unwrap();
+            index.add_path(Path::new("pkg/app.py")).unwrap();
+            let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<_> = parent.iter().collect();
+            let commit = repository.commit(Some("HEAD"), &signature, &signature, "update", &tree, &parents).unwrap();
+            parent = Some(repository.find_commit(commit).unwrap());
+        }
+        
+        let repo_path = repo_dir.to_string_lossy().to_string();
+        let previous = parse_git_blob(&repo_path, "HEAD~1", "pkg/app.py", Language::Python).await.unwrap();
+        assert_eq!(previous.file_path, "pkg/app.py");
+        assert_eq!(previous.constructs[0].name.as_deref(), Some("old_name"));
+        
+        let current = parse_git_blob(&repo_path, "HEAD", "pkg/app.py", Language::Python).await.unwrap();
+        assert_eq!(current.constructs[0].name.as_deref(), Some("new_name"));
+        
+        let missing = parse_git_blob(&repo_path, "HEAD", "pkg/missing.py", Language::Python).await;
+        assert!(matches!(missing, Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let source = "def main():\n    return 1\n";
+        let first = parse_string_sync(source, Language::Python).unwrap();
+        let second = parse_string_sync(source, Language::Python).unwrap();
+        assert_eq!(first.content_hash, second.content_hash);
+        
+        let changed = parse_string_sync("def main():\n    return 2\n", Language::Python).unwrap();
+        assert_ne!(first.content_hash, changed.content_hash);
+    }
+
+    #[test]
+    fn test_leading_comment_documentation() {
+        let rust = "// Unrelated header\n\n/// Adds two numbers.\n/// Wraps on overflow.\n#[inline]\nfn add(a: u8, b: u8) -> u8 { a.wrapping_add(b) }\n\nfn bare() {}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        assert_eq!(parsed.constructs[0].metadata.documentation.as_deref(), Some("Adds two numbers.\nWraps on overflow."));
+        assert_eq!(parsed.constructs[1].metadata.documentation, None);
+        
+        let python = "x = 1  # trailing note\n# Loads the config.\n@cached\ndef load():\n    pass\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let load = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("load")).unwrap();
+        assert_eq!(load.metadata.documentation.as_deref(), Some("Loads the config."));
+    }
+
+    #[test]
+    fn test_parsed_annotations() {
+        let rust = "#[derive(Debug, Clone, Serialize)]\n#[serde(rename_all = \"camelCase\", tag = \"kind, type\")]\n#[doc = \"A point\"]\n#[non_exhaustive]\nstruct Point { x: i32 }\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let point = &parsed.constructs[0];
+        
+        let annotation = |name: &str, arguments: &[&str]| Annotation {
+            name: name.to_string(),
+            arguments: arguments.iter().map(|a| a.to_string()).collect(),
+        };
+        assert_eq!(point.metadata.parsed_annotations, vec![
+            annotation("derive", &["Debug", "Clone", "Serialize"]),
+            annotation("serde", &["rename_all = \"camelCase\"", "tag = \"kind, type\""]),
+            annotation("doc", &["\"A point\""]),
+            annotation("non_exhaustive", &[]),
+        ]);
+        assert!(point.metadata.parsed_annotations.iter()
+            .any(|a| a.name == "derive" && a.arguments.iter().any(|arg| arg == "Serialize")));
+    }
+    
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_parsed_java_annotations() {
+        let java = "class Api {\n    @Override\n    @RequestMapping(path = \"/x\", method = {GET, POST})\n    public String handle() { return \"\"; }\n}\n";
+        let parsed = parse_string_sync(java, Language::Java).unwrap();
+        let handle = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("handle")).unwrap();
+        
+        let annotations: Vec<_> = handle.metadata.parsed_annotations.iter()
+            .map(|a| (a.name.as_str(), a.arguments.clone()))
+            .collect();
+        assert_eq!(annotations, vec![
+            ("Override", vec![]),
+            ("RequestMapping", vec!["path = \"/x\"".to_string(), "method = {GET, POST}".to_string()]),
+        ]);
+    }
+    
+    #[test]
+    fn test_body_range() {
+        let source = "use std::fmt;\n\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        
+        let import = parsed.constructs.iter().find(|c| c.node_type == "use_declaration").unwrap();
+        assert_eq!(import.body_range, None);
+        
+        let add = parsed.constructs.iter().find(|c| c.node_type == "function_item").unwrap();
+        let (start, end) = add.body_range.unwrap();
+        assert_eq!(&source[start..end], "{\n    a + b\n}");
+        assert_eq!(&source[add.start_byte..start], "pub fn add(a: i32, b: i32) -> i32 ");
+        assert_eq!(end, add.end_byte);
+    }
+    
+    #[test]
+    #[cfg(feature = "lua")]
+    fn test_lua_module_functions() {
+        let lua = "local M = {}\n\nfunction M.greet(name)\n  return \"hi \" .. name\nend\n\nfunction M:reset() end\n\nlocal function helper() end\n\nM.add = function(a, b) return a + b end\n\nreturn M\n";
+        let parsed = parse_string_sync(lua, Language::Lua).unwrap();
+        
+        let names: Vec<_> = parsed.constructs.iter().map(|c| (c.node_type.as_str(), c.name.as_deref())).collect();
+        assert_eq!(names, vec![
+            ("variable_declaration", Some("M")),
+            ("function_declaration", Some("M.greet")),
+            ("function_declaration", Some("M:reset")),
+            ("function_declaration", Some("helper")),
+            ("function_definition", Some("M.add")),
+        ]);
+        
+        let add = &parsed.constructs[4];
+        let parameters: Vec<_> = add.metadata.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(parameters, vec!["a", "b"]);
+    }
+    
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_go_comment_documentation() {
+        let go = "package main\n\n// Foo does the thing.\n// It is idempotent.\nfunc Foo() {}\n\n// Stale comment\n\nfunc Bar() {}\n";
+        let parsed = parse_string_sync(go, Language::Go).unwrap();
+        let foo = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("Foo")).unwrap();
+        assert_eq!(foo.metadata.documentation.as_deref(), Some("Foo does the thing.\nIt is idempotent."));
+        let bar = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("Bar")).unwrap();
+        assert_eq!(bar.metadata.documentation, None);
+    }
+
+    #[test]
+    fn test_language_specific_name_kinds() {
+        let rust = "struct Config { port: u16 }
+enum Mode { Fast }
+trait Run {}
+impl Run for Config {}
+";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let names: Vec<_> = parsed
+            .constructs
+            .iter()
+            .filter(|c| c.node_type != "field_declaration")
+            .map(|c| (c.node_type.as_str(), c.name.as_deref()))
+            .collect();
+        assert_eq!(names, vec![
+            ("struct_item", Some("Config")),
+            ("enum_item", Some("Mode")),
+            ("trait_item", Some("Run")),
+            ("impl_item", Some("Config")),
+        ]);
+        
+        let js = "class Cart {\n  total() { return 0; }\n}\n";
+        let parsed = parse_string_sync(js, Language::JavaScript).unwrap();
+        let method = parsed.constructs.iter().find(|c| c.node_type == "method_definition").unwrap();
+        assert_eq!(method.name.as_deref(), Some("total"));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_join_bounded_respects_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = join_bounded((0..40).collect(), 3, |i: usize| {
+            let (active, peak) = (&active, &peak);
+            async move {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+                i * 2
+            }
+        })
+        .await;
+        
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(results, (0..40).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_relative_path_keeps_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("net").join("http")).unwrap();
+        std::fs::write(root.join("lib.rs"), "mod net;\n").unwrap();
+        std::fs::write(root.join("net").join("mod.rs"), "pub mod http;\n").unwrap();
+        std::fs::write(root.join("net").join("http").join("mod.rs"), "pub fn get() {}\n").unwrap();
+        
+        let project = parse_directory(&root.to_string_lossy(), ParseOptions::default()).await.unwrap();
+        let mut relative: Vec<_> = project.files.iter().map(|f| f.relative_path.replace('\\', "/")).collect();
+        relative.sort();
+        assert_eq!(relative, vec!["lib.rs", "net/http/mod.rs", "net/mod.rs"]);
+        
+        let single = parse_file(&root.join("net").join("mod.rs").to_string_lossy(), Language::Rust).await.unwrap();
+        assert_eq!(single.relative_path, "mod.rs");
+    }
+
+    #[test]
+    fn test_construct_kind() {
+        let python = parse_string_sync("import os\nclass A:\n    def m(self):\n        pass\n", Language::Python).unwrap();
+        let kinds: Vec<_> = python.constructs.iter().map(|c| (c.node_type.as_str(), c.kind)).collect();
+        assert_eq!(kinds, vec![
+            ("import_statement", ConstructKind::Import),
+            ("class_definition", ConstructKind::Class),
+            ("function_definition", ConstructKind::Function),
+        ]);
+        
+        let rust = parse_string_sync("fn f() {}\nstruct S;\ntrait T { fn g(&self); }\n", Language::Rust).unwrap();
+        let kinds: Vec<_> = rust.constructs.iter().map(|c| (c.node_type.as_str(), c.kind)).collect();
+        assert_eq!(kinds, vec![
+            ("function_item", ConstructKind::Function),
+            ("struct_item", ConstructKind::Struct),
+            ("trait_item", ConstructKind::Interface),
+            ("function_signature_item", ConstructKind::Method),
+        ]);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_parse_directory_many_files_bounded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for i in 0..2_000 {
+            std::fs::write(root.join(format!("m{}.py", i)), format!("def f{}():\n    pass\n", i)).unwrap();
+        }
+        
+        let options = ParseOptions {
+            max_concurrent_files: 4,
+            language_detection: LanguageDetection::Combined,
+            dedupe_identical: false,
+            ..ParseOptions::default()
+        };
+        let probe = probe::watch(root);
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        
+        assert!(project.error_files.is_empty(), "{:?}", project.error_files.first());
+        assert_eq!(project.files.len(), 2_000);
+        // Detection and parsing each open the file, always under the same limit
+        assert!(probe.reads.peak() <= 4, "{} files open at once", probe.reads.peak());
+        assert!(probe.reads.peak() > 1);
+    }
+
+    #[test]
+    fn test_rust_type_parameters() {
+        let rust = "fn pick<'a, T: Clone + Send, const N: usize>(items: &'a [T; N]) -> T { items[0].clone() }\nfn plain() {}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        assert_eq!(parsed.constructs[0].metadata.type_parameters, vec!["'a", "T: Clone + Send", "const N: usize"]);
+        assert!(parsed.constructs[1].metadata.type_parameters.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_java_type_parameters() {
+        let java = "class Registry<E extends Comparable<E>, K> {\n    <T> void put(T value) {}\n}\n";
+        let parsed = parse_string_sync(java, Language::Java).unwrap();
+        assert_eq!(parsed.constructs[0].metadata.type_parameters, vec!["E extends Comparable<E>", "K"]);
+        let method = crate::search_by_node_type(&parsed, "method_declaration", None);
+        assert_eq!(method[0].metadata.type_parameters, vec!["T"]);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_list_parseable_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("pkg")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("main.py"), "def main():\n    pass\n").unwrap();
+        std::fs::write(root.join("lib.rs"), "fn lib() {}\n").unwrap();
+        std::fs::write(root.join("pkg").join("util.py"), "def util():\n    pass\n").unwrap();
+        std::fs::write(root.join("node_modules").join("dep.js"), "function dep() {}\n").unwrap();
+        
+        let root_str = root.to_string_lossy().to_string();
+        let relative = |files: Vec<PathBuf>| {
+            let mut names: Vec<_> = files
+                .iter()
+                .map(|path| path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect();
+            names.sort();
+            names
+        };
+        
+        let options = ParseOptions {
+            ignore_patterns: vec!["node_modules".to_string()],
+            ..ParseOptions::default()
+        };
+        assert_eq!(relative(list_parseable_files(&root_str, &options).unwrap()), vec!["lib.rs", "main.py", "pkg/util.py"]);
+        
+        let flat = ParseOptions { recursive: false, ..options.clone() };
+        assert_eq!(relative(list_parseable_files(&root_str, &flat).unwrap()), vec!["lib.rs", "main.py"]);
+        
+        let filter = crate::FileFilter {
+            extensions: Some(vec!["py".to_string()]),
+            languages: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            custom_predicate: None,
+        };
+        let filtered = list_parseable_files_with_filter(&root_str, &filter, &options).unwrap();
+        assert_eq!(relative(filtered), vec!["main.py", "pkg/util.py"]);
+        
+        assert!(list_parseable_files(&root.join("missing").to_string_lossy(), &options).is_err());
+    }
+
+    #[test]
+    fn test_leading_bom_is_stripped() {
+        let parsed = parse_string_sync("\u{FEFF}def first():\n    pass\n", Language::Python).unwrap();
+        let first = &parsed.constructs[0];
+        assert_eq!(first.name.as_deref(), Some("first"));
+        assert_eq!((first.start_byte, first.start_column), (0, 0));
+        assert!(first.source_code.starts_with("def first"));
+        
+        // The retained source matches the offsets, so queries agree with extraction
+        let source = parsed.source.as_deref().unwrap();
+        assert_eq!(&source[first.start_byte..first.end_byte], first.source_code);
+        let matches = crate::search_by_query(&parsed, "(function_definition) @f").unwrap();
+        assert_eq!(matches[0].start_byte, first.start_byte);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_crlf_normalization() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tree_parser_crlf_test.py");
+        std::fs::write(&path, "def one():\r\n    pass\r\n\r\ndef two():\r\n    pass\r\n").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        
+        // Line endings are kept as-is by default
+        let options = ParseOptions { retain_source: true, ..ParseOptions::default() };
+        let parsed = read_and_parse_file(&path_str, Language::Python, &options).unwrap();
+        assert!(!parsed.line_endings_normalized);
+        assert!(parsed.source.as_deref().unwrap().contains("\r\n"));
+        assert_eq!(parsed.constructs[1].start_line, 4);
+        
+        let options = ParseOptions { normalize_line_endings: true, ..ParseOptions::default() };
+        let parsed = read_and_parse_file(&path_str, Language::Python, &options).unwrap();
+        assert!(parsed.line_endings_normalized);
+        let two = parsed.constructs[1].clone();
+        assert_eq!((two.name.as_deref(), two.start_line), (Some("two"), 4));
+        assert!(!two.source_code.contains('\r'));
+        
+        // Without `retain_source` the text is re-read from disk with the same
+        // normalization, keeping offsets valid
+        assert!(parsed.source.is_none());
+        let source = retained_source(&parsed).unwrap();
+        assert_eq!(&source[two.start_byte..two.end_byte], two.source_code);
+    }
+
+    #[test]
+    fn test_parameter_extraction() {
+        let python = "def run(self, path: str, retries=3, *args, **kwargs):\n    pass\n";
+        let parsed = parse_string_sync(python, Language::Python).unwrap();
+        let params: Vec<_> = parsed.constructs[0].metadata.parameters.iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_deref(), p.default_value.as_deref(), p.is_variadic))
+            .collect();
+        assert_eq!(params, vec![
+            ("self", None, None, false),
+            ("path", Some("str"), None, false),
+            ("retries", None, Some("3"), false),
+            ("args", None, None, true),
+            ("kwargs", None, None, true),
+        ]);
+        
+        let rust = "fn scale(&self, factor: f64) -> f64 { factor }\nstruct Empty;\n";
+        let parsed = parse_string_sync(rust, Language::Rust).unwrap();
+        let params: Vec<_> = parsed.constructs[0].metadata.parameters.iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_deref()))
+            .collect();
+        assert_eq!(params, vec![("&self", None), ("factor", Some("f64"))]);
+        assert!(parsed.constructs[1].metadata.parameters.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "cpp")]
+    fn test_cpp_parameter_extraction() {
+        let cpp = "int add(int a, const char *label = nullptr, ...) { return a; }\n";
+        let parsed = parse_string_sync(cpp, Language::Cpp).unwrap();
+        let params: Vec<_> = parsed.constructs[0].metadata.parameters.iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_deref(), p.default_value.as_deref(), p.is_variadic))
+            .collect();
+        assert_eq!(params, vec![
+            ("a", Some("int"), None, false),
+            ("label", Some("const char *"), Some("nullptr"), false),
+            ("...", None, None, true),
+        ]);
+        
+        let cpp = "void f(char c);\nvoid f(const char *s);\nvoid g(int &r, char **argv, int arr[], int (*cb)(int), volatile int *const p);\n";
+        let parsed = parse_string_sync(cpp, Language::Cpp).unwrap();
+        let types: Vec<Vec<_>> = parsed.constructs.iter()
+            .map(|c| c.metadata.parameters.iter().map(|p| p.param_type.clone().unwrap_or_default()).collect())
+            .collect();
+        assert_eq!(types, vec![
+            vec!["char".to_string()],
+            vec!["const char *".to_string()],
+            vec![
+                "int &".to_string(),
+                "char **".to_string(),
+                "int []".to_string(),
+                "int (*)(int)".to_string(),
+                "volatile int *const".to_string(),
+            ],
+        ]);
+    }
+
+    #[test]
+    fn test_report_syntax_errors() {
+        // The closing brace of `main` is missing
+        let source = "fn main() {\n    let x = 1;\n";
+        let options = ParseOptions { report_syntax_errors: true, ..ParseOptions::default() };
+        let parsed = parse_source(source.to_string(), STRING_PATH, STRING_PATH.to_string(), Language::Rust, true, &options).unwrap();
+        
+        assert_eq!(parsed.syntax_errors.len(), 1);
+        let error = parsed.syntax_errors[0];
+        assert_eq!((error.start_byte, error.start_point.row), (0, 0));
+        assert_eq!(error.end_point.row, 1);
+        assert_eq!(&source[error.start_byte..error.end_byte], "fn main() {\n    let x = 1;");
+        
+        // The locations survive a JSON round-trip, and older JSON without them still loads
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(json["syntax_errors"][0]["end_point"]["row"], 1);
+        let restored: ParsedFile = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(restored.syntax_errors, parsed.syntax_errors);
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("syntax_errors");
+        assert!(serde_json::from_value::<ParsedFile>(legacy).unwrap().syntax_errors.is_empty());
+        
+        // Off by default, and valid files report nothing
+        assert!(parse_string_sync(source, Language::Rust).unwrap().syntax_errors.is_empty());
+        let valid = parse_source("fn ok() {}\n".to_string(), STRING_PATH, STRING_PATH.to_string(), Language::Rust, true, &options).unwrap();
+        assert!(valid.syntax_errors.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_skip_minified_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("app.js"), "function main() {\n  return 1;\n}\n").unwrap();
+        std::fs::write(root.join("vendor.min.js"), "function a(){return 1}\n").unwrap();
+        let bundle: String = (0..400).map(|i| format!("function f{}(){{return {}}};", i, i)).collect();
+        std::fs::write(root.join("bundle.js"), bundle).unwrap();
+        
+        let names = |skip_minified: bool| {
+            let options = ParseOptions { skip_minified, ..ParseOptions::default() };
+            let mut names: Vec<_> = collect_files(root, &options).unwrap()
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        
+        assert_eq!(names(false), vec!["app.js", "bundle.js", "vendor.min.js"]);
+        assert_eq!(names(true), vec!["app.js"]);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_dedupe_identical_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("vendor_a")).unwrap();
+        std::fs::create_dir_all(root.join("vendor_b")).unwrap();
+        let shared = "def shared():\n    pass\n";
+        std::fs::write(root.join("vendor_a").join("lib.py"), shared).unwrap();
+        std::fs::write(root.join("vendor_b").join("lib.py"), shared).unwrap();
+        std::fs::write(root.join("main.py"), "def main():\n    pass\n").unwrap();
+        
+        let options = ParseOptions { dedupe_identical: true, max_concurrent_files: 2, ..ParseOptions::default() };
+        let probe = probe::watch(root);
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        assert_eq!(project.total_files_processed, 3);
+        
+        // Each file is read once, while holding one of the parse permits
+        assert_eq!(probe.reads.total(), 3);
+        assert!(probe.reads.peak() <= 2);
+        assert_eq!(probe.parses.total(), 2);
+        
+        let copies: Vec<_> = project.files.iter().filter(|f| f.file_path.ends_with("lib.py")).collect();
+        assert_eq!(copies.len(), 2);
+        assert_ne!(copies[0].file_path, copies[1].file_path);
+        assert_eq!(copies[0].constructs[0].name.as_deref(), Some("shared"));
+        assert_eq!(copies[1].constructs[0].name.as_deref(), Some("shared"));
+        
+        // Copies of one tree share its subtrees, so tree-sitter parsed the content once
+        let root_id = |file: &ParsedFile| file.syntax_tree.as_ref().unwrap().root_node().child(0).unwrap().id();
+        assert_eq!(root_id(copies[0]), root_id(copies[1]));
+        
+        let options = ParseOptions { dedupe_identical: false, ..ParseOptions::default() };
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        let copies: Vec<_> = project.files.iter().filter(|f| f.file_path.ends_with("lib.py")).collect();
+        assert_ne!(root_id(copies[0]), root_id(copies[1]));
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_parse_directory_incremental() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("stable.py"), "def stable():\n    pass\n").unwrap();
+        std::fs::write(root.join("edited.py"), "def before():\n    pass\n").unwrap();
+        let dir = root.to_string_lossy().to_string();
+        
+        let first = parse_directory(&dir, ParseOptions::default()).await.unwrap();
+        std::fs::write(root.join("edited.py"), "def after():\n    pass\n").unwrap();
+        std::fs::write(root.join("added.py"), "def added():\n    pass\n").unwrap();
+        let second = parse_directory_incremental(&dir, ParseOptions::default(), &first).await.unwrap();
+        
+        assert_eq!(second.total_files_processed, 3);
+        let find = |project: &ParsedProject, name: &str| {
+            project.files.iter().find(|f| f.file_path.ends_with(name)).cloned().unwrap()
+        };
+        assert_eq!(find(&second, "edited.py").constructs[0].name.as_deref(), Some("after"));
+        assert_eq!(find(&second, "added.py").constructs[0].name.as_deref(), Some("added"));
+        
+        // A reused entry shares its subtrees with the previous run; a re-parsed one does not
+        let first_child_id = |file: &ParsedFile| file.syntax_tree.as_ref().unwrap().root_node().child(0).unwrap().id();
+        assert_eq!(first_child_id(&find(&first, "stable.py")), first_child_id(&find(&second, "stable.py")));
+        assert_ne!(first_child_id(&find(&first, "edited.py")), first_child_id(&find(&second, "edited.py")));
+        
+        // On a dedicated thread pool, an unchanged tree reuses every entry
+        let options = ParseOptions { thread_pool_size: Some(2), ..ParseOptions::default() };
+        let third = parse_directory_incremental(&dir, options, &second).await.unwrap();
+        assert_eq!(third.total_files_processed, 3);
+        for name in ["stable.py", "edited.py", "added.py"] {
+            assert_eq!(first_child_id(&find(&second, name)), first_child_id(&find(&third, name)));
+        }
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_thread_pool_size_limits_parallelism() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for i in 0..24 {
+            std::fs::write(root.join(format!("m{}.py", i)), format!("def f{}():\n    pass\n", i)).unwrap();
+        }
+        let probe = probe::watch(root);
+        
+        // The file semaphore would allow 8 parses at once; the pool allows 2
+        let options = ParseOptions {
+            thread_pool_size: Some(2),
+            max_concurrent_files: 8,
+            ..ParseOptions::default()
+        };
+        let project = parse_directory(&root.to_string_lossy(), options.clone()).await.unwrap();
+        assert_eq!(project.files.len(), 24);
+        assert!(probe.parses.peak() <= 2, "{} parses at once", probe.parses.peak());
+        
+        // Batches share one pool and stay within the same limit
+        let batched = ParseOptions { batch_size: Some(5), ..options };
+        let project = parse_directory(&root.to_string_lossy(), batched).await.unwrap();
+        assert_eq!(project.files.len(), 24);
+        assert!(probe.parses.peak() <= 2, "{} parses at once", probe.parses.peak());
+        
+        // A larger pool lets parses overlap
+        let options = ParseOptions {
+            thread_pool_size: Some(4),
+            max_concurrent_files: 8,
+            ..ParseOptions::default()
+        };
+        let wide = probe::watch(root);
+        parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        assert!(wide.parses.peak() > 1);
+    }
+    
+    #[test]
+    fn test_parse_bytes_utf16le() {
+        // UTF-16LE with a byte order mark, as Windows editors write it
+        let source = "# Grüße\ndef greet():\n    return 'héllo'\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(source.encode_utf16().flat_map(u16::to_le_bytes));
+        
+        let parsed = parse_bytes(&bytes, crate::UTF_16LE, Language::Python, true).unwrap();
+        let function = &parsed.constructs[0];
+        assert_eq!(function.name.as_deref(), Some("greet"));
+        assert_eq!(function.start_line, 2);
+        
+        // Offsets index into the transcoded UTF-8 text
+        let retained = parsed.source.as_deref().unwrap();
+        assert_eq!(retained, source);
+        assert_eq!(&retained[function.start_byte..function.end_byte], function.source_code);
+        assert_eq!(function.start_byte, "# Grüße\n".len());
+        
+        // An unpaired surrogate is not valid UTF-16
+        let invalid = [0x00, 0xD8, 0x41, 0x00];
+        assert!(matches!(parse_bytes(&invalid, crate::UTF_16LE, Language::Python, true), Err(Error::Parse(_))));
+    }
+    
+    #[test]
+    fn test_synthesize_anonymous_names() {
+        let source = "const double = (x) => x * 2;\n[1, 2].forEach((n) => console.log(n));\n";
+        let options = ParseOptions { synthesize_anonymous_names: true, ..ParseOptions::default() };
+        let parsed = parse_source(source.to_string(), STRING_PATH, STRING_PATH.to_string(), Language::JavaScript, true, &options).unwrap();
+        
+        let arrows: Vec<_> = parsed
+            .all_constructs()
+            .into_iter()
+            .filter(|c| c.node_type == "arrow_function")
+            .map(|c| (c.name.clone(), c.synthetic_name.clone()))
+            .collect();
+        assert_eq!(arrows, vec![
+            (None, Some("<anonymous@1:15>".to_string())),
+            (None, Some("<anonymous@2:15>".to_string())),
+        ]);
+        
+        // Named constructs and the default options are left alone
+        let named = parse_source("function named() {}\n".to_string(), STRING_PATH, STRING_PATH.to_string(), Language::JavaScript, true, &options).unwrap();
+        assert_eq!(named.constructs[0].synthetic_name, None);
+        let default = parse_string_sync(source, Language::JavaScript).unwrap();
+        assert!(default.all_constructs().iter().all(|c| c.synthetic_name.is_none()));
+    }
+    
+    #[test]
+    #[cfg(feature = "c")]
+    fn test_c_macro_extraction() {
+        let header = "#define MAX_SIZE 1024\n#define SQUARE(x) ((x) * (x))\n#define LOG(fmt, ...) printf(fmt, __VA_ARGS__)\n#define EMPTY()\n";
+        let parsed = parse_string_sync(header, Language::C).unwrap();
+        
+        let macros: Vec<_> = parsed
+            .constructs
+            .iter()
+            .map(|c| {
+                let parameters: Vec<_> = c.metadata.parameters.iter().map(|p| (p.name.as_str(), p.is_variadic)).collect();
+                (c.node_type.as_str(), c.name.as_deref(), parameters)
+            })
+            .collect();
+        assert_eq!(macros, vec![
+            ("preproc_def", Some("MAX_SIZE"), vec![]),
+            ("preproc_function_def", Some("SQUARE"), vec![("x", false)]),
+            ("preproc_function_def", Some("LOG"), vec![("fmt", false), ("...", true)]),
+            ("preproc_function_def", Some("EMPTY"), vec![]),
+        ]);
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_parse_directory_in_batches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for package in 0..10 {
+            let dir = root.join(format!("package_{}", package));
+            std::fs::create_dir_all(&dir).unwrap();
+            for module in 0..50 {
+                std::fs::write(dir.join(format!("module_{}.py", module)), format!("def f_{}_{}():\n    pass\n", package, module)).unwrap();
+            }
+        }
+        std::fs::write(root.join("broken.py"), [0xFF, 0xFE, 0x00]).unwrap();
+        let dir = root.to_string_lossy().to_string();
+        
+        let unbatched = parse_directory(&dir, ParseOptions::default()).await.unwrap();
+        let options = ParseOptions { batch_size: Some(64), ..ParseOptions::default() };
+        let batched = parse_directory(&dir, options).await.unwrap();
+        
+        assert_eq!(batched.total_files_processed, 500);
+        assert_eq!(batched.error_files.len(), 1);
+        let paths = |project: &ParsedProject| {
+            let mut paths: Vec<_> = project.files.iter().map(|f| f.file_path.clone()).collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(paths(&batched), paths(&unbatched));
+        
+        // Filters apply to each batch as it is walked
+        let filter = crate::FileFilter {
+            extensions: None,
+            languages: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            custom_predicate: Some(std::sync::Arc::new(|path: &Path| path.to_string_lossy().contains("package_3"))),
+        };
+        let options = ParseOptions { batch_size: Some(7), ..ParseOptions::default() };
+        let filtered = parse_directory_with_filter(&dir, &filter, options).await.unwrap();
+        assert_eq!(filtered.total_files_processed, 50);
+    }
+    
+    #[test]
+    fn test_strict_mode() {
+        let broken = "def ok():\n    pass\n\ndef broken(:\n    pass\n";
+        let strict = ParseOptions { strict: true, ..ParseOptions::default() };
+        let parse = |source: &str, options: &ParseOptions| {
+            parse_source(source.to_string(), "broken.py", "broken.py".to_string(), Language::Python, false, options)
+        };
+        
+        match parse(broken, &strict) {
+            Err(Error::Parse(message)) => assert_eq!(message, "Syntax error in broken.py at line 4, column 11"),
+            other => panic!("expected a strict parse error, got {:?}", other.map(|f| f.constructs.len())),
+        }
+        assert!(parse(broken, &ParseOptions::default()).is_ok());
+        assert!(parse("def ok():\n    pass\n", &strict).is_ok());
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_binary_file_with_source_extension() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tree_parser_binary_test.c");
+        std::fs::write(&path, b"\x7fELF\x02\x01\x01\x00\x00\x00int main() {}").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        
+        // Rejected while reading, before the grammar is ever consulted
+        let error = parse_file(&path_str, Language::Python).await.unwrap_err();
+        assert!(matches!(error, Error::BinaryFile(_)));
+        assert!(matches!(error.error_type(), ErrorType::BinaryFile));
+    }
+    
+    #[test]
+    #[cfg(all(unix, not(target_arch = "wasm32")))]
+    fn test_only_languages_skips_other_files_unopened() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("lib.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("tool.py"), "def tool():\n    pass\n").unwrap();
+        // Opening a FIFO for reading blocks until a writer appears, so any read of it hangs
+        let status = std::process::Command::new("mkfifo").arg(root.join("pipe.py")).status().unwrap();
+        assert!(status.success());
+        
+        let dir = root.to_string_lossy().to_string();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let options = ParseOptions {
+                only_languages: Some(vec![Language::Rust]),
+                language_detection: LanguageDetection::Combined,
+                skip_minified: true,
+                ..ParseOptions::default()
+            };
+            let project = runtime.block_on(parse_directory(&dir, options)).unwrap();
+            
+            let filter = crate::FileFilter {
+                extensions: None,
+                languages: Some(vec![Language::Rust]),
+                min_size_bytes: None,
+                max_size_bytes: None,
+                custom_predicate: None,
+            };
+            let options = ParseOptions { skip_minified: true, ..ParseOptions::default() };
+            let filtered = runtime.block_on(parse_directory_with_filter(&dir, &filter, options)).unwrap();
+            sender.send((project, filtered)).unwrap();
+        });
+        
+        let (project, filtered) = receiver
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .expect("a Python file was opened");
+        for project in [project, filtered] {
+            assert_eq!(project.files.len(), 1);
+            assert!(project.files[0].file_path.ends_with("lib.rs"));
+            assert!(project.error_files.is_empty());
+            assert!(project.warnings.is_empty());
+        }
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_parse_directory_deterministic_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        for i in 0..20 {
+            // Vary sizes so files finish parsing out of order
+            let body = "    x = 1\n".repeat((20 - i) * 50);
+            std::fs::write(root.join(format!("module_{}.py", i)), format!("def f():\n{}", body)).unwrap();
+            std::fs::write(root.join("nested").join(format!("{}.rs", i)), "fn f() {}\n").unwrap();
+        }
+        
+        let options = ParseOptions { max_concurrent_files: 8, ..ParseOptions::default() };
+        let first = parse_directory(&root.to_string_lossy(), options.clone()).await.unwrap();
+        let second = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        
+        let paths = |project: &ParsedProject| project.files.iter().map(|f| f.file_path.clone()).collect::<Vec<_>>();
+        assert_eq!(first.files.len(), 40);
+        assert_eq!(paths(&first), paths(&second));
+        assert!(paths(&first).is_sorted());
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_count_constructs_matches_full_parse() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("a.py"), "class A:\n    def m(self):\n        def inner():\n            pass\n\ndef f():\n    pass\n").unwrap();
+        std::fs::write(root.join("nested").join("b.py"), "def g():\n    pass\n").unwrap();
+        std::fs::write(root.join("c.rs"), "struct S;\nimpl S {\n    fn m(&self) {}\n}\nfn main() {}\n").unwrap();
+        
+        let node_types = ["function_definition", "class_definition", "function_item", "struct_item"];
+        let (counts, error_files) = count_constructs(&root.to_string_lossy(), ParseOptions::default(), &node_types).await.unwrap();
+        assert!(error_files.is_empty());
+        
+        let project = parse_directory(&root.to_string_lossy(), ParseOptions::default()).await.unwrap();
+        for node_type in node_types {
+            let expected = crate::project_search_by_node_type(&project, node_type, None).len();
+            assert_eq!(counts.get(node_type).copied().unwrap_or(0), expected, "{}", node_type);
+        }
+        assert_eq!(counts.get("function_definition"), Some(&4));
+        assert_eq!(counts.get("function_item"), Some(&2));
+        
+        // A depth limit cuts off nested functions in both
+        let options = ParseOptions { max_depth: Some(4), ..ParseOptions::default() };
+        let (counts, _) = count_constructs(&root.to_string_lossy(), options.clone(), &node_types).await.unwrap();
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        for node_type in node_types {
+            let expected = crate::project_search_by_node_type(&project, node_type, None).len();
+            assert_eq!(counts.get(node_type).copied().unwrap_or(0), expected, "{}", node_type);
+        }
+        assert!(counts["function_definition"] < 4);
+        
+        // Files that fail are reported instead of counting as zero
+        std::fs::write(root.join("broken.py"), "def broken(:\n    pass\n").unwrap();
+        let strict = ParseOptions { strict: true, ..ParseOptions::default() };
+        let (counts, error_files) = count_constructs(&root.to_string_lossy(), strict, &node_types).await.unwrap();
+        assert_eq!(counts.get("function_definition"), Some(&4));
+        assert_eq!(error_files.len(), 1);
+        assert!(error_files[0].file_path.ends_with("broken.py"));
+        assert_eq!(error_files[0].error_type, ErrorType::ParseError);
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_count_constructs_thread_pool_and_batches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for i in 0..24 {
+            std::fs::write(root.join(format!("m{}.py", i)), format!("def f{}():\n    pass\n", i)).unwrap();
+        }
+        let probe = probe::watch(root);
+        
+        // The file semaphore would allow 8 files at once; the pool allows 2
+        let options = ParseOptions {
+            thread_pool_size: Some(2),
+            max_concurrent_files: 8,
+            ..ParseOptions::default()
+        };
+        let (counts, _) = count_constructs(&root.to_string_lossy(), options.clone(), &["function_definition"]).await.unwrap();
+        assert_eq!(counts["function_definition"], 24);
+        assert!(probe.parses.peak() <= 2, "{} parses at once", probe.parses.peak());
+        
+        // Batches share one pool and add up to the same counts
+        let batched = ParseOptions { batch_size: Some(5), ..options };
+        let (counts, _) = count_constructs(&root.to_string_lossy(), batched, &["function_definition"]).await.unwrap();
+        assert_eq!(counts["function_definition"], 24);
+        assert!(probe.parses.peak() <= 2, "{} parses at once", probe.parses.peak());
+        assert_eq!(probe.parses.total(), 48);
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_parse_directory_warnings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("ok.py"), "def ok():\n    pass\n").unwrap();
+        std::fs::write(root.join("recovered.py"), "def broken(:\n    pass\n").unwrap();
+        std::fs::write(root.join("large.py"), "x = 1\n".repeat(300_000)).unwrap();
+        std::fs::write(root.join("script.pl"), "sub hello { }\n").unwrap();
+        std::fs::write(root.join("invalid.py"), [0xFF, 0xFE, 0x00]).unwrap();
+        
+        let options = ParseOptions { max_file_size_mb: 0, ..ParseOptions::default() };
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        
+        let mut warnings: Vec<_> = project
+            .warnings
+            .iter()
+            .map(|w| (Path::new(&w.file_path).file_name().unwrap().to_string_lossy().to_string(), w.reason.clone()))
+            .collect();
+        warnings.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(warnings, vec![
+            ("large.py".to_string(), WarningReason::FileTooLarge),
+            ("recovered.py".to_string(), WarningReason::SyntaxErrorsRecovered),
+            ("script.pl".to_string(), WarningReason::UnsupportedLanguage),
+        ]);
+        
+        // Only the unreadable file is a hard failure
+        assert_eq!(project.error_files.len(), 1);
+        assert!(project.error_files[0].file_path.ends_with("invalid.py"));
+        assert_eq!(project.total_files_processed, 2);
+    }
+    
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_depth_limit_is_a_warning() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("deep.py"), "class A:\n    class B:\n        def f(self):\n            pass\n").unwrap();
+        
+        let options = ParseOptions { max_depth: Some(2), ..ParseOptions::default() };
+        let project = parse_directory(&root.to_string_lossy(), options).await.unwrap();
+        
+        assert_eq!(project.files.len(), 1);
+        assert!(project.files[0].depth_limit_reached);
+        assert_eq!(project.warnings.len(), 1);
+        assert_eq!(project.warnings[0].reason, WarningReason::DepthLimitReached);
+        assert!(project.warnings[0].file_path.ends_with("deep.py"));
+        assert!(project.error_files.is_empty());
+    }
+}
@@ -0,0 +1,438 @@
+//! Preset tree-sitter queries for common searches
+//!
+//! Each language module exposes named `Preset`s that can be passed to
+//! `run_preset`, so common searches don't require writing queries by hand.
+//! Every preset captures whole constructs with a single capture, and all
+//! presets are compiled against their grammar in the crate's tests.
+//!
+//! ```rust,no_run
+//! use tree_parser::{parse_file, queries, run_preset, Language};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let parsed = parse_file("src/lib.rs", Language::Rust).await?;
+//!     let public_functions = run_preset(&parsed, queries::rust::PUBLIC_FUNCTIONS)?;
+//!     println!("Found {} public functions", public_functions.len());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{search_by_query, CodeConstruct, Error, Language, ParsedFile};
+
+/// A named, language-specific tree-sitter query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    /// Short identifier of the preset (e.g. "public_functions")
+    pub name: &'static str,
+    /// Language whose grammar the query is written against
+    pub language: Language,
+    /// The tree-sitter query source
+    pub query: &'static str,
+}
+
+/// Run a preset query against a parsed file
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `preset` - The preset to run; its language must match the file's language
+/// 
+/// # Returns
+/// 
+/// A `Result` containing the captured constructs, or an `Error` if the preset
+/// targets a different language or the query cannot be executed.
+pub fn run_preset(parsed_file: &ParsedFile, preset: Preset) -> Result<Vec<CodeConstruct>, Error> {
+    if preset.language != parsed_file.language {
+        return Err(Error::InvalidQuery(format!(
+            "Preset '{}' targets {:?} but the file is {:?}",
+            preset.name, preset.language, parsed_file.language
+        )));
+    }
+    
+    search_by_query(parsed_file, preset.query)
+}
+
+/// Get all presets available for a language
+pub fn presets_for(language: &Language) -> &'static [Preset] {
+    match language {
+        Language::Python => python::ALL,
+        Language::Rust => rust::ALL,
+        Language::JavaScript => javascript::ALL,
+        Language::TypeScript => typescript::ALL,
+        Language::Java => java::ALL,
+        Language::C => c::ALL,
+        Language::Cpp => cpp::ALL,
+        Language::Go => go::ALL,
+        _ => &[],
+    }
+}
+
+/// Preset queries for Python
+pub mod python {
+    use super::Preset;
+    use crate::Language;
+
+    /// All function and method definitions
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::Python,
+        query: "(function_definition) @function",
+    };
+
+    /// All class definitions
+    pub const CLASSES: Preset = Preset {
+        name: "classes",
+        language: Language::Python,
+        query: "(class_definition) @class",
+    };
+
+    /// Functions that carry at least one decorator
+    pub const DECORATED_FUNCTIONS: Preset = Preset {
+        name: "decorated_functions",
+        language: Language::Python,
+        query: "(decorated_definition definition: (function_definition) @function)",
+    };
+
+    /// `import` and `from ... import` statements
+    pub const IMPORTS: Preset = Preset {
+        name: "imports",
+        language: Language::Python,
+        query: "[(import_statement) (import_from_statement)] @import",
+    };
+
+    /// All Python presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, CLASSES, DECORATED_FUNCTIONS, IMPORTS];
+}
+
+/// Preset queries for Rust
+pub mod rust {
+    use super::Preset;
+    use crate::Language;
+
+    /// All function items, including methods
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::Rust,
+        query: "(function_item) @function",
+    };
+
+    /// Function items with a visibility modifier (`pub`, `pub(crate)`, ...)
+    pub const PUBLIC_FUNCTIONS: Preset = Preset {
+        name: "public_functions",
+        language: Language::Rust,
+        query: "(function_item (visibility_modifier)) @function",
+    };
+
+    /// All struct items
+    pub const STRUCTS: Preset = Preset {
+        name: "structs",
+        language: Language::Rust,
+        query: "(struct_item) @struct",
+    };
+
+    /// `impl Trait for Type` blocks
+    pub const TRAIT_IMPLS: Preset = Preset {
+        name: "trait_impls",
+        language: Language::Rust,
+        query: "(impl_item trait: (_)) @impl",
+    };
+
+    /// All Rust presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, PUBLIC_FUNCTIONS, STRUCTS, TRAIT_IMPLS];
+}
+
+/// Preset queries for JavaScript
+pub mod javascript {
+    use super::Preset;
+    use crate::Language;
+
+    /// Function declarations, function expressions and arrow functions
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::JavaScript,
+        query: "[(function_declaration) (function_expression) (arrow_function)] @function",
+    };
+
+    /// All class declarations
+    pub const CLASSES: Preset = Preset {
+        name: "classes",
+        language: Language::JavaScript,
+        query: "(class_declaration) @class",
+    };
+
+    /// All export statements
+    pub const EXPORTS: Preset = Preset {
+        name: "exports",
+        language: Language::JavaScript,
+        query: "(export_statement) @export",
+    };
+
+    /// All JavaScript presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, CLASSES, EXPORTS];
+}
+
+/// Preset queries for TypeScript
+pub mod typescript {
+    use super::Preset;
+    use crate::Language;
+
+    /// Function declarations, function expressions and arrow functions
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::TypeScript,
+        query: "[(function_declaration) (function_expression) (arrow_function)] @function",
+    };
+
+    /// All class declarations
+    pub const CLASSES: Preset = Preset {
+        name: "classes",
+        language: Language::TypeScript,
+        query: "(class_declaration) @class",
+    };
+
+    /// All interface declarations
+    pub const INTERFACES: Preset = Preset {
+        name: "interfaces",
+        language: Language::TypeScript,
+        query: "(interface_declaration) @interface",
+    };
+
+    /// All `type X = ...` declarations
+    pub const TYPE_ALIASES: Preset = Preset {
+        name: "type_aliases",
+        language: Language::TypeScript,
+        query: "(type_alias_declaration) @type_alias",
+    };
+
+    /// All TypeScript presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, CLASSES, INTERFACES, TYPE_ALIASES];
+}
+
+/// Preset queries for Java
+pub mod java {
+    use super::Preset;
+    use crate::Language;
+
+    /// All method declarations
+    pub const METHODS: Preset = Preset {
+        name: "methods",
+        language: Language::Java,
+        query: "(method_declaration) @method",
+    };
+
+    /// All class declarations
+    pub const CLASSES: Preset = Preset {
+        name: "classes",
+        language: Language::Java,
+        query: "(class_declaration) @class",
+    };
+
+    /// All interface declarations
+    pub const INTERFACES: Preset = Preset {
+        name: "interfaces",
+        language: Language::Java,
+        query: "(interface_declaration) @interface",
+    };
+
+    /// All Java presets
+    pub const ALL: &[Preset] = &[METHODS, CLASSES, INTERFACES];
+}
+
+/// Preset queries for C
+pub mod c {
+    use super::Preset;
+    use crate::Language;
+
+    /// All function definitions
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::C,
+        query: "(function_definition) @function",
+    };
+
+    /// All struct specifiers
+    pub const STRUCTS: Preset = Preset {
+        name: "structs",
+        language: Language::C,
+        query: "(struct_specifier) @struct",
+    };
+
+    /// Object-like and function-like `#define` macros
+    pub const MACROS: Preset = Preset {
+        name: "macros",
+        language: Language::C,
+        query: "[(preproc_def) (preproc_function_def)] @macro",
+    };
+
+    /// All C presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, STRUCTS, MACROS];
+}
+
+/// Preset queries for C++
+pub mod cpp {
+    use super::Preset;
+    use crate::Language;
+
+    /// All function definitions
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::Cpp,
+        query: "(function_definition) @function",
+    };
+
+    /// All class specifiers
+    pub const CLASSES: Preset = Preset {
+        name: "classes",
+        language: Language::Cpp,
+        query: "(class_specifier) @class",
+    };
+
+    /// All namespace definitions
+    pub const NAMESPACES: Preset = Preset {
+        name: "namespaces",
+        language: Language::Cpp,
+        query: "(namespace_definition) @namespace",
+    };
+
+    /// All C++ presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, CLASSES, NAMESPACES];
+}
+
+/// Preset queries for Go
+pub mod go {
+    use super::Preset;
+    use crate::Language;
+
+    /// All top-level function declarations
+    pub const FUNCTIONS: Preset = Preset {
+        name: "functions",
+        language: Language::Go,
+        query: "(function_declaration) @function",
+    };
+
+    /// All method declarations
+    pub const METHODS: Preset = Preset {
+        name: "methods",
+        language: Language::Go,
+        query: "(method_declaration) @method",
+    };
+
+    /// Type specs declaring a struct
+    pub const STRUCTS: Preset = Preset {
+        name: "structs",
+        language: Language::Go,
+        query: "(type_spec type: (struct_type)) @struct",
+    };
+
+    /// All Go presets
+    pub const ALL: &[Preset] = &[FUNCTIONS, METHODS, STRUCTS];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_tree_sitter_language, parse_string_sync};
+    use tree_sitter::Query;
+
+    /// Run every preset for a language against a sample and assert each matches
+    fn assert_presets_match(language: Language, source: &str) {
+        let parsed = parse_string_sync(source, language.clone()).expect("Failed to parse sample");
+        
+        for preset in presets_for(&language) {
+            let results = run_preset(&parsed, preset.clone())
+                .unwrap_or_else(|e| panic!("Preset {:?}/{} failed: {}", language, preset.name, e));
+            assert!(!results.is_empty(), "Preset {:?}/{} matched nothing", language, preset.name);
+        }
+    }
+
+    #[test]
+    fn test_presets_compile() {
+        let languages = [
+            Language::Python,
+            Language::Rust,
+            Language::JavaScript,
+            Language::TypeScript,
+            Language::Java,
+            Language::C,
+            Language::Cpp,
+            Language::Go,
+        ];
+        
+        // Only grammars enabled by feature flags can be checked
+        for language in languages {
+            let Ok(ts_language) = get_tree_sitter_language(&language) else {
+                continue;
+            };
+            for preset in presets_for(&language) {
+                assert_eq!(preset.language, language);
+                Query::new(&ts_language, preset.query)
+                    .unwrap_or_else(|e| panic!("Preset {:?}/{} is invalid: {}", language, preset.name, e));
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_preset_language_mismatch() {
+        let parsed = parse_string_sync("def f():\n    pass\n", Language::Python).unwrap();
+        
+        assert!(matches!(run_preset(&parsed, rust::FUNCTIONS), Err(Error::InvalidQuery(_))));
+        assert_eq!(run_preset(&parsed, python::FUNCTIONS).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_python_presets() {
+        let source = "import os\nfrom sys import argv\n\nclass App:\n    @staticmethod\n    def run():\n        pass\n";
+        assert_presets_match(Language::Python, source);
+    }
+
+    #[test]
+    fn test_rust_presets() {
+        let source = "struct Unit;\n\nimpl Clone for Unit {\n    fn clone(&self) -> Self { Unit }\n}\n\npub fn build() -> Unit { Unit }\n";
+        assert_presets_match(Language::Rust, source);
+        
+        let parsed = parse_string_sync(source, Language::Rust).unwrap();
+        let public = run_preset(&parsed, rust::PUBLIC_FUNCTIONS).unwrap();
+        assert_eq!(public.len(), 1);
+        assert!(public[0].source_code.starts_with("pub fn build"));
+    }
+
+    #[test]
+    fn test_javascript_presets() {
+        let source = "export class Widget {}\nfunction render() {}\nconst handler = () => {};\n";
+        assert_presets_match(Language::JavaScript, source);
+    }
+
+    #[test]
+    fn test_typescript_presets() {
+        let source = "interface Shape { area(): number }\ntype Id = string;\nclass Square {}\nfunction make(): Square { return new Square(); }\n";
+        assert_presets_match(Language::TypeScript, source);
+    }
+
+    #[cfg(feature = "java")]
+    #[test]
+    fn test_java_presets() {
+        let source = "interface Greeter { void greet(); }\nclass Hello implements Greeter { public void greet() {} }\n";
+        assert_presets_match(Language::Java, source);
+    }
+
+    #[cfg(feature = "c")]
+    #[test]
+    fn test_c_presets() {
+        let source = "#define MAX 10\nstruct point { int x; };\nint main(void) { return 0; }\n";
+        assert_presets_match(Language::C, source);
+    }
+
+    #[cfg(feature = "cpp")]
+    #[test]
+    fn test_cpp_presets() {
+        let source = "namespace app {\nclass Widget {};\nint run() { return 0; }\n}\n";
+        assert_presets_match(Language::Cpp, source);
+    }
+
+    #[cfg(feature = "go")]
+    #[test]
+    fn test_go_presets() {
+        let source = "package main\n\ntype Point struct { X int }\n\nfunc (p Point) Norm() int { return p.X }\n\nfunc main() {}\n";
+        assert_presets_match(Language::Go, source);
+    }
+}
@@ -1,7 +1,15 @@
 //! Search functionality for finding code constructs
 
-use crate::{languages::get_tree_sitter_language, CodeConstruct, Error, Language, ParsedFile};
-use regex::Regex;
+use crate::{
+    languages::{
+        get_class_node_types, get_construct_kind, get_function_node_types, get_name_node_types,
+        get_tree_sitter_language, get_type_alias_node_types,
+    },
+    parser::{annotation_name, retained_source, slice_source},
+    CodeConstruct, ConstructKind, Error, Language, ParsedFile, ParsedProject, SearchOptions, SymbolMatch,
+};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
 use tree_sitter::{Query, QueryCursor};
 use streaming_iterator::StreamingIterator;
 
@@ -23,7 +31,7 @@ use streaming_iterator::StreamingIterator;
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use tree_parser::{parse_file, search_by_node_type, Language};
 /// 
 /// #[tokio::main]
@@ -44,12 +52,49 @@ pub fn search_by_node_type(
     parsed_file: &ParsedFile,
     node_type: &str,
     name_pattern: Option<&str>,
+) -> Vec<CodeConstruct> {
+    search_by_node_type_with_options(parsed_file, node_type, name_pattern, &SearchOptions::default())
+}
+
+/// Search for code constructs by node type, with options for name matching
+/// 
+/// Behaves like `search_by_node_type`, with `options` controlling how
+/// `name_pattern` is compiled.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `node_type` - The tree-sitter node type to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// * `options` - Options controlling name matching, such as case-insensitivity
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_node_type_with_options, Language, SearchOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     
+///     // Finds `load_config` as well as `LoadConfig`
+///     let options = SearchOptions { case_insensitive: true };
+///     let loaders = search_by_node_type_with_options(&parsed, "function_definition", Some("^load_?config$"), &options);
+///     println!("Found {} config loaders", loaders.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_by_node_type_with_options(
+    parsed_file: &ParsedFile,
+    node_type: &str,
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
 ) -> Vec<CodeConstruct> {
     let mut results = Vec::new();
     
     // Compile regex pattern if provided
     let regex = if let Some(pattern) = name_pattern {
-        match Regex::new(pattern) {
+        match compile_name_pattern(pattern, options) {
             Ok(r) => Some(r),
             Err(_) => return results, // Invalid regex, return empty results
         }
@@ -57,15 +102,14 @@ pub fn search_by_node_type(
         None
     };
     
-    // Search through all constructs (already flattened, no need for recursive search)
-    for construct in &parsed_file.constructs {
+    // Search through every construct once, whether or not the file was flattened
+    for construct in parsed_file.all_constructs() {
         if construct.node_type == node_type {
             // Check name pattern if provided
             if let Some(ref regex) = regex {
-                if let Some(ref name) = construct.name {
-                    if regex.is_match(name) {
-                        results.push(construct.clone());
-                    }
+                if let Some(ref name) = construct.name
+                    && regex.is_match(name) {
+                    results.push(construct.clone());
                 }
             } else {
                 results.push(construct.clone());
@@ -76,6 +120,75 @@ pub fn search_by_node_type(
     results
 }
 
+/// Search for code constructs of a node type, stopping after `limit` matches
+/// 
+/// Behaves like `search_by_node_type` but walks the construct hierarchy
+/// lazily, in source order, and stops as soon as `limit` matches have been
+/// found. Constructs after the last match are neither visited nor cloned,
+/// which makes this cheap for "first few results" queries on large files.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `node_type` - The tree-sitter node type to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// * `limit` - The maximum number of matches to return
+/// 
+/// # Returns
+/// 
+/// At most `limit` matching constructs, the same as the first `limit`
+/// results of `search_by_node_type`.
+pub fn search_by_node_type_limited(
+    parsed_file: &ParsedFile,
+    node_type: &str,
+    name_pattern: Option<&str>,
+    limit: usize,
+) -> Vec<CodeConstruct> {
+    search_limited(parsed_file, node_type, name_pattern, limit).0
+}
+
+/// Run a limited search, also returning how many constructs were visited
+fn search_limited(
+    parsed_file: &ParsedFile,
+    node_type: &str,
+    name_pattern: Option<&str>,
+    limit: usize,
+) -> (Vec<CodeConstruct>, usize) {
+    let mut results = Vec::new();
+    let mut visited = 0;
+    
+    let regex = match name_pattern.map(Regex::new) {
+        Some(Ok(regex)) => Some(regex),
+        Some(Err(_)) => return (results, visited), // Invalid regex, return empty results
+        None => None,
+    };
+    if limit == 0 {
+        return (results, visited);
+    }
+    
+    // Explicit stack of pending siblings, so the walk can stop at any point
+    let roots: Vec<&CodeConstruct> = parsed_file.constructs.iter().filter(|c| c.depth == 0).collect();
+    let mut stack: Vec<&CodeConstruct> = roots.into_iter().rev().collect();
+    while let Some(construct) = stack.pop() {
+        visited += 1;
+        if construct.node_type == node_type {
+            let name_matches = match &regex {
+                Some(regex) => construct.name.as_ref().is_some_and(|name| regex.is_match(name)),
+                None => true,
+            };
+            if name_matches {
+                results.push(construct.clone());
+                if results.len() == limit {
+                    break;
+                }
+            }
+        }
+        stack.extend(construct.children.iter().rev());
+    }
+    
+    (results, visited)
+}
+
 /// Search for code constructs matching any of the specified node types
 /// 
 /// This function extends `search_by_node_type` to search for multiple node types
@@ -90,63 +203,1406 @@ pub fn search_by_node_type(
 /// 
 /// # Returns
 /// 
-/// A vector of `CodeConstruct` objects that match any of the specified node types
-/// and the optional name pattern.
+/// A vector of `CodeConstruct` objects that match any of the specified node types
+/// and the optional name pattern.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_multiple_node_types, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.js", Language::JavaScript).await?;
+///     
+///     // Find all function-like constructs
+///     let functions = search_by_multiple_node_types(
+///         &parsed,
+///         &["function_declaration", "function_expression", "arrow_function"],
+///         None
+///     );
+///     
+///     println!("Found {} function-like constructs", functions.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_by_multiple_node_types(
+    parsed_file: &ParsedFile,
+    node_types: &[&str],
+    name_pattern: Option<&str>,
+) -> Vec<CodeConstruct> {
+    search_by_multiple_node_types_with_options(parsed_file, node_types, name_pattern, &SearchOptions::default())
+}
+
+/// Search for code constructs matching any of several node types, with options for name matching
+/// 
+/// Behaves like `search_by_multiple_node_types`, with `options` controlling
+/// how `name_pattern` is compiled.
+pub fn search_by_multiple_node_types_with_options(
+    parsed_file: &ParsedFile,
+    node_types: &[&str],
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
+) -> Vec<CodeConstruct> {
+    let mut results = Vec::new();
+    
+    // Compile regex pattern if provided
+    let regex = if let Some(pattern) = name_pattern {
+        match compile_name_pattern(pattern, options) {
+            Ok(r) => Some(r),
+            Err(_) => return results, // Invalid regex, return empty results
+        }
+    } else {
+        None
+    };
+    
+    // Search through every construct once, whether or not the file was flattened
+    for construct in parsed_file.all_constructs() {
+        if node_types.contains(&construct.node_type.as_str()) {
+            // Check name pattern if provided
+            if let Some(ref regex) = regex {
+                if let Some(ref name) = construct.name
+                    && regex.is_match(name) {
+                    results.push(construct.clone());
+                }
+            } else {
+                results.push(construct.clone());
+            }
+        }
+    }
+    
+    results
+}
+
+/// Compile a name pattern according to the search options
+fn compile_name_pattern(pattern: &str, options: &SearchOptions) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(options.case_insensitive)
+        .build()
+}
+
+/// Search for code constructs by node type with include and exclude name patterns
+/// 
+/// Like `search_by_node_type`, but additionally drops constructs whose name
+/// matches `exclude`. When `include` is given only named constructs can
+/// match; unnamed constructs are kept if only `exclude` is given. Unlike the
+/// other search functions, an invalid pattern is reported as an error rather
+/// than producing empty results.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `node_type` - The tree-sitter node type to search for
+/// * `include` - Optional regex a construct name must match
+/// * `exclude` - Optional regex a construct name must not match
+/// 
+/// # Errors
+/// 
+/// Returns `Error::InvalidQuery` if either pattern is not a valid regex.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_node_type_excluding, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("handlers.py", Language::Python).await?;
+///     
+///     // Request handlers, without their test doubles
+///     let handlers = search_by_node_type_excluding(&parsed, "function_definition", Some("^handle_"), Some("_test$"))?;
+///     println!("Found {} handlers", handlers.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_by_node_type_excluding(
+    parsed_file: &ParsedFile,
+    node_type: &str,
+    include: Option<&str>,
+    exclude: Option<&str>,
+) -> Result<Vec<CodeConstruct>, Error> {
+    let compile = |pattern: &str| {
+        Regex::new(pattern)
+            .map_err(|e| Error::InvalidQuery(format!("invalid name pattern '{}': {}", pattern, e)))
+    };
+    let include = include.map(compile).transpose()?;
+    let exclude = exclude.map(compile).transpose()?;
+    
+    let results = parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| construct.node_type == node_type)
+        .filter(|construct| match (&include, &construct.name) {
+            (Some(regex), Some(name)) => regex.is_match(name),
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .filter(|construct| match (&exclude, &construct.name) {
+            (Some(regex), Some(name)) => !regex.is_match(name),
+            _ => true,
+        })
+        .cloned()
+        .collect();
+    
+    Ok(results)
+}
+
+/// Search for functions and methods using the language's function node types
+/// 
+/// This is a convenience wrapper around `search_by_multiple_node_types` that
+/// uses `get_function_node_types` for the file's language, so callers don't
+/// need to know each grammar's node names.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `name_pattern` - Optional regex pattern to filter results by function name
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_functions, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/lib.rs", Language::Rust).await?;
+///     let tests = search_functions(&parsed, Some("^test_"));
+///     println!("Found {} test functions", tests.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_functions(parsed_file: &ParsedFile, name_pattern: Option<&str>) -> Vec<CodeConstruct> {
+    search_functions_with_options(parsed_file, name_pattern, &SearchOptions::default())
+}
+
+/// Search for functions and methods, with options for name matching
+/// 
+/// Behaves like `search_functions`, with `options` controlling how
+/// `name_pattern` is compiled.
+pub fn search_functions_with_options(
+    parsed_file: &ParsedFile,
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
+) -> Vec<CodeConstruct> {
+    search_by_multiple_node_types_with_options(
+        parsed_file,
+        get_function_node_types(&parsed_file.language),
+        name_pattern,
+        options,
+    )
+}
+
+/// Search for classes and similar type definitions using the language's class node types
+/// 
+/// This is a convenience wrapper around `search_by_multiple_node_types` that
+/// uses `get_class_node_types` for the file's language (e.g. structs, enums
+/// and traits in Rust, classes and interfaces in Java).
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `name_pattern` - Optional regex pattern to filter results by name
+pub fn search_classes(parsed_file: &ParsedFile, name_pattern: Option<&str>) -> Vec<CodeConstruct> {
+    search_classes_with_options(parsed_file, name_pattern, &SearchOptions::default())
+}
+
+/// Search for classes and similar type definitions, with options for name matching
+/// 
+/// Behaves like `search_classes`, with `options` controlling how
+/// `name_pattern` is compiled.
+pub fn search_classes_with_options(
+    parsed_file: &ParsedFile,
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
+) -> Vec<CodeConstruct> {
+    search_by_multiple_node_types_with_options(
+        parsed_file,
+        get_class_node_types(&parsed_file.language),
+        name_pattern,
+        options,
+    )
+}
+
+/// Search for code constructs by their language-independent kind
+/// 
+/// Matches on `CodeConstruct::kind`, so the same call finds e.g. every
+/// class in a Python, Java or C++ file without naming each grammar's node
+/// types. See `get_construct_kind` for how node types are classified; note
+/// that Rust structs are `ConstructKind::Struct`, not `Class`.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `kind` - The construct kind to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_kind, ConstructKind, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     let classes = search_by_kind(&parsed, ConstructKind::Class, None);
+///     println!("Found {} classes", classes.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_by_kind(parsed_file: &ParsedFile, kind: ConstructKind, name_pattern: Option<&str>) -> Vec<CodeConstruct> {
+    search_by_kind_with_options(parsed_file, kind, name_pattern, &SearchOptions::default())
+}
+
+/// Search for code constructs by kind, with options for name matching
+/// 
+/// Behaves like `search_by_kind`, with `options` controlling how
+/// `name_pattern` is compiled.
+pub fn search_by_kind_with_options(
+    parsed_file: &ParsedFile,
+    kind: ConstructKind,
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
+) -> Vec<CodeConstruct> {
+    let regex = match name_pattern.map(|pattern| compile_name_pattern(pattern, options)) {
+        Some(Ok(regex)) => Some(regex),
+        Some(Err(_)) => return Vec::new(), // Invalid regex, return empty results
+        None => None,
+    };
+    
+    parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| construct.kind == kind)
+        .filter(|construct| match &regex {
+            Some(regex) => construct.name.as_ref().is_some_and(|name| regex.is_match(name)),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Search for module-level functions, excluding methods and nested functions
+/// 
+/// Like `search_functions`, but only returns functions that are not nested
+/// inside another construct. Declaration wrappers don't count as nesting, so
+/// decorated Python functions, exported JavaScript/TypeScript functions and
+/// functions assigned at module level (`var f = function () {}`,
+/// `f = lambda x: x`) are still included.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `name_pattern` - Optional regex pattern to filter results by function name
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_functions_top_level, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     for function in search_functions_top_level(&parsed, None) {
+///         println!("{:?}", function.name);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_functions_top_level(parsed_file: &ParsedFile, name_pattern: Option<&str>) -> Vec<CodeConstruct> {
+    search_functions(parsed_file, name_pattern)
+        .into_iter()
+        .filter(is_top_level)
+        .collect()
+}
+
+/// Node types that wrap a declaration without nesting it in another scope
+const DECLARATION_WRAPPER_NODE_TYPES: &[&str] = &[
+    "decorated_definition",
+    "export_statement",
+    "variable_declaration",
+    "assignment",
+];
+
+/// Check whether a construct's only enclosing constructs are declaration wrappers
+fn is_top_level(construct: &CodeConstruct) -> bool {
+    ancestors(construct).is_some_and(|ancestors| {
+        ancestors.iter().all(|ancestor| DECLARATION_WRAPPER_NODE_TYPES.contains(&ancestor.node_type.as_str()))
+    })
+}
+
+/// Search for functions defined inside other functions, such as closures
+/// 
+/// Returns functions whose nearest enclosing construct, skipping declaration
+/// wrappers like decorators and `var` declarations, is itself a function.
+/// Methods of a class defined inside a function are not included. Nested
+/// functions are also reachable as `children` of their enclosing function.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string_sync, search_nested_functions, Language};
+/// 
+/// let source = "def outer():\n    def inner():\n        pass\n    return inner\n";
+/// let parsed = parse_string_sync(source, Language::Python).unwrap();
+/// 
+/// let nested = search_nested_functions(&parsed);
+/// assert_eq!(nested.len(), 1);
+/// assert_eq!(nested[0].name.as_deref(), Some("inner"));
+/// ```
+pub fn search_nested_functions(parsed_file: &ParsedFile) -> Vec<CodeConstruct> {
+    let function_types = get_function_node_types(&parsed_file.language);
+    search_functions(parsed_file, None)
+        .into_iter()
+        .filter(|function| {
+            ancestors(function)
+                .and_then(|ancestors| {
+                    ancestors
+                        .into_iter()
+                        .find(|ancestor| !DECLARATION_WRAPPER_NODE_TYPES.contains(&ancestor.node_type.as_str()))
+                })
+                .is_some_and(|enclosing| function_types.contains(&enclosing.node_type.as_str()))
+        })
+        .collect()
+}
+
+/// Search for type aliases and typedefs using the language's alias node types
+/// 
+/// Covers Rust `type X = Y;`, TypeScript `type X = ...`, C/C++ `typedef`,
+/// C++ `using X = Y;` and Go `type X = Y` (see `get_type_alias_node_types`).
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_type_aliases, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/types.ts", Language::TypeScript).await?;
+///     for alias in search_type_aliases(&parsed) {
+///         println!("type {}", alias.name.as_deref().unwrap_or("<anonymous>"));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_type_aliases(parsed_file: &ParsedFile) -> Vec<CodeConstruct> {
+    search_by_multiple_node_types(
+        parsed_file,
+        get_type_alias_node_types(&parsed_file.language),
+        None,
+    )
+}
+
+/// Search for code constructs by node type across every file in a project
+/// 
+/// Each match is paired with the `file_path` of the file it was found in,
+/// in the same order as `project.files`.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to search within
+/// * `node_type` - The tree-sitter node type to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_directory, project_search_by_node_type, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     for (path, construct) in project_search_by_node_type(&project, "function_item", Some("^parse_")) {
+///         println!("{}:{} {}", path, construct.start_line, construct.name.unwrap_or_default());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn project_search_by_node_type(
+    project: &ParsedProject,
+    node_type: &str,
+    name_pattern: Option<&str>,
+) -> Vec<(String, CodeConstruct)> {
+    search_project(project, |file| search_by_node_type(file, node_type, name_pattern))
+}
+
+/// Search for code constructs by node type across a project, with options for name matching
+/// 
+/// See `search_by_node_type_with_options` for how `options` apply.
+pub fn project_search_by_node_type_with_options(
+    project: &ParsedProject,
+    node_type: &str,
+    name_pattern: Option<&str>,
+    options: &SearchOptions,
+) -> Vec<(String, CodeConstruct)> {
+    search_project(project, |file| search_by_node_type_with_options(file, node_type, name_pattern, options))
+}
+
+/// Search for code constructs matching any of several node types across a project
+/// 
+/// See `project_search_by_node_type` for the shape of the results.
+pub fn project_search_by_multiple_node_types(
+    project: &ParsedProject,
+    node_types: &[&str],
+    name_pattern: Option<&str>,
+) -> Vec<(String, CodeConstruct)> {
+    search_project(project, |file| search_by_multiple_node_types(file, node_types, name_pattern))
+}
+
+/// Search for functions and methods across a project, using each file's language
+pub fn project_search_functions(
+    project: &ParsedProject,
+    name_pattern: Option<&str>,
+) -> Vec<(String, CodeConstruct)> {
+    search_project(project, |file| search_functions(file, name_pattern))
+}
+
+/// Search for classes and similar type definitions across a project, using each file's language
+pub fn project_search_classes(
+    project: &ParsedProject,
+    name_pattern: Option<&str>,
+) -> Vec<(String, CodeConstruct)> {
+    search_project(project, |file| search_classes(file, name_pattern))
+}
+
+/// Execute a tree-sitter query against every file of a project
+/// 
+/// Runs `search_by_query` on each file and tags results with the file's
+/// path, like `project_search_by_node_type`. Files without a syntax tree
+/// (e.g. ones whose tree was dropped to save memory) are skipped, as are
+/// files whose language the query does not compile for, so a Python query
+/// can run over a mixed Python/Rust project.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to search
+/// * `query` - A tree-sitter query string
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_directory, project_search_by_query, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     let query = "(decorated_definition (function_definition name: (identifier) @name))";
+///     for (path, construct) in project_search_by_query(&project, query)? {
+///         println!("{}:{} {}", path, construct.start_line, construct.source_code);
+///     }
+///     Ok(())
+/// }
+/// ```
+/// 
+/// # Errors
+/// 
+/// Returns `Error::SyntaxTreeUnavailable` if no file retained its syntax
+/// tree, and the query error if the query compiles for none of the files.
+pub fn project_search_by_query(project: &ParsedProject, query: &str) -> Result<Vec<(String, CodeConstruct)>, Error> {
+    let mut results = Vec::new();
+    let mut searched_any = false;
+    let mut first_error = None;
+    
+    for file in project.files.iter().filter(|file| file.syntax_tree.is_some()) {
+        match search_by_query(file, query) {
+            Ok(constructs) => {
+                searched_any = true;
+                results.extend(constructs.into_iter().map(|construct| (file.file_path.clone(), construct)));
+            }
+            Err(error @ (Error::InvalidQuery(_) | Error::UnsupportedLanguage(_))) => {
+                first_error.get_or_insert(error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    
+    if searched_any {
+        Ok(results)
+    } else {
+        Err(first_error.unwrap_or(Error::SyntaxTreeUnavailable))
+    }
+}
+
+/// Find functions that are duplicated across a project
+/// 
+/// Functions are grouped using `CodeConstruct::structural_eq`, so copies
+/// with the same name and the same source up to whitespace form a cluster
+/// regardless of where they appear. Only clusters with two or more members
+/// are returned, each member paired with its file path like
+/// `project_search_functions`.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{find_duplicate_functions, parse_directory, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     for cluster in find_duplicate_functions(&project) {
+///         let locations: Vec<_> = cluster.iter()
+///             .map(|(path, function)| format!("{}:{}", path, function.start_line))
+///             .collect();
+///         println!("Duplicated: {}", locations.join(", "));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn find_duplicate_functions(project: &ParsedProject) -> Vec<Vec<(String, CodeConstruct)>> {
+    let mut clusters: Vec<Vec<(String, CodeConstruct)>> = Vec::new();
+    let mut clusters_by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+    
+    for (path, function) in project_search_functions(project, None) {
+        let candidates = clusters_by_hash.entry(function.structural_hash()).or_default();
+        // Hash collisions are resolved by checking structural equality
+        match candidates.iter().find(|&&index| clusters[index][0].1.structural_eq(&function)) {
+            Some(&index) => clusters[index].push((path, function)),
+            None => {
+                candidates.push(clusters.len());
+                clusters.push(vec![(path, function)]);
+            }
+        }
+    }
+    
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
+
+/// Group constructs by name, collecting overloads together
+/// 
+/// Java and C++ allow several methods to share a name; this gathers them
+/// under that name, in input order, so a navigation UI can show one entry
+/// per name. The overloads in each group can be told apart by their
+/// `metadata.parameters`. Unnamed constructs are skipped.
+/// 
+/// # Arguments
+/// 
+/// * `constructs` - The constructs to group, typically from `search_functions`
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{group_overloads, parse_file, search_functions, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("Calculator.java", Language::Java).await?;
+///     
+///     for (name, overloads) in group_overloads(search_functions(&parsed, None)) {
+///         for overload in &overloads {
+///             let types: Vec<_> = overload.metadata.parameters.iter()
+///                 .map(|p| p.param_type.clone().unwrap_or_default())
+///                 .collect();
+///             println!("{}({})", name, types.join(", "));
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn group_overloads(constructs: Vec<CodeConstruct>) -> HashMap<String, Vec<CodeConstruct>> {
+    let mut groups: HashMap<String, Vec<CodeConstruct>> = HashMap::new();
+    for construct in constructs {
+        if let Some(name) = construct.name.clone() {
+            groups.entry(name).or_default().push(construct);
+        }
+    }
+    groups
+}
+
+/// Find the constructs whose names best match a fuzzy query across a project
+/// 
+/// A name matches when the query's characters appear in it in order, not
+/// necessarily adjacent, ignoring case (so `prsfl` matches `parse_file`).
+/// Matches score higher when matched characters are consecutive or start a
+/// word (after `_`, `.`, `:` or `-`, or at a lowercase-to-uppercase change),
+/// and lower for unmatched characters. Results are sorted best first, with
+/// ties broken by shorter name and then by location, and cut to `limit`.
+/// 
+/// # Arguments
+/// 
+/// * `project` - The parsed project to search
+/// * `query` - The characters to look for; an empty query matches nothing
+/// * `limit` - The maximum number of matches to return
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{fuzzy_find_symbols, parse_directory, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let project = parse_directory("./src", ParseOptions::default()).await?;
+///     
+///     for symbol in fuzzy_find_symbols(&project, "prsfl", 10) {
+///         println!("{} {}:{}", symbol.name, symbol.file_path, symbol.start_line);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn fuzzy_find_symbols(project: &ParsedProject, query: &str, limit: usize) -> Vec<SymbolMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    
+    let mut matches: Vec<SymbolMatch> = Vec::new();
+    for file in &project.files {
+        for construct in file.all_constructs() {
+            let Some(name) = &construct.name else {
+                continue;
+            };
+            if let Some(score) = fuzzy_score(name, &query) {
+                matches.push(SymbolMatch {
+                    name: name.clone(),
+                    node_type: construct.node_type.clone(),
+                    file_path: file.file_path.clone(),
+                    start_line: construct.start_line,
+                    start_column: construct.start_column,
+                    score,
+                });
+            }
+        }
+    }
+    
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then(a.start_line.cmp(&b.start_line))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// Score `candidate` against a lowercased query, or `None` if it is not a subsequence
+fn fuzzy_score(candidate: &str, query: &[char]) -> Option<i64> {
+    const MATCH: i64 = 16;
+    const CONSECUTIVE_BONUS: i64 = 12;
+    const BOUNDARY_BONUS: i64 = 10;
+    const GAP_PENALTY: i64 = 1;
+    
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_match: Option<usize> = None;
+    
+    for &wanted in query {
+        let position = (next..chars.len()).find(|&i| chars[i].to_lowercase().eq(std::iter::once(wanted)))?;
+        score += MATCH;
+        if previous_match.is_some_and(|previous| previous + 1 == position) {
+            score += CONSECUTIVE_BONUS;
+        }
+        let at_boundary = position == 0
+            || matches!(chars[position - 1], '_' | '.' | ':' | '-')
+            || (chars[position - 1].is_lowercase() && chars[position].is_uppercase());
+        if at_boundary {
+            score += BOUNDARY_BONUS;
+        }
+        previous_match = Some(position);
+        next = position + 1;
+    }
+    
+    score -= GAP_PENALTY * (chars.len() - query.len()) as i64;
+    Some(score)
+}
+
+/// Run a per-file search over every file in a project, tagging results with their path
+fn search_project<F>(project: &ParsedProject, search: F) -> Vec<(String, CodeConstruct)>
+where
+    F: Fn(&ParsedFile) -> Vec<CodeConstruct>,
+{
+    project
+        .files
+        .iter()
+        .flat_map(|file| {
+            search(file)
+                .into_iter()
+                .map(|construct| (file.file_path.clone(), construct))
+        })
+        .collect()
+}
+
+/// Filter code constructs with an arbitrary predicate
+/// 
+/// This composes with the search functions, e.g. to keep only public
+/// async functions. See `is_public`, `is_async` and `has_annotation` for
+/// ready-made predicates.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{filter_constructs, is_async, is_public, parse_file, search_functions, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/lib.rs", Language::Rust).await?;
+///     let public_async = filter_constructs(search_functions(&parsed, None), |c| is_public(c) && is_async(c));
+///     println!("Found {} public async functions", public_async.len());
+///     Ok(())
+/// }
+/// ```
+pub fn filter_constructs<F: Fn(&CodeConstruct) -> bool>(
+    constructs: Vec<CodeConstruct>,
+    pred: F,
+) -> Vec<CodeConstruct> {
+    constructs.into_iter().filter(|construct| pred(construct)).collect()
+}
+
+/// Check whether a construct is declared public (`pub` in Rust, `public` in Java/TypeScript)
+/// 
+/// Restricted visibilities such as `pub(crate)` are not considered public.
+/// Languages without visibility modifiers never match.
+pub fn is_public(construct: &CodeConstruct) -> bool {
+    matches!(construct.metadata.visibility.as_deref(), Some("pub" | "public"))
+}
+
+/// Check whether a construct carries the `async` modifier
+pub fn is_async(construct: &CodeConstruct) -> bool {
+    construct.metadata.modifiers.iter().any(|modifier| modifier == "async")
+}
+
+/// Create a predicate matching constructs that carry the named annotation
+/// 
+/// The name is compared against the annotation without its sigil and
+/// arguments, so `"derive"` matches `#[derive(Debug)]`, `"Override"` matches
+/// `@Override` and `"app.route"` matches `@app.route("/")`.
+pub fn has_annotation(name: &str) -> impl Fn(&CodeConstruct) -> bool + '_ {
+    move |construct| {
+        construct.metadata.annotations.iter().any(|annotation| annotation_name(annotation) == name)
+    }
+}
+
+/// Check whether a construct is test code, using per-language conventions
+/// 
+/// * Rust: `#[test]` (or a `::test` attribute such as `#[tokio::test]`), or
+///   `#[cfg(test)]` on the construct or any enclosing construct
+/// * Python: `test_`-prefixed functions and classes inheriting `TestCase`
+/// * JavaScript/TypeScript: callbacks passed to `describe`, `it` or `test`
+///   (including forms such as `it.only`)
+/// * Java: methods annotated with `@Test`
+/// * Go: functions named `TestXxx`
+/// 
+/// Other languages never match.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct to check
+/// * `language` - The language of the file the construct came from
+pub fn is_test_construct(construct: &CodeConstruct, language: &Language) -> bool {
+    let name = construct.name.as_deref().unwrap_or_default();
+    let annotations = || construct.metadata.annotations.iter().map(|annotation| annotation_name(annotation));
+    
+    match language {
+        Language::Rust => {
+            if annotations().any(|annotation| annotation == "test" || annotation.ends_with("::test")) {
+                return true;
+            }
+            let mut current = Some(construct);
+            while let Some(node) = current {
+                if node.metadata.annotations.iter().any(|annotation| is_cfg_test(annotation)) {
+                    return true;
+                }
+                current = node.parent.as_deref();
+            }
+            false
+        }
+        Language::Python => match construct.node_type.as_str() {
+            "function_definition" => name.starts_with("test_"),
+            "class_definition" => python_superclasses(&construct.source_code)
+                .is_some_and(|bases| bases.split(',').any(|base| base.trim().rsplit('.').next() == Some("TestCase"))),
+            _ => false,
+        },
+        Language::JavaScript | Language::TypeScript => construct.metadata.registered_by.as_deref().is_some_and(|callee| {
+            matches!(callee.split('.').next(), Some("describe" | "it" | "test"))
+        }),
+        Language::Java => annotations().any(|annotation| annotation == "Test"),
+        Language::Go => name
+            .strip_prefix("Test")
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase())),
+        _ => false,
+    }
+}
+
+/// Check whether a raw Rust attribute is `#[cfg(test)]`
+fn is_cfg_test(annotation: &str) -> bool {
+    let compact: String = annotation.chars().filter(|c| !c.is_whitespace()).collect();
+    compact == "#[cfg(test)]"
+}
+
+/// Get the text between the parentheses of a Python class header (`class A(B, C):` -> `B, C`)
+fn python_superclasses(source: &str) -> Option<&str> {
+    let header = source.split(':').next()?;
+    let open = header.find('(')?;
+    let close = header.rfind(')')?;
+    header.get(open + 1..close)
+}
+
+/// Find the test functions in a parsed file
+/// 
+/// Combines `search_functions` with `is_test_construct`, so it returns
+/// `#[test]` functions in Rust, `test_` functions in Python, `describe`/`it`
+/// callbacks in JavaScript and so on.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_test_functions, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/lib.rs", Language::Rust).await?;
+///     for test in search_test_functions(&parsed) {
+///         println!("{}", test.name.unwrap_or_default());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_test_functions(parsed_file: &ParsedFile) -> Vec<CodeConstruct> {
+    filter_constructs(search_functions(parsed_file, None), |construct| {
+        is_test_construct(construct, &parsed_file.language)
+    })
+}
+
+/// Find the constructs that make up a file's public API surface
+/// 
+/// What counts as exported depends on the language:
+/// 
+/// * JavaScript/TypeScript: every `export_statement`, including default
+///   exports, export clauses and re-exports (`export * from './x'`). Each
+///   is named after its declaration, or `default` for `export default <expr>`.
+/// * Rust: items declared `pub`, including `pub use` re-exports
+/// * Python: the module-level definitions listed in `__all__`, or, without
+///   `__all__`, module-level functions, classes and assignments whose names
+///   don't start with `_`
+/// * Other languages: constructs declared public (see `is_public`)
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// 
+/// # Returns
+/// 
+/// The exported constructs in source order.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_exports, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/index.ts", Language::TypeScript).await?;
+///     for export in search_exports(&parsed) {
+///         println!("{}", export.name.as_deref().unwrap_or("<re-export>"));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_exports(parsed_file: &ParsedFile) -> Vec<CodeConstruct> {
+    let constructs = parsed_file.all_constructs();
+    
+    let exported: Vec<&CodeConstruct> = match parsed_file.language {
+        Language::JavaScript | Language::TypeScript => constructs
+            .into_iter()
+            .filter(|construct| construct.node_type == "export_statement")
+            .collect(),
+        Language::Python => {
+            let module_level: Vec<&CodeConstruct> = constructs
+                .into_iter()
+                .filter(|construct| is_python_module_level(construct))
+                .collect();
+            let dunder_all = module_level
+                .iter()
+                .find(|construct| construct.node_type == "assignment" && construct.name.as_deref() == Some("__all__"))
+                .map(|construct| python_string_items(&construct.source_code));
+            
+            module_level
+                .into_iter()
+                .filter(|construct| matches!(construct.node_type.as_str(), "function_definition" | "class_definition" | "assignment"))
+                .filter(|construct| {
+                    let Some(name) = construct.name.as_deref() else {
+                        return false;
+                    };
+                    match &dunder_all {
+                        Some(names) => names.iter().any(|listed| listed == name),
+                        None => !name.starts_with('_'),
+                    }
+                })
+                .collect()
+        }
+        _ => constructs.into_iter().filter(|construct| is_public(construct)).collect(),
+    };
+    
+    exported.into_iter().cloned().collect()
+}
+
+/// Check whether a Python construct sits at module level (possibly under decorators)
+fn is_python_module_level(construct: &CodeConstruct) -> bool {
+    ancestors(construct).is_some_and(|ancestors| {
+        ancestors.iter().all(|ancestor| ancestor.node_type == "decorated_definition")
+    })
+}
+
+/// Get a construct's enclosing constructs, innermost first
+/// 
+/// Returns `None` when the `parent` snapshots stop short of `depth`, as for
+/// a construct deserialized on its own, so that a detached construct is
+/// never mistaken for a top-level one.
+fn ancestors(construct: &CodeConstruct) -> Option<Vec<&CodeConstruct>> {
+    let mut ancestors = Vec::new();
+    let mut parent = construct.parent.as_deref();
+    while let Some(current) = parent {
+        ancestors.push(current);
+        parent = current.parent.as_deref();
+    }
+    (ancestors.len() == construct.depth).then_some(ancestors)
+}
+
+/// Get the string literals on the right-hand side of an assignment like `__all__ = ["a", 'b']`
+fn python_string_items(assignment: &str) -> Vec<String> {
+    let Some((_, value)) = assignment.split_once('=') else {
+        return Vec::new();
+    };
+    let pattern = Regex::new(r#"["']([^"']*)["']"#).expect("valid string literal pattern");
+    pattern
+        .captures_iter(value)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// Find every construct that overlaps a range of lines
+/// 
+/// Returns constructs whose `[start_line, end_line]` span intersects the
+/// inclusive range `[start_line, end_line]` (1-based, like the construct
+/// fields). This includes constructs that enclose the whole range, such as
+/// a class around a visible method, and constructs cut off by either edge.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `start_line` - First line of the range (inclusive)
+/// * `end_line` - Last line of the range (inclusive)
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_line_range, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/main.rs", Language::Rust).await?;
+///     
+///     // Constructs visible in an editor viewport showing lines 40-80
+///     for construct in search_by_line_range(&parsed, 40, 80) {
+///         println!("{} at {}-{}", construct.node_type, construct.start_line, construct.end_line);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_by_line_range(parsed_file: &ParsedFile, start_line: usize, end_line: usize) -> Vec<CodeConstruct> {
+    parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| construct.start_line <= end_line && construct.end_line >= start_line)
+        .cloned()
+        .collect()
+}
+
+/// Find constructs spanning at least `min_lines` lines
+/// 
+/// A construct's size is `end_line - start_line + 1`, so a one-line function
+/// has size 1. This is handy for spotting oversized ("god") functions or
+/// classes.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `node_type` - Optional node type to restrict results to
+/// * `min_lines` - Minimum size in lines (inclusive)
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_large_constructs, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("src/server.py", Language::Python).await?;
+///     
+///     for function in search_large_constructs(&parsed, Some("function_definition"), 100) {
+///         println!("{} is {} lines long", function.name.unwrap_or_default(), function.end_line - function.start_line + 1);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_large_constructs(parsed_file: &ParsedFile, node_type: Option<&str>, min_lines: usize) -> Vec<CodeConstruct> {
+    search_by_line_count(parsed_file, node_type, |lines| lines >= min_lines)
+}
+
+/// Find constructs spanning at most `max_lines` lines
+/// 
+/// The counterpart of `search_large_constructs`, e.g. for finding trivially
+/// small wrapper functions.
+pub fn search_small_constructs(parsed_file: &ParsedFile, node_type: Option<&str>, max_lines: usize) -> Vec<CodeConstruct> {
+    search_by_line_count(parsed_file, node_type, |lines| lines <= max_lines)
+}
+
+/// Collect constructs of an optional node type whose line count satisfies `accept`
+fn search_by_line_count<F>(parsed_file: &ParsedFile, node_type: Option<&str>, accept: F) -> Vec<CodeConstruct>
+where
+    F: Fn(usize) -> bool,
+{
+    parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| node_type.is_none_or(|node_type| construct.node_type == node_type))
+        .filter(|construct| accept(construct.end_line - construct.start_line + 1))
+        .cloned()
+        .collect()
+}
+
+/// Search for code constructs by node type within a single construct's subtree
+/// 
+/// This function works like `search_by_node_type` but only looks at the
+/// descendants of the given construct (its `children`, recursively), which
+/// avoids re-scanning the whole file when drilling into a known scope.
+/// The construct itself is not included in the results.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct whose subtree should be searched
+/// * `node_type` - The tree-sitter node type to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// 
+/// # Returns
+/// 
+/// A vector of matching descendant `CodeConstruct` objects in source order.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_node_type, search_in_construct, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     
+///     for class in search_by_node_type(&parsed, "class_definition", Some("^Cache")) {
+///         let methods = search_in_construct(&class, "function_definition", None);
+///         println!("{:?} has {} methods", class.name, methods.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_in_construct(
+    construct: &CodeConstruct,
+    node_type: &str,
+    name_pattern: Option<&str>,
+) -> Vec<CodeConstruct> {
+    let mut results = Vec::new();
+    
+    // Compile regex pattern if provided
+    let regex = if let Some(pattern) = name_pattern {
+        match Regex::new(pattern) {
+            Ok(r) => Some(r),
+            Err(_) => return results, // Invalid regex, return empty results
+        }
+    } else {
+        None
+    };
+    
+    collect_descendants_by_node_type(&construct.children, node_type, regex.as_ref(), &mut results);
+    
+    results
+}
+
+/// Node types that open a namespace or module scope
+const NAMESPACE_NODE_TYPES: &[&str] = &["namespace_definition", "namespace_declaration", "mod_item"];
+
+/// Search for code constructs inside a namespace or module, located by path
+/// 
+/// The namespace is found by following `namespace_path` through nested
+/// C++ `namespace_definition`s, C# `namespace_declaration`s or Rust
+/// `mod_item`s from the top of the file, so `&["outer", "inner"]` matches
+/// `namespace outer { namespace inner { ... } }` as well as
+/// `namespace outer::inner { ... }`. Every construct of `node_type` within
+/// the matching namespaces (including reopened ones) is returned, as with
+/// `search_in_construct`. An empty path searches the whole file.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// * `namespace_path` - Names of the enclosing namespaces, outermost first
+/// * `node_type` - The tree-sitter node type to search for
+/// * `name_pattern` - Optional regex pattern to filter results by construct name
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_in_namespace, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("engine.cpp", Language::Cpp).await?;
+///     let functions = search_in_namespace(&parsed, &["engine", "render"], "function_definition", Some("^draw"));
+///     println!("Found {} draw functions in engine::render", functions.len());
+///     Ok(())
+/// }
+/// ```
+pub fn search_in_namespace(
+    parsed_file: &ParsedFile,
+    namespace_path: &[&str],
+    node_type: &str,
+    name_pattern: Option<&str>,
+) -> Vec<CodeConstruct> {
+    if namespace_path.is_empty() {
+        return search_by_node_type(parsed_file, node_type, name_pattern);
+    }
+    
+    let roots: Vec<&CodeConstruct> = parsed_file.constructs.iter().filter(|c| c.depth == 0).collect();
+    let mut namespaces = Vec::new();
+    find_namespaces(&roots, namespace_path, &mut namespaces);
+    
+    namespaces
+        .into_iter()
+        .flat_map(|namespace| search_in_construct(namespace, node_type, name_pattern))
+        .collect()
+}
+
+/// Collect the namespaces reached by following `path` from `constructs`
+fn find_namespaces<'a>(constructs: &[&'a CodeConstruct], path: &[&str], found: &mut Vec<&'a CodeConstruct>) {
+    for &construct in constructs {
+        if !NAMESPACE_NODE_TYPES.contains(&construct.node_type.as_str()) {
+            continue;
+        }
+        let Some(name) = &construct.name else {
+            continue;
+        };
+        
+        // A nested definition such as `namespace a::b` consumes several segments at once
+        let segments: Vec<&str> = name.split("::").map(str::trim).collect();
+        if !path.starts_with(&segments) {
+            continue;
+        }
+        let remaining = &path[segments.len()..];
+        if remaining.is_empty() {
+            found.push(construct);
+        } else {
+            let children: Vec<&CodeConstruct> = construct.children.iter().collect();
+            find_namespaces(&children, remaining, found);
+        }
+    }
+}
+
+/// Recursively collect constructs of a node type from a construct hierarchy
+fn collect_descendants_by_node_type(
+    constructs: &[CodeConstruct],
+    node_type: &str,
+    regex: Option<&Regex>,
+    results: &mut Vec<CodeConstruct>,
+) {
+    for construct in constructs {
+        if construct.node_type == node_type {
+            let name_matches = match regex {
+                Some(regex) => construct.name.as_ref().is_some_and(|name| regex.is_match(name)),
+                None => true,
+            };
+            if name_matches {
+                results.push(construct.clone());
+            }
+        }
+        
+        collect_descendants_by_node_type(&construct.children, node_type, regex, results);
+    }
+}
+
+/// Find the nearest construct of one of the given kinds that encloses a target
+/// 
+/// Ancestors are found through the file's own construct hierarchy by byte
+/// range rather than the cloned `parent` snapshots, so the result borrows
+/// from `parsed_file` and works whether or not the file was flattened. The
+/// target itself is never returned, and it does not have to be one of the
+/// file's constructs (a construct from `search_by_query` works too).
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file the target came from
+/// * `target` - The construct whose ancestor to find
+/// * `kinds` - Node types to accept, e.g. the language's function and class node types
+/// 
+/// # Returns
+/// 
+/// The innermost enclosing construct whose node type is in `kinds`, or
+/// `None` if there is none.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{enclosing_construct, parse_file, search_functions, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     
+///     for method in search_functions(&parsed, None) {
+///         if let Some(class) = enclosing_construct(&parsed, &method, &["class_definition"]) {
+///             println!("{:?} belongs to {:?}", method.name, class.name);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn enclosing_construct<'a>(
+    parsed_file: &'a ParsedFile,
+    target: &CodeConstruct,
+    kinds: &[&str],
+) -> Option<&'a CodeConstruct> {
+    parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| kinds.contains(&construct.node_type.as_str()))
+        .filter(|construct| construct.start_byte <= target.start_byte && target.end_byte <= construct.end_byte)
+        .filter(|construct| {
+            (construct.start_byte, construct.end_byte, construct.node_type.as_str())
+                != (target.start_byte, target.end_byte, target.node_type.as_str())
+        })
+        .min_by_key(|construct| construct.end_byte - construct.start_byte)
+}
+
+/// Get the innermost constructs of a file, those with no constructs nested inside
+/// 
+/// Built on `ParsedFile::all_constructs`, so each leaf appears once whether
+/// or not the file was flattened.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file to search within
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{leaf_constructs, parse_string_sync, Language};
+/// 
+/// let source = "class Shape:\n    def area(self):\n        pass\n";
+/// let parsed = parse_string_sync(source, Language::Python).unwrap();
+/// 
+/// let leaves = leaf_constructs(&parsed);
+/// assert_eq!(leaves.len(), 1);
+/// assert_eq!(leaves[0].name.as_deref(), Some("area"));
+/// ```
+pub fn leaf_constructs(parsed_file: &ParsedFile) -> Vec<&CodeConstruct> {
+    parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| construct.children.is_empty())
+        .collect()
+}
+
+/// Get the names of the constructs enclosing a target, from outermost down to the target itself
+/// 
+/// Like `enclosing_construct`, ancestors are resolved through the file's own
+/// construct hierarchy by byte range, so this works whether or not the file
+/// was flattened. Anonymous constructs (those without a name, such as Python
+/// decorator wrappers or lambdas) are skipped, including the target itself.
+/// 
+/// # Arguments
+/// 
+/// * `parsed_file` - The parsed file the target came from
+/// * `target` - The construct to build the breadcrumb for
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{breadcrumb, parse_string_sync, search_by_node_type, Language};
+/// 
+/// let source = "class Outer:\n    def method(self):\n        pass\n";
+/// let parsed = parse_string_sync(source, Language::Python).unwrap();
+/// let method = &search_by_node_type(&parsed, "function_definition", None)[0];
+/// 
+/// assert_eq!(breadcrumb(&parsed, method).join(" > "), "Outer > method");
+/// ```
+pub fn breadcrumb(parsed_file: &ParsedFile, target: &CodeConstruct) -> Vec<String> {
+    let mut ancestors: Vec<&CodeConstruct> = parsed_file
+        .all_constructs()
+        .into_iter()
+        .filter(|construct| construct.start_byte <= target.start_byte && target.end_byte <= construct.end_byte)
+        .filter(|construct| {
+            (construct.start_byte, construct.end_byte, construct.node_type.as_str())
+                != (target.start_byte, target.end_byte, target.node_type.as_str())
+        })
+        .collect();
+    // Stable, so of two equally sized ancestors the outer one (visited first) stays first
+    ancestors.sort_by_key(|construct| std::cmp::Reverse(construct.end_byte - construct.start_byte));
+    
+    ancestors
+        .into_iter()
+        .chain(std::iter::once(target))
+        .filter_map(|construct| construct.name.clone())
+        .collect()
+}
+
+/// Resolve the raw tree-sitter node behind a construct
+/// 
+/// Constructs only keep byte offsets, so the node is looked up again in
+/// `tree` with `descendant_for_byte_range`. When a wrapper and its only
+/// child span the same bytes, the ancestor whose kind matches the
+/// construct's `node_type` is preferred.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct to resolve
+/// * `tree` - The syntax tree the construct was extracted from, usually `ParsedFile::syntax_tree`
+/// 
+/// # Returns
+/// 
+/// The node spanning exactly the construct's bytes, or `None` if there is none
+/// (for example when `tree` belongs to a different file).
 /// 
 /// # Examples
 /// 
 /// ```rust
-/// use tree_parser::{parse_file, search_by_multiple_node_types, Language};
+/// use tree_parser::{node_for, parse_string_sync, search_functions, Language};
 /// 
-/// #[tokio::main]
-/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     let parsed = parse_file("example.js", Language::JavaScript).await?;
-///     
-///     // Find all function-like constructs
-///     let functions = search_by_multiple_node_types(
-///         &parsed,
-///         &["function_declaration", "function_expression", "arrow_function"],
-///         None
-///     );
-///     
-///     println!("Found {} function-like constructs", functions.len());
-///     Ok(())
-/// }
+/// let parsed = parse_string_sync("def greet(name):\n    pass\n", Language::Python).unwrap();
+/// let tree = parsed.syntax_tree.as_ref().unwrap();
+/// let function = &search_functions(&parsed, None)[0];
+/// 
+/// let node = node_for(function, tree).unwrap();
+/// assert_eq!(node.child_by_field_name("parameters").unwrap().kind(), "parameters");
 /// ```
-pub fn search_by_multiple_node_types(
-    parsed_file: &ParsedFile,
-    node_types: &[&str],
-    name_pattern: Option<&str>,
-) -> Vec<CodeConstruct> {
-    let mut results = Vec::new();
-    
-    // Compile regex pattern if provided
-    let regex = if let Some(pattern) = name_pattern {
-        match Regex::new(pattern) {
-            Ok(r) => Some(r),
-            Err(_) => return results, // Invalid regex, return empty results
-        }
-    } else {
-        None
-    };
+pub fn node_for<'t>(construct: &CodeConstruct, tree: &'t tree_sitter::Tree) -> Option<tree_sitter::Node<'t>> {
+    let node = tree
+        .root_node()
+        .descendant_for_byte_range(construct.start_byte, construct.end_byte)?;
+    if node.start_byte() != construct.start_byte || node.end_byte() != construct.end_byte {
+        return None;
+    }
     
-    // Search through all constructs (already flattened, no need for recursive search)
-    for construct in &parsed_file.constructs {
-        if node_types.contains(&construct.node_type.as_str()) {
-            // Check name pattern if provided
-            if let Some(ref regex) = regex {
-                if let Some(ref name) = construct.name {
-                    if regex.is_match(name) {
-                        results.push(construct.clone());
-                    }
-                }
-            } else {
-                results.push(construct.clone());
-            }
+    let mut candidate = Some(node);
+    while let Some(current) = candidate
+        && current.byte_range() == node.byte_range()
+    {
+        if current.kind() == construct.node_type {
+            return Some(current);
         }
+        candidate = current.parent();
     }
-    
-    results
+    Some(node)
 }
 
 /// Execute a custom tree-sitter query for advanced searching
@@ -165,9 +1621,13 @@ pub fn search_by_multiple_node_types(
 /// A `Result` containing a vector of `CodeConstruct` objects that match
 /// the query, or an `Error` if the query is invalid or execution fails.
 /// 
+/// Results come back in tree-sitter capture order, which interleaves the
+/// captures of each match and is not source order. Use `sort_by_position`
+/// to order them top-to-bottom.
+/// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use tree_parser::{parse_file, search_by_query, Language};
 /// 
 /// #[tokio::main]
@@ -191,7 +1651,7 @@ pub fn search_by_multiple_node_types(
 /// 
 /// This function will return an error if:
 /// - The query syntax is invalid
-/// - The syntax tree is not available
+/// - The syntax tree was not retained (`Error::SyntaxTreeUnavailable`)
 /// - File I/O operations fail
 pub fn search_by_query(
     parsed_file: &ParsedFile,
@@ -201,7 +1661,7 @@ pub fn search_by_query(
     
     // Get the syntax tree
     let tree = parsed_file.syntax_tree.as_ref()
-        .ok_or_else(|| Error::Parse("No syntax tree available".to_string()))?;
+        .ok_or(Error::SyntaxTreeUnavailable)?;
     
     // Get the tree-sitter language
     let ts_language = get_tree_sitter_language(&parsed_file.language)?;
@@ -212,9 +1672,8 @@ pub fn search_by_query(
     
     let mut cursor = QueryCursor::new();
     
-    // Read the source code to extract text
-    let source = std::fs::read_to_string(&parsed_file.file_path)
-        .map_err(|e| Error::Io(e.to_string()))?;
+    // Use the retained source text, falling back to reading the file
+    let source = retained_source(parsed_file)?;
     
     let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
     while let Some(query_match) = matches.next() {
@@ -228,31 +1687,66 @@ pub fn search_by_query(
     Ok(results)
 }
 
+/// Sort code constructs by their position in the source file
+/// 
+/// Constructs are ordered by `(start_byte, end_byte)` ascending so that
+/// results read top-to-bottom as they appear in the file. This is mainly
+/// useful for `search_by_query` results, which are otherwise unordered.
+/// 
+/// # Arguments
+/// 
+/// * `constructs` - The constructs to sort in place
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{parse_file, search_by_query, sort_by_position, Language};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let parsed = parse_file("example.py", Language::Python).await?;
+///     
+///     let mut results = search_by_query(&parsed, "(function_definition) @function")?;
+///     sort_by_position(&mut results);
+///     Ok(())
+/// }
+/// ```
+pub fn sort_by_position(constructs: &mut [CodeConstruct]) {
+    constructs.sort_by_key(|construct| (construct.start_byte, construct.end_byte));
+}
+
 /// Create a CodeConstruct from a tree-sitter node (used in query search)
 fn create_code_construct_from_node(
     node: tree_sitter::Node,
     source: &str,
-    _language: &Language,
+    language: &Language,
 ) -> CodeConstruct {
     let start_byte = node.start_byte();
     let end_byte = node.end_byte();
-    let source_code = source[start_byte..end_byte].to_string();
+    let source_code = slice_source(source, start_byte, end_byte);
     
     let start_point = node.start_position();
     let end_point = node.end_position();
     
     // Extract name if possible
-    let name = extract_node_name(node, source);
+    let name = extract_node_name(node, source, language);
     
     CodeConstruct {
         node_type: node.kind().to_string(),
+        kind: get_construct_kind(language, node.kind()),
+        language: Some(language.clone()),
         name,
+        synthetic_name: None,
         source_code,
         start_line: start_point.row + 1, // Convert to 1-based
         end_line: end_point.row + 1,
+        start_column: start_point.column,
+        end_column: end_point.column,
         start_byte,
         end_byte,
+        body_range: node.child_by_field_name("body").map(|body| (body.start_byte(), body.end_byte())),
         parent: None,
+        depth: 0,
         children: Vec::new(),
         metadata: crate::ConstructMetadata {
             visibility: None,
@@ -261,21 +1755,25 @@ fn create_code_construct_from_node(
             return_type: None,
             inheritance: Vec::new(),
             annotations: Vec::new(),
+            parsed_annotations: Vec::new(),
             documentation: None,
+            field_type: None,
+            type_parameters: Vec::new(),
+            registered_by: None,
         },
     }
 }
 
 /// Extract name from a tree-sitter node
-fn extract_node_name(node: tree_sitter::Node, source: &str) -> Option<String> {
-    // Try to find identifier child
+fn extract_node_name(node: tree_sitter::Node, source: &str, language: &Language) -> Option<String> {
+    // Try to find a name-bearing child
+    let name_kinds = get_name_node_types(language);
     for i in 0..node.child_count() {
-        if let Some(child) = node.child(i) {
-            if child.kind() == "identifier" || child.kind() == "name" {
-                let start = child.start_byte();
-                let end = child.end_byte();
-                return Some(source[start..end].to_string());
-            }
+        if let Some(child) = node.child(i)
+            && name_kinds.contains(&child.kind()) {
+            let start = child.start_byte();
+            let end = child.end_byte();
+            return Some(slice_source(source, start, end));
         }
     }
     None
@@ -284,10 +1782,18 @@ fn extract_node_name(node: tree_sitter::Node, source: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{parse_file, Language};
+    use crate::{parse_string_sync, Language};
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::parse_file;
+    #[cfg(not(target_arch = "wasm32"))]
     use std::fs;
-    use tokio;
+    
+    /// Serialize a parsed file to JSON and back, dropping the tree and `parent` snapshots
+    fn round_trip(parsed: &ParsedFile) -> ParsedFile {
+        serde_json::from_str(&serde_json::to_string(parsed).unwrap()).unwrap()
+    }
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn test_no_duplicate_results() {
         // Create a test Python file with nested functions
@@ -321,10 +1827,9 @@ class CacheEngine:
         // Verify the functions have different parents
         let mut parent_names = Vec::new();
         for func in &functions {
-            if let Some(parent) = &func.parent {
-                if let Some(parent_name) = &parent.name {
-                    parent_names.push(parent_name.clone());
-                }
+            if let Some(parent) = &func.parent
+                && let Some(parent_name) = &parent.name {
+                parent_names.push(parent_name.clone());
             }
         }
         
@@ -336,4 +1841,698 @@ class CacheEngine:
         // Clean up
         fs::remove_file(test_file).ok();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_search_in_construct() {
+        let source = r#"
+class Reader:
+    def open(self):
+        pass
+
+    def read(self):
+        pass
+
+class Writer:
+    def open(self):
+        pass
+
+    def write(self):
+        pass
+"#;
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let writer = search_by_node_type(&parsed, "class_definition", Some("^Writer$"))
+            .pop()
+            .expect("Writer class should exist");
+        
+        let methods = search_in_construct(&writer, "function_definition", None);
+        let names: Vec<_> = methods.iter().filter_map(|m| m.name.as_deref()).collect();
+        assert_eq!(names, vec!["open", "write"]);
+        
+        let opens = search_in_construct(&writer, "function_definition", Some("^open$"));
+        assert_eq!(opens.len(), 1);
+        assert_eq!(opens[0].parent.as_ref().and_then(|p| p.name.as_deref()), Some("Writer"));
+    }
+
+    #[test]
+    fn test_case_insensitive_name_pattern() {
+        let source = "def foo():\n    pass\n\ndef bar():\n    pass\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        assert!(search_by_node_type(&parsed, "function_definition", Some("^FOO$")).is_empty());
+        
+        let options = SearchOptions { case_insensitive: true };
+        let found = search_by_node_type_with_options(&parsed, "function_definition", Some("^FOO$"), &options);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name.as_deref(), Some("foo"));
+        
+        let found = search_by_multiple_node_types_with_options(&parsed, &["function_definition"], Some("FOO|BAR"), &options);
+        assert_eq!(found.len(), 2);
+        
+        assert!(search_functions(&parsed, Some("^FOO$")).is_empty());
+        assert_eq!(search_functions_with_options(&parsed, Some("^FOO$"), &options).len(), 1);
+        assert_eq!(search_by_kind_with_options(&parsed, ConstructKind::Function, Some("^BAR$"), &options).len(), 1);
+        
+        let classes = parse_string_sync("class Widget:\n    pass\n", Language::Python).expect("Failed to parse source");
+        assert!(search_classes(&classes, Some("^widget$")).is_empty());
+        assert_eq!(search_classes_with_options(&classes, Some("^widget$"), &options).len(), 1);
+    }
+
+    #[test]
+    fn test_sort_by_position() {
+        let source = r#"
+class Service:
+    def start(self):
+        pass
+
+    def stop(self):
+        pass
+"#;
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        // Each match captures the class name again after the previous method
+        let query = r#"
+            (class_definition
+              name: (identifier) @class_name
+              body: (block (function_definition name: (identifier) @method_name)))
+        "#;
+        let mut results = search_by_query(&parsed, query).expect("Query should run");
+        let names: Vec<_> = results.iter().map(|c| c.source_code.as_str()).collect();
+        assert_eq!(names, vec!["Service", "start", "Service", "stop"]);
+        
+        sort_by_position(&mut results);
+        let names: Vec<_> = results.iter().map(|c| c.source_code.as_str()).collect();
+        assert_eq!(names, vec!["Service", "Service", "start", "stop"]);
+        assert!(results.windows(2).all(|w| w[0].start_byte <= w[1].start_byte));
+    }
+
+    #[test]
+    fn test_filter_constructs_with_predicates() {
+        let source = r#"
+pub async fn serve() {}
+pub fn configure() {}
+pub(crate) async fn helper() {}
+async fn private_task() {}
+
+#[test]
+fn it_works() {}
+"#;
+        let parsed = parse_string_sync(source, Language::Rust).expect("Failed to parse source");
+        let functions = search_functions(&parsed, None);
+        assert_eq!(functions.len(), 5);
+        
+        let public = filter_constructs(functions.clone(), is_public);
+        let names: Vec<_> = public.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["serve", "configure"]);
+        
+        let public_async = filter_constructs(functions.clone(), |c| is_public(c) && is_async(c));
+        assert_eq!(public_async.len(), 1);
+        assert_eq!(public_async[0].name.as_deref(), Some("serve"));
+        
+        let tests = filter_constructs(functions, has_annotation("test"));
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].name.as_deref(), Some("it_works"));
+    }
+
+    #[test]
+    fn test_annotation_name() {
+        assert_eq!(annotation_name("#[derive(Debug, Clone)]"), "derive");
+        assert_eq!(annotation_name("#![allow(dead_code)]"), "allow");
+        assert_eq!(annotation_name("@Override"), "Override");
+        assert_eq!(annotation_name("@app.route('/')"), "app.route");
+    }
+
+    #[test]
+    #[cfg(feature = "scala")]
+    fn test_scala_functions_and_classes() {
+        let scala = "case class Point(x: Int, y: Int) {\n  def norm: Int = x * x + y * y\n}\n\nobject Point {\n  val Origin = Point(0, 0)\n  def apply(x: Int): Point = Point(x, x)\n}\n\ntrait Shape {\n  def area(): Double\n}\n";
+        let parsed = parse_string_sync(scala, Language::Scala).expect("Failed to parse source");
+        
+        let classes: Vec<_> = search_classes(&parsed, None)
+            .into_iter()
+            .map(|c| (c.node_type, c.name.unwrap_or_default()))
+            .collect();
+        assert_eq!(classes, vec![
+            ("class_definition".to_string(), "Point".to_string()),
+            ("object_definition".to_string(), "Point".to_string()),
+            ("trait_definition".to_string(), "Shape".to_string()),
+        ]);
+        
+        let functions: Vec<_> = search_functions(&parsed, None).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(functions, vec!["norm", "apply", "area"]);
+        
+        let origin = search_by_node_type(&parsed, "val_definition", None);
+        assert_eq!(origin[0].name.as_deref(), Some("Origin"));
+    }
+    
+    #[test]
+    fn test_node_for() {
+        let source = "struct Point { x: i32 }\n\nimpl Point {\n    fn norm(&self) -> i32 { self.x }\n}\n";
+        let parsed = parse_string_sync(source, Language::Rust).expect("Failed to parse source");
+        let tree = parsed.syntax_tree.as_ref().unwrap();
+        
+        let constructs = parsed.all_constructs();
+        assert!(!constructs.is_empty());
+        for construct in constructs {
+            let node = node_for(construct, tree).expect("Construct should resolve to a node");
+            assert_eq!(node.kind(), construct.node_type);
+            assert_eq!(node.byte_range(), construct.start_byte..construct.end_byte);
+        }
+    }
+    
+    #[test]
+    fn test_missing_syntax_tree_error() {
+        let mut parsed = parse_string_sync("def f():\n    pass\n", Language::Python).expect("Failed to parse source");
+        parsed.syntax_tree = None;
+        
+        let error = search_by_query(&parsed, "(function_definition) @f").unwrap_err();
+        assert!(matches!(error, Error::SyntaxTreeUnavailable));
+        assert!(matches!(error.error_type(), crate::ErrorType::SyntaxTreeUnavailable));
+        assert!(matches!(crate::extract_string_literals(&parsed), Err(Error::SyntaxTreeUnavailable)));
+    }
+
+    #[test]
+    fn test_project_search() {
+        let mut rust_file = parse_string_sync("pub fn parse() {}\nstruct Config;\n", Language::Rust).expect("Failed to parse source");
+        rust_file.file_path = "src/lib.rs".to_string();
+        let mut python_file = parse_string_sync("class Parser:\n    def parse(self):\n        pass\n", Language::Python).expect("Failed to parse source");
+        python_file.file_path = "tools/parser.py".to_string();
+        
+        let project = ParsedProject::from_files(vec![rust_file, python_file]);
+        
+        let functions = project_search_functions(&project, Some("^parse$"));
+        let paths: Vec<_> = functions.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs", "tools/parser.py"]);
+        
+        let classes = project_search_classes(&project, None);
+        let paths: Vec<_> = classes.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs", "tools/parser.py"]);
+        
+        let structs = project_search_by_node_type(&project, "struct_item", None);
+        assert_eq!(structs.len(), 1);
+        assert_eq!(structs[0].0, "src/lib.rs");
+        
+        let mixed = project_search_by_multiple_node_types(&project, &["function_item", "class_definition"], None);
+        assert_eq!(mixed.len(), 2);
+    }
+
+    #[test]
+    fn test_project_search_by_query() {
+        let mut routes = parse_string_sync("@app.route('/')\ndef index():\n    pass\n\ndef helper():\n    pass\n", Language::Python).expect("Failed to parse source");
+        routes.file_path = "app/routes.py".to_string();
+        let mut models = parse_string_sync("class User:\n    @property\n    def name(self):\n        pass\n", Language::Python).expect("Failed to parse source");
+        models.file_path = "app/models.py".to_string();
+        
+        let mut project = ParsedProject::from_files(vec![routes, models]);
+        
+        let query = "(decorated_definition (function_definition name: (identifier) @name))";
+        let results = project_search_by_query(&project, query).unwrap();
+        let found: Vec<_> = results.iter().map(|(path, c)| (path.as_str(), c.source_code.as_str())).collect();
+        assert_eq!(found, vec![("app/routes.py", "index"), ("app/models.py", "name")]);
+        
+        for file in &mut project.files {
+            file.syntax_tree = None;
+        }
+        assert!(matches!(project_search_by_query(&project, query), Err(Error::SyntaxTreeUnavailable)));
+    }
+    
+    #[test]
+    fn test_search_functions_rust_methods() {
+        let source = "struct Counter { n: u32 }\n\nimpl Counter {\n    fn new() -> Self { Counter { n: 0 } }\n    fn increment(&mut self) { self.n += 1; }\n    fn get(&self) -> u32 { self.n }\n}\n\ntrait Shape {\n    fn area(&self) -> f64;\n    fn describe(&self) -> String { String::new() }\n}\n";
+        let mut parsed = parse_string_sync(source, Language::Rust).expect("Failed to parse source");
+        
+        let expected = vec!["new", "increment", "get", "area", "describe"];
+        let names: Vec<_> = search_functions(&parsed, None).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, expected);
+        
+        // Same result when only the top-level constructs are kept
+        parsed.constructs.retain(|c| c.parent.is_none());
+        let names: Vec<_> = search_functions(&parsed, None).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, expected);
+    }
+    
+    #[test]
+    fn test_search_nested_functions() {
+        let source = "def outer():\n    def inner():\n        pass\n\n    @cache\n    def cached():\n        pass\n\n    class Local:\n        def method(self):\n            pass\n\n    return inner\n\ndef standalone():\n    pass\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let nested: Vec<_> = search_nested_functions(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(nested, vec!["inner", "cached"]);
+        let restored: Vec<_> = search_nested_functions(&round_trip(&parsed)).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(restored, vec!["inner", "cached"]);
+        
+        // The inner function stays a child of the outer one
+        let outer = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("outer")).unwrap();
+        assert!(outer.children.iter().any(|c| c.name.as_deref() == Some("inner")));
+        
+        let js = "function outer() {\n  const inner = () => 1;\n  return inner;\n}\nconst top = () => 2;\n";
+        let parsed = parse_string_sync(js, Language::JavaScript).expect("Failed to parse source");
+        let nested = search_nested_functions(&parsed);
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].node_type, "arrow_function");
+        assert_eq!(nested[0].start_line, 2);
+    }
+    
+    #[test]
+    fn test_search_by_node_type_excluding() {
+        let source = "def handle_login(): pass\ndef handle_login_test(): pass\ndef handle_logout(): pass\ndef render(): pass\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let handlers = search_by_node_type_excluding(&parsed, "function_definition", Some("^handle_"), Some("_test$")).unwrap();
+        let names: Vec<_> = handlers.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["handle_login", "handle_logout"]);
+        
+        let non_tests = search_by_node_type_excluding(&parsed, "function_definition", None, Some("_test$")).unwrap();
+        assert_eq!(non_tests.len(), 3);
+        
+        assert!(matches!(
+            search_by_node_type_excluding(&parsed, "function_definition", Some("("), None),
+            Err(Error::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_duplicate_functions() {
+        let helper = "def clamp(value, low, high):\n    return max(low, min(value, high))\n";
+        let mut first = parse_string_sync(&format!("{}\ndef scale(x):\n    return x * 2\n", helper), Language::Python).expect("Failed to parse source");
+        first.file_path = "a.py".to_string();
+        let mut second = parse_string_sync(&format!("import math\n\n\n{}\ndef scale(x):\n    return x * 3\n", helper), Language::Python).expect("Failed to parse source");
+        second.file_path = "b.py".to_string();
+        
+        let project = ParsedProject::from_files(vec![first, second]);
+        
+        let clusters = find_duplicate_functions(&project);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 2);
+        let locations: Vec<_> = clusters[0].iter().map(|(path, f)| (path.as_str(), f.start_line)).collect();
+        assert_eq!(locations, vec![("a.py", 1), ("b.py", 4)]);
+    }
+
+    #[test]
+    fn test_search_by_line_range() {
+        let source = "def first():\n    return 1\n\n\nclass Viewport:\n    def a(self):\n        pass\n\n    def b(self):\n        pass\n\n    def c(self):\n        pass\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        // The class spans the whole viewport; `a` overlaps its top edge
+        let visible = search_by_line_range(&parsed, 7, 9);
+        let names: Vec<_> = visible.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["Viewport", "a", "b"]);
+        
+        // Touching a single line at either end still counts as overlapping
+        let names: Vec<_> = search_by_line_range(&parsed, 2, 5).iter().filter_map(|c| c.name.clone()).collect();
+        assert_eq!(names, vec!["first", "Viewport"]);
+        assert!(search_by_line_range(&parsed, 3, 4).is_empty());
+    }
+
+    #[test]
+    fn test_search_by_construct_size() {
+        let body: String = (0..98).map(|i| format!("    step_{i}()\n")).collect();
+        let source = format!("def tiny():\n    pass\n\ndef huge():\n{}    return None\n\ndef short():\n    a = 1\n    return a\n", body);
+        let parsed = parse_string_sync(&source, Language::Python).expect("Failed to parse source");
+        
+        let large = search_large_constructs(&parsed, Some("function_definition"), 100);
+        assert_eq!(large.len(), 1);
+        assert_eq!(large[0].name.as_deref(), Some("huge"));
+        assert_eq!(large[0].end_line - large[0].start_line + 1, 100);
+        assert!(search_large_constructs(&parsed, Some("function_definition"), 101).is_empty());
+        
+        let small = search_small_constructs(&parsed, Some("function_definition"), 2);
+        let names: Vec<_> = small.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["tiny"]);
+        
+        // Without a node type, the assignment inside `short` counts too
+        assert_eq!(search_small_constructs(&parsed, None, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_search_test_functions_rust() {
+        let source = "fn run() {}
+
+#[test]
+fn standalone() {}
+
+#[tokio::test]
+async fn async_case() {}
+
+#[cfg(test)]
+mod tests {
+    fn helper() {}
+}
+";
+        let parsed = parse_string_sync(source, Language::Rust).expect("Failed to parse source");
+        
+        let names: Vec<_> = search_test_functions(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, vec!["standalone", "async_case", "helper"]);
+        
+        let module = search_by_node_type(&parsed, "mod_item", None);
+        assert!(is_test_construct(&module[0], &Language::Rust));
+    }
+
+    #[test]
+    fn test_search_test_functions_python() {
+        let source = "import unittest
+
+class MathTest(unittest.TestCase):
+    def setUp(self):
+        pass
+
+    def test_add(self):
+        pass
+
+class Helper(object):
+    pass
+
+def test_standalone():
+    pass
+
+def testing_tool():
+    pass
+";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let names: Vec<_> = search_test_functions(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, vec!["test_add", "test_standalone"]);
+        
+        let classes: Vec<_> = search_classes(&parsed, None)
+            .into_iter()
+            .filter(|c| is_test_construct(c, &Language::Python))
+            .filter_map(|c| c.name)
+            .collect();
+        assert_eq!(classes, vec!["MathTest"]);
+    }
+
+    #[test]
+    fn test_search_test_functions_javascript() {
+        let source = "describe('math', () => {\n  it('adds', function () {\n    [1, 2].map(x => x * 2);\n  });\n  test.skip('later', () => {});\n});\n";
+        let parsed = parse_string_sync(source, Language::JavaScript).expect("Failed to parse source");
+        
+        let tests = search_test_functions(&parsed);
+        let callees: Vec<_> = tests.iter().filter_map(|c| c.metadata.registered_by.as_deref()).collect();
+        assert_eq!(callees, vec!["describe", "it", "test.skip"]);
+        assert!(tests.iter().all(|c| c.metadata.annotations.is_empty()));
+    }
+
+    #[test]
+    fn test_fuzzy_find_symbols() {
+        let mut lib = parse_string_sync(
+            "def parse_file(path):\n    pass\n\ndef parse_string(text):\n    pass\n\ndef profile():\n    pass\n",
+            Language::Python,
+        ).expect("Failed to parse source");
+        lib.file_path = "lib.py".to_string();
+        let mut app = parse_string_sync("class PreSaveFilter:\n    pass\n", Language::Python).expect("Failed to parse source");
+        app.file_path = "app.py".to_string();
+        let project = ParsedProject::from_files(vec![lib, app]);
+        
+        let matches = fuzzy_find_symbols(&project, "prsfl", 10);
+        let names: Vec<_> = matches.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["PreSaveFilter", "parse_file"]);
+        assert_eq!(matches[1].file_path, "lib.py");
+        assert_eq!(matches[1].start_line, 1);
+        
+        // Word starts and runs of consecutive characters rank higher
+        let names: Vec<_> = fuzzy_find_symbols(&project, "pfile", 10).into_iter().map(|m| m.name).collect();
+        assert_eq!(names, vec!["parse_file", "profile", "PreSaveFilter"]);
+        
+        // Case-insensitive, limited, and empty queries match nothing
+        assert_eq!(fuzzy_find_symbols(&project, "PARSE", 1)[0].name, "parse_file");
+        assert!(fuzzy_find_symbols(&project, "", 10).is_empty());
+        assert!(fuzzy_find_symbols(&project, "xyz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_enclosing_construct() {
+        let source = "class Outer:\n    class Inner:\n        def method(self):\n            total = 1\n            return total\n\ndef free():\n    pass\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let method = &search_by_node_type(&parsed, "function_definition", Some("^method$"))[0];
+        let class = enclosing_construct(&parsed, method, &["class_definition"]).expect("method has a class");
+        assert_eq!(class.name.as_deref(), Some("Inner"));
+        
+        // Walks past non-matching kinds to the nearest match, never the target itself
+        let assignment = &search_by_node_type(&parsed, "assignment", None)[0];
+        let function = enclosing_construct(&parsed, assignment, &["function_definition", "class_definition"]);
+        assert_eq!(function.and_then(|f| f.name.as_deref()), Some("method"));
+        let outer = enclosing_construct(&parsed, class, &["class_definition"]);
+        assert_eq!(outer.and_then(|c| c.name.as_deref()), Some("Outer"));
+        
+        let free = &search_by_node_type(&parsed, "function_definition", Some("^free$"))[0];
+        assert!(enclosing_construct(&parsed, free, &["class_definition"]).is_none());
+        
+        let restored = round_trip(&parsed);
+        let method = &search_by_node_type(&restored, "function_definition", Some("^method$"))[0];
+        let class = enclosing_construct(&restored, method, &["class_definition"]);
+        assert_eq!(class.and_then(|c| c.name.as_deref()), Some("Inner"));
+    }
+
+    #[test]
+    fn test_leaf_constructs() {
+        let source = "class Account:\n    def deposit(self, amount):\n        pass\n\n    def withdraw(self, amount):\n        pass\n";
+        let flat = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        // The layout `flatten_constructs: false` produces
+        let mut nested = flat.clone();
+        nested.constructs.retain(|construct| construct.parent.is_none());
+        
+        for parsed in [flat, nested] {
+            let leaves: Vec<_> = leaf_constructs(&parsed).into_iter().map(|c| c.name.as_deref()).collect();
+            assert_eq!(leaves, vec![Some("deposit"), Some("withdraw")]);
+        }
+    }
+
+    #[test]
+    fn test_breadcrumb() {
+        let source = "class Outer:\n    class Inner:\n        @staticmethod\n        def method():\n            total = 1\n            return total\n";
+        let parsed = parse_string_sync(source, Language::Python).expect("Failed to parse source");
+        
+        let method = &search_by_node_type(&parsed, "function_definition", Some("^method$"))[0];
+        assert_eq!(breadcrumb(&parsed, method), vec!["Outer", "Inner", "method"]);
+        
+        let assignment = &search_by_node_type(&parsed, "assignment", None)[0];
+        assert_eq!(breadcrumb(&parsed, assignment), vec!["Outer", "Inner", "method", "total"]);
+        
+        let outer = &search_by_node_type(&parsed, "class_definition", Some("^Outer$"))[0];
+        assert_eq!(breadcrumb(&parsed, outer), vec!["Outer"]);
+        
+        let restored = round_trip(&parsed);
+        let assignment = &search_by_node_type(&restored, "assignment", None)[0];
+        assert_eq!(breadcrumb(&restored, assignment), vec!["Outer", "Inner", "method", "total"]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_typescript_declaration_file() {
+        let source = "declare function greet(name: string): string;\nexport declare function add(a: number, b: number): number;\nexport interface Options {\n    verbose: boolean;\n    run(x: number): void;\n}\ndeclare namespace Tools {\n    function format(value: string): string;\n}\n";
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tree_parser_declarations.d.ts");
+        fs::write(&path, source).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        
+        let language = crate::detect_language_by_extension(&path_str).unwrap();
+        assert_eq!(language, Language::TypeScript);
+        let parsed = parse_file(&path_str, language).await.unwrap();
+        
+        let functions: Vec<_> = search_functions(&parsed, None).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(functions, vec!["greet", "add", "run", "format"]);
+        
+        let interfaces: Vec<_> = search_classes(&parsed, None).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(interfaces, vec!["Options"]);
+        
+        let greet = &search_functions(&parsed, Some("^greet$"))[0];
+        assert!(greet.metadata.modifiers.contains(&"declare".to_string()));
+        assert_eq!(greet.metadata.parameters[0].name, "name");
+        
+        let exports: Vec<_> = search_exports(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(exports, vec!["add", "Options"]);
+    }
+    
+    #[test]
+    fn test_search_exports_typescript() {
+        let source = "export function add(a: number, b: number) { return a + b; }\nexport const PI = 3.14;\nexport default class Calculator {}\nexport { add as plus };\nexport * from './shapes';\nexport interface Shape {}\nfunction hidden() {}\n";
+        let parsed = parse_string_sync(source, Language::TypeScript).expect("Failed to parse source");
+        
+        let exports: Vec<_> = search_exports(&parsed).into_iter().map(|c| (c.start_line, c.name)).collect();
+        assert_eq!(exports, vec![
+            (1, Some("add".to_string())),
+            (2, Some("PI".to_string())),
+            (3, Some("Calculator".to_string())),
+            (4, None),
+            (5, None),
+            (6, Some("Shape".to_string())),
+        ]);
+        
+        let parsed = parse_string_sync("const answer = 42;\nexport default answer;\n", Language::TypeScript).expect("Failed to parse source");
+        assert_eq!(search_exports(&parsed)[0].name.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn test_search_exports_rust_and_python() {
+        let rust = "pub use crate::parser::parse;\npub struct Config;\nstruct Internal;\npub(crate) fn helper() {}\npub fn run() {}\n";
+        let parsed = parse_string_sync(rust, Language::Rust).expect("Failed to parse source");
+        let exports: Vec<_> = search_exports(&parsed).into_iter().map(|c| c.node_type).collect();
+        assert_eq!(exports, vec!["use_declaration", "struct_item", "function_item"]);
+        
+        let python = "__all__ = ['load', \"Store\"]\n\ndef load():\n    pass\n\n@dataclass\nclass Store:\n    def save(self):\n        pass\n\ndef dump():\n    pass\n";
+        let parsed = parse_string_sync(python, Language::Python).expect("Failed to parse source");
+        let names: Vec<_> = search_exports(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, vec!["load", "Store"]);
+        
+        // Without `__all__`, module-level names without a leading underscore are public
+        let python = "VERSION = '1'\n_cache = {}\n\ndef dump():\n    inner = 1\n";
+        let parsed = parse_string_sync(python, Language::Python).expect("Failed to parse source");
+        let names: Vec<_> = search_exports(&parsed).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, vec!["VERSION", "dump"]);
+        
+        let names: Vec<_> = search_exports(&round_trip(&parsed)).into_iter().filter_map(|c| c.name).collect();
+        assert_eq!(names, vec!["VERSION", "dump"]);
+    }
+
+    #[test]
+    fn test_search_by_node_type_limited() {
+        let source: String = (0..50).map(|i| format!("def f{}():\n    def inner():\n        pass\n\n", i)).collect();
+        let parsed = parse_string_sync(&source, Language::Python).expect("Failed to parse source");
+        let total = parsed.all_constructs().len();
+        
+        let limited = search_by_node_type_limited(&parsed, "function_definition", Some("^f"), 3);
+        let names: Vec<_> = limited.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["f0", "f1", "f2"]);
+        
+        // Stops after the third match instead of walking all 100 functions
+        let (results, visited) = search_limited(&parsed, "function_definition", Some("^f"), 3);
+        assert_eq!(results.len(), 3);
+        assert_eq!(visited, 5);
+        assert!(visited < total);
+        
+        // Without enough matches it visits everything, like the unlimited search
+        let (results, visited) = search_limited(&parsed, "function_definition", None, 1000);
+        assert_eq!(results.len(), search_by_node_type(&parsed, "function_definition", None).len());
+        assert_eq!(visited, total);
+        assert!(search_by_node_type_limited(&parsed, "function_definition", None, 0).is_empty());
+        
+        let (results, visited) = search_limited(&round_trip(&parsed), "function_definition", None, 1000);
+        assert_eq!((results.len(), visited), (100, total));
+    }
+
+    #[test]
+    fn test_search_by_kind() {
+        let python = parse_string_sync("import os\nclass Shape:\n    def area(self):\n        pass\n", Language::Python).unwrap();
+        let rust = parse_string_sync("use std::fmt;\nstruct Shape;\nfn area() {}\n", Language::Rust).unwrap();
+        
+        for parsed in [&python, &rust] {
+            let imports = search_by_kind(parsed, ConstructKind::Import, None);
+            assert_eq!(imports.len(), 1, "{:?}", parsed.language);
+            let functions = search_by_kind(parsed, ConstructKind::Function, Some("^area$"));
+            assert_eq!(functions.len(), 1, "{:?}", parsed.language);
+        }
+        assert_eq!(search_by_kind(&python, ConstructKind::Class, None)[0].name.as_deref(), Some("Shape"));
+        assert_eq!(search_by_kind(&rust, ConstructKind::Struct, None)[0].name.as_deref(), Some("Shape"));
+        assert!(search_by_kind(&rust, ConstructKind::Class, None).is_empty());
+        
+        // Nested methods aren't found twice on a deserialized file
+        assert_eq!(search_by_kind(&round_trip(&python), ConstructKind::Function, None).len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_search_by_kind_java() {
+        let source = "import java.util.List;\nclass Shape {\n    double area() { return 0; }\n}\n";
+        let parsed = parse_string_sync(source, Language::Java).expect("Failed to parse source");
+        
+        assert_eq!(search_by_kind(&parsed, ConstructKind::Import, None).len(), 1);
+        assert_eq!(search_by_kind(&parsed, ConstructKind::Class, None)[0].name.as_deref(), Some("Shape"));
+        assert_eq!(search_by_kind(&parsed, ConstructKind::Method, Some("^area$")).len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_group_overloads() {
+        let source = "class Calculator {\n    int add(int a, int b) { return a + b; }\n    double add(double a, double... rest) { return a; }\n    void reset() {}\n}\n";
+        let parsed = parse_string_sync(source, Language::Java).expect("Failed to parse source");
+        let groups = group_overloads(search_functions(&parsed, None));
+        
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["reset"].len(), 1);
+        let signatures: Vec<Vec<_>> = groups["add"].iter()
+            .map(|overload| overload.metadata.parameters.iter()
+                .map(|p| (p.name.as_str(), p.param_type.as_deref().unwrap(), p.is_variadic))
+                .collect())
+            .collect();
+        assert_eq!(signatures, vec![
+            vec![("a", "int", false), ("b", "int", false)],
+            vec![("a", "double", false), ("rest", "double", true)],
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "cpp")]
+    fn test_search_in_namespace_cpp() {
+        let source = "namespace outer {\n    namespace inner {\n        int foo() { return 1; }\n    }\n    int foo() { return 2; }\n}\nint foo() { return 3; }\nnamespace outer::inner {\n    void bar() {}\n}\n";
+        let parsed = parse_string_sync(source, Language::Cpp).expect("Failed to parse source");
+        
+        let found = search_in_namespace(&parsed, &["outer", "inner"], "function_definition", Some("^foo$"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start_line, 3);
+        
+        // Reopened namespaces, including the nested `a::b` form, are searched too
+        let all: Vec<_> = search_in_namespace(&parsed, &["outer", "inner"], "function_definition", None)
+            .into_iter()
+            .filter_map(|c| c.name)
+            .collect();
+        assert_eq!(all, vec!["foo", "bar"]);
+        
+        let outer = search_in_namespace(&parsed, &["outer"], "function_definition", Some("^foo$"));
+        assert_eq!(outer.iter().map(|c| c.start_line).collect::<Vec<_>>(), vec![3, 5]);
+        assert!(search_in_namespace(&parsed, &["inner"], "function_definition", None).is_empty());
+    }
+
+    #[test]
+    fn test_search_in_namespace_rust() {
+        let source = "mod net {\n    pub mod http {\n        fn get() {}\n    }\n    fn get() {}\n}\nfn get() {}\n";
+        let parsed = parse_string_sync(source, Language::Rust).expect("Failed to parse source");
+        
+        let found = search_in_namespace(&parsed, &["net", "http"], "function_item", Some("^get$"));
+        assert_eq!(found.iter().map(|c| c.start_line).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(search_in_namespace(&parsed, &[], "function_item", None).len(), 3);
+        
+        // A nested `mod` must not be found again as a root after a round-trip
+        let restored = round_trip(&parsed);
+        assert_eq!(search_in_namespace(&restored, &["http"], "function_item", None).len(), 0);
+        assert_eq!(search_in_namespace(&restored, &["net"], "function_item", None).len(), 2);
+    }
+    
+    #[test]
+    fn test_search_functions_top_level() {
+        let python = "@cache\ndef module_function():\n    def inner():\n        pass\n\nclass Service:\n    def method(self):\n        pass\n\nhandler = lambda event: event\n";
+        let parsed = parse_string_sync(python, Language::Python).expect("Failed to parse source");
+        
+        let all: Vec<_> = search_functions(&parsed, None).into_iter().filter_map(|f| f.name).collect();
+        assert!(all.contains(&"method".to_string()) && all.contains(&"inner".to_string()));
+        let top_level: Vec<_> = search_functions_top_level(&parsed, None).into_iter().map(|f| (f.node_type, f.start_line)).collect();
+        assert_eq!(top_level, vec![("function_definition".to_string(), 2), ("lambda".to_string(), 10)]);
+        let restored: Vec<_> = search_functions_top_level(&round_trip(&parsed), None).into_iter().map(|f| f.start_line).collect();
+        assert_eq!(restored, vec![2, 10]);
+        
+        // A construct detached from its file can't be shown to be top-level
+        let method = search_functions(&parsed, Some("^method$")).pop().unwrap();
+        let detached: CodeConstruct = serde_json::from_str(&serde_json::to_string(&method).unwrap()).unwrap();
+        assert!(!is_top_level(&detached));
+        
+        let javascript = "export function render() {}\nclass View {\n  draw() {}\n}\nvar helper = function () {};\n";
+        let parsed = parse_string_sync(javascript, Language::JavaScript).expect("Failed to parse source");
+        let lines: Vec<_> = search_functions_top_level(&parsed, None).into_iter().map(|f| f.start_line).collect();
+        assert_eq!(lines, vec![1, 5]);
+    }
+
+    #[test]
+    fn test_search_type_aliases() {
+        let rust = crate::parse_string_sync("type Result<T> = std::result::Result<T, Error>;\nstruct Error;\n", Language::Rust).unwrap();
+        let aliases = search_type_aliases(&rust);
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].node_type, "type_item");
+        assert_eq!(aliases[0].name.as_deref(), Some("Result"));
+        
+        let typescript = crate::parse_string_sync("type Id = string | number;\ninterface User { id: Id }\n", Language::TypeScript).unwrap();
+        let aliases = search_type_aliases(&typescript);
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].name.as_deref(), Some("Id"));
+        assert_eq!(aliases[0].source_code, "type Id = string | number;");
+    }
+}
@@ -1,6 +1,8 @@
 //! Utility functions for the tree parser library
 
-use crate::Language;
+use crate::{CodeConstruct, Language, ParsedProject, Point};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Check if a file extension is supported by the parser
 /// 
@@ -39,7 +41,8 @@ pub fn is_supported_extension(extension: &str) -> bool {
 /// # Returns
 /// 
 /// A vector of strings containing all supported file extensions
-/// (without the leading dot).
+/// (without the leading dot), including those of languages added with
+/// `register_language`.
 /// 
 /// # Examples
 /// 
@@ -54,36 +57,17 @@ pub fn is_supported_extension(extension: &str) -> bool {
 /// assert!(extensions.contains(&"rs".to_string()));
 /// ```
 pub fn get_supported_extensions() -> Vec<String> {
-    vec![
-        "py".to_string(), "pyw".to_string(), "pyi".to_string(),
-        "rs".to_string(),
-        "js".to_string(), "mjs".to_string(), "cjs".to_string(),
-        "ts".to_string(), "mts".to_string(), "cts".to_string(),
-        "java".to_string(),
-        "c".to_string(), "h".to_string(),
-        "cpp".to_string(), "cc".to_string(), "cxx".to_string(), "c++".to_string(),
-        "hpp".to_string(), "hh".to_string(), "hxx".to_string(), "h++".to_string(),
-        "go".to_string(),
-        "cs".to_string(),
-        "php".to_string(), "phtml".to_string(), "php3".to_string(), "php4".to_string(), "php5".to_string(), "phps".to_string(),
-        "rb".to_string(), "rbw".to_string(),
-        "swift".to_string(),
-        "kt".to_string(), "kts".to_string(),
-        "scala".to_string(), "sc".to_string(),
-        "hs".to_string(), "lhs".to_string(),
-        "lua".to_string(),
-        "pl".to_string(), "pm".to_string(), "t".to_string(), "pod".to_string(),
-        "r".to_string(), "R".to_string(),
-        "sh".to_string(), "bash".to_string(), "zsh".to_string(), "fish".to_string(),
-        "ps1".to_string(), "psm1".to_string(), "psd1".to_string(),
-        "html".to_string(), "htm".to_string(), "xhtml".to_string(),
-        "css".to_string(),
-        "sql".to_string(),
-        "json".to_string(),
-        "yaml".to_string(), "yml".to_string(),
-        "toml".to_string(),
-        "xml".to_string(), "xsd".to_string(), "xsl".to_string(), "xslt".to_string(),
-    ]
+    let mut extensions: Vec<String> = crate::languages::BUILTIN_LANGUAGES
+        .iter()
+        .flat_map(|language| language.all_extensions().iter().map(|extension| extension.to_string()))
+        .collect();
+    // A custom language may claim a built-in extension; list it once
+    for extension in crate::languages::custom_language_extensions() {
+        if !extensions.contains(&extension) {
+            extensions.push(extension);
+        }
+    }
+    extensions
 }
 
 /// Convert a string representation to a Language enum
@@ -99,7 +83,8 @@ pub fn get_supported_extensions() -> Vec<String> {
 /// 
 /// # Returns
 /// 
-/// `Some(Language)` if the string is recognized, `None` otherwise.
+/// `Some(Language)` if the string is recognized, `None` otherwise. Names
+/// added with `register_language` map to `Language::Custom`.
 /// 
 /// # Supported Aliases
 /// 
@@ -150,6 +135,8 @@ pub fn language_from_string(lang_str: &str) -> Option<Language> {
         "yaml" | "yml" => Some(Language::Yaml),
         "toml" => Some(Language::Toml),
         "xml" => Some(Language::Xml),
+        "markdown" | "md" => Some(Language::Markdown),
+        _ if crate::languages::is_registered_language(lang_str) => Some(Language::Custom(lang_str.to_string())),
         _ => None,
     }
 }
@@ -180,33 +167,8 @@ pub fn language_from_string(lang_str: &str) -> Option<Language> {
 /// ```
 pub fn language_to_string(language: &Language) -> String {
     match language {
-        Language::Python => "Python".to_string(),
-        Language::Rust => "Rust".to_string(),
-        Language::JavaScript => "JavaScript".to_string(),
-        Language::TypeScript => "TypeScript".to_string(),
-        Language::Java => "Java".to_string(),
-        Language::C => "C".to_string(),
-        Language::Cpp => "C++".to_string(),
-        Language::Go => "Go".to_string(),
-        Language::CSharp => "C#".to_string(),
-        Language::Php => "PHP".to_string(),
-        Language::Ruby => "Ruby".to_string(),
-        Language::Swift => "Swift".to_string(),
-        Language::Kotlin => "Kotlin".to_string(),
-        Language::Scala => "Scala".to_string(),
-        Language::Haskell => "Haskell".to_string(),
-        Language::Lua => "Lua".to_string(),
-        Language::Perl => "Perl".to_string(),
-        Language::R => "R".to_string(),
-        Language::Bash => "Bash".to_string(),
-        Language::PowerShell => "PowerShell".to_string(),
-        Language::Html => "HTML".to_string(),
-        Language::Css => "CSS".to_string(),
-        Language::Sql => "SQL".to_string(),
-        Language::Json => "JSON".to_string(),
-        Language::Yaml => "YAML".to_string(),
-        Language::Toml => "TOML".to_string(),
-        Language::Xml => "XML".to_string(),
+        Language::Custom(name) => name.clone(),
+        _ => language.display_name().to_string(),
     }
 }
 
@@ -458,6 +420,298 @@ pub fn sanitize_path(path: &str) -> String {
         .to_string()
 }
 
+/// Get a construct's source code with formatting differences removed
+/// 
+/// Line endings are normalized to `\n`, leading indentation and trailing
+/// whitespace are stripped from every line, blank lines are dropped and
+/// other runs of whitespace collapse to a single space. String literals
+/// (quoted with `"`, `'` or `` ` ``, including Python triple-quoted strings)
+/// are copied unchanged, so two constructs normalize equally only if their
+/// literals match exactly.
+/// 
+/// Literal detection is lexical rather than grammar-aware. In Rust
+/// constructs (by their `language`) `'` marks lifetimes and char literals
+/// rather than strings, so only `"` starts a literal there; elsewhere an
+/// unterminated `'` or `"` only extends to the end of its line.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct whose source code to normalize
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{normalized_source, parse_string_sync, Language};
+/// 
+/// let parsed = parse_string_sync("def greet():\r\n    print('hi   there')\r\n", Language::Python).unwrap();
+/// assert_eq!(normalized_source(&parsed.constructs[0]), "def greet():\nprint('hi   there')");
+/// ```
+pub fn normalized_source(construct: &CodeConstruct) -> String {
+    let rust = construct.language == Some(Language::Rust);
+    normalize_whitespace(&construct.source_code, !rust)
+}
+
+/// Normalize whitespace outside string literals (see `normalized_source`)
+/// 
+/// `single_quoted_strings` controls whether `'` opens a string literal.
+fn normalize_whitespace(source: &str, single_quoted_strings: bool) -> String {
+    let chars: Vec<char> = source.replace("\r\n", "\n").chars().collect();
+    let mut normalized = String::with_capacity(chars.len());
+    let mut pending_space = false;
+    let mut pending_newline = false;
+    
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            pending_newline = !normalized.is_empty();
+            pending_space = false;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+        
+        if pending_newline {
+            normalized.push('\n');
+        } else if pending_space && !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        pending_newline = false;
+        pending_space = false;
+        
+        if c == '"' || c == '`' || (c == '\'' && single_quoted_strings) {
+            let end = string_literal_end(&chars, i);
+            normalized.extend(&chars[i..end]);
+            i = end;
+        } else {
+            normalized.push(c);
+            i += 1;
+        }
+    }
+    
+    normalized
+}
+
+/// Find the index just past the string literal opening at `start`
+fn string_literal_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let triple = quote != '`' && chars[start..].starts_with(&[quote, quote, quote]);
+    
+    let mut i = start + if triple { 3 } else { 1 };
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if triple {
+            if chars[i..].starts_with(&[quote, quote, quote]) {
+                return i + 3;
+            }
+        } else if chars[i] == quote {
+            return i + 1;
+        } else if chars[i] == '\n' && quote != '`' {
+            // Unterminated single-line literal (or a lone apostrophe)
+            return i;
+        }
+        i += 1;
+    }
+    
+    chars.len()
+}
+
+/// Convert a tree-sitter `Point` into a byte offset within `source`
+/// 
+/// Following tree-sitter, `row` is the 0-based line and `column` is a byte
+/// offset within that line, so multibyte characters count once per byte.
+/// A point may sit at the end of a line or at the end of the source.
+/// 
+/// # Returns
+/// 
+/// The byte offset, or `None` if the row or column is out of range or the
+/// column falls inside a multibyte character.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{point_to_byte, Point};
+/// 
+/// let source = "let é = 1;\nlet x = 2;\n";
+/// assert_eq!(point_to_byte(source, Point::new(1, 4)), Some(16));
+/// assert_eq!(point_to_byte(source, Point::new(0, 5)), None); // inside `é`
+/// ```
+pub fn point_to_byte(source: &str, point: Point) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..point.row {
+        line_start += source[line_start..].find('\n')? + 1;
+    }
+    
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |offset| line_start + offset);
+    let byte = line_start + point.column;
+    
+    (byte <= line_end && source.is_char_boundary(byte)).then_some(byte)
+}
+
+/// Convert a byte offset within `source` into a tree-sitter `Point`
+/// 
+/// This is the inverse of `point_to_byte`: the column is the byte offset
+/// from the start of the containing line.
+/// 
+/// # Returns
+/// 
+/// The point, or `None` if the offset is past the end of the source or
+/// falls inside a multibyte character.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{byte_to_point, Point};
+/// 
+/// let source = "let é = 1;\nlet x = 2;\n";
+/// assert_eq!(byte_to_point(source, 16), Some(Point::new(1, 4)));
+/// assert_eq!(byte_to_point(source, 5), None); // inside `é`
+/// ```
+pub fn byte_to_point(source: &str, byte: usize) -> Option<Point> {
+    if !source.is_char_boundary(byte) {
+        return None;
+    }
+    
+    let before = &source[..byte];
+    let row = before.matches('\n').count();
+    let column = before.rfind('\n').map_or(byte, |newline| byte - newline - 1);
+    Some(Point::new(row, column))
+}
+
+/// Render a short, line-numbered preview of a construct for search results
+/// 
+/// Shows the construct's first line together with up to `context_lines`
+/// lines before and after it, rather than the whole body. Each line is
+/// prefixed with its 1-based number, right-aligned to a common width, and
+/// the window is clipped at the start and end of the file.
+/// 
+/// # Arguments
+/// 
+/// * `construct` - The construct to preview
+/// * `full_source` - The source text of the file the construct came from
+/// * `context_lines` - How many lines to show on each side of the first line
+/// 
+/// # Returns
+/// 
+/// The preview lines joined with `\n`, or an empty string if the construct's
+/// first line is outside `full_source`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use tree_parser::{parse_string_sync, preview, Language};
+/// 
+/// let source = "import os\n\ndef main():\n    os.exit(0)\n";
+/// let parsed = parse_string_sync(source, Language::Python).unwrap();
+/// let main = parsed.constructs.iter().find(|c| c.name.as_deref() == Some("main")).unwrap();
+/// 
+/// assert_eq!(preview(main, source, 1), "2 |\n3 | def main():\n4 |     os.exit(0)");
+/// ```
+pub fn preview(construct: &CodeConstruct, full_source: &str, context_lines: usize) -> String {
+    let lines: Vec<&str> = full_source.lines().collect();
+    let Some(center) = construct.start_line.checked_sub(1).filter(|&index| index < lines.len()) else {
+        return String::new();
+    };
+    
+    let first = center.saturating_sub(context_lines);
+    let last = (center + context_lines).min(lines.len() - 1);
+    let width = (last + 1).to_string().len();
+    
+    (first..=last)
+        .map(|index| format!("{:>width$} | {}", index + 1, lines[index], width = width).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Merge several parsed projects into one combined project
+/// 
+/// Files and error files are concatenated in order. Files that appear in
+/// more than one project (by `file_path`) are kept only once, so
+/// `total_files_processed` and `language_distribution` are recomputed from
+/// the merged files. `root_path` is set to the longest common path prefix of
+/// the inputs (empty if they share none); assign it afterwards to use a
+/// custom label instead.
+/// 
+/// # Arguments
+/// 
+/// * `projects` - The projects to merge
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use tree_parser::{merge_projects, parse_directory, ParseOptions};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let src = parse_directory("./src", ParseOptions::default()).await?;
+///     let tests = parse_directory("./tests", ParseOptions::default()).await?;
+///     
+///     let combined = merge_projects(vec![src, tests]);
+///     println!("{} files under {}", combined.total_files_processed, combined.root_path);
+///     Ok(())
+/// }
+/// ```
+pub fn merge_projects(projects: Vec<ParsedProject>) -> ParsedProject {
+    let root_path = common_path_prefix(projects.iter().map(|project| project.root_path.as_str()));
+    
+    let mut seen_paths = HashSet::new();
+    let mut files = Vec::new();
+    let mut error_files = Vec::new();
+    let mut warnings = Vec::new();
+    for project in projects {
+        for file in project.files {
+            if seen_paths.insert(file.file_path.clone()) {
+                files.push(file);
+            }
+        }
+        error_files.extend(project.error_files);
+        warnings.extend(project.warnings);
+    }
+    
+    let mut language_distribution = HashMap::new();
+    for file in &files {
+        *language_distribution.entry(file.language.clone()).or_insert(0) += 1;
+    }
+    
+    ParsedProject {
+        root_path,
+        total_files_processed: files.len(),
+        files,
+        language_distribution,
+        error_files,
+        warnings,
+    }
+}
+
+/// Get the longest common component-wise prefix of several paths
+fn common_path_prefix<'a>(paths: impl Iterator<Item = &'a str>) -> String {
+    let mut prefix: Option<PathBuf> = None;
+    for path in paths {
+        prefix = Some(match prefix {
+            None => PathBuf::from(path),
+            Some(current) => current
+                .components()
+                .zip(Path::new(path).components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    
+    prefix
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,4 +755,141 @@ mod tests {
         assert!(is_supported_extension("js"));
         assert!(!is_supported_extension("xyz"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_supported_extensions_round_trip() {
+        let extensions = get_supported_extensions();
+        for extension in &extensions {
+            let path = format!("file.{}", extension);
+            assert!(crate::languages::detect_language_by_extension(&path).is_some(), "{}", path);
+            assert!(is_supported_extension(extension), "{}", extension);
+        }
+        
+        let mut unique = extensions.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), extensions.len());
+    }
+
+    #[test]
+    fn test_merge_projects() {
+        use crate::{parse_string_sync, ErrorType, FileError};
+        
+        let file = |path: &str, source: &str, language: Language| {
+            let mut parsed = parse_string_sync(source, language).unwrap();
+            parsed.file_path = path.to_string();
+            parsed
+        };
+        let src = ParsedProject {
+            root_path: "/repo/src".to_string(),
+            ..ParsedProject::from_files(vec![
+                file("/repo/src/lib.rs", "fn a() {}", Language::Rust),
+                file("/repo/shared/util.py", "def b(): pass", Language::Python),
+            ])
+        };
+        let tests = ParsedProject {
+            root_path: "/repo/tests".to_string(),
+            error_files: vec![FileError {
+                file_path: "/repo/tests/broken.py".to_string(),
+                error_type: ErrorType::ParseError,
+                message: "bad".to_string(),
+            }],
+            ..ParsedProject::from_files(vec![
+                file("/repo/shared/util.py", "def b(): pass", Language::Python),
+                file("/repo/tests/test_a.py", "def test_a(): pass", Language::Python),
+            ])
+        };
+        
+        let merged = merge_projects(vec![src, tests]);
+        assert_eq!(merged.root_path, "/repo");
+        assert_eq!(merged.total_files_processed, 3);
+        assert_eq!(merged.files.len(), 3);
+        assert_eq!(merged.language_distribution.get(&Language::Python), Some(&2));
+        assert_eq!(merged.language_distribution.get(&Language::Rust), Some(&1));
+        assert_eq!(merged.error_files.len(), 1);
+    }
+
+    #[test]
+    fn test_normalized_source_ignores_indentation() {
+        use crate::parse_string_sync;
+        
+        let nested = "class Shapes:\n    def area(w, h):\n        total = w *  h\n\n        return total\n";
+        let top_level = "def area(w, h):\r\n  total = w * h\r\n  return total\r\n";
+        let nested = parse_string_sync(nested, Language::Python).unwrap();
+        let top_level = parse_string_sync(top_level, Language::Python).unwrap();
+        
+        let method = nested.constructs.iter().find(|c| c.name.as_deref() == Some("area")).unwrap();
+        let function = &top_level.constructs[0];
+        assert_ne!(method.source_code, function.source_code);
+        assert_eq!(normalized_source(method), normalized_source(function));
+        assert_eq!(normalized_source(function), "def area(w, h):\ntotal = w * h\nreturn total");
+    }
+
+    #[test]
+    fn test_normalize_whitespace_preserves_literals() {
+        assert_eq!(normalize_whitespace("  x  =  'a   b'  ", true), "x = 'a   b'");
+        assert_eq!(normalize_whitespace("s = \"say \\\"hi  there\\\"\"", true), "s = \"say \\\"hi  there\\\"\"");
+        assert_eq!(normalize_whitespace("doc = \"\"\"\n    keep   this\n\"\"\"\n    y", true), "doc = \"\"\"\n    keep   this\n\"\"\"\ny");
+        // Lifetimes don't open a literal when `'` is not a string quote
+        assert_eq!(normalize_whitespace("fn f<'a>(x:   &'a str) {}\n    g()", false), "fn f<'a>(x: &'a str) {}\ng()");
+    }
+    
+    #[test]
+    fn test_normalized_source_rust_lifetimes() {
+        use crate::parse_string_sync;
+        
+        let spaced = parse_string_sync("fn f<'a>(x:   &'a str) -> char {\n    '\\''\n}\n", Language::Rust).unwrap();
+        assert_eq!(normalized_source(&spaced.constructs[0]), "fn f<'a>(x: &'a str) -> char {\n'\\''\n}");
+    }
+    
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_normalized_source_shared_node_type() {
+        use crate::parse_string_sync;
+        
+        // Java and Rust both have `field_declaration`, but only Rust treats `'` as a non-string
+        let java = parse_string_sync("class Row {\n    char sep = '    ';\n}\n", Language::Java).unwrap();
+        let field = &java.constructs[0].children[0];
+        assert_eq!(field.node_type, "field_declaration");
+        assert_eq!(normalized_source(field), "char sep = '    ';");
+    }
+
+    #[test]
+    fn test_point_byte_conversion_multibyte() {
+        // "日本" is 6 bytes, "ü" is 2
+        let source = "名前 = \"日本\"\nüber = 1\n\nend";
+        
+        for byte in 0..=source.len() {
+            match byte_to_point(source, byte) {
+                Some(point) => assert_eq!(point_to_byte(source, point), Some(byte)),
+                None => assert!(!source.is_char_boundary(byte)),
+            }
+        }
+        
+        assert_eq!(point_to_byte(source, Point::new(1, 2)), Some(20));
+        assert_eq!(byte_to_point(source, 20), Some(Point::new(1, 2)));
+        assert_eq!(point_to_byte(source, Point::new(1, 1)), None);
+        // End of a line, an empty line and the end of the source
+        assert_eq!(point_to_byte(source, Point::new(1, 9)), Some(27));
+        assert_eq!(point_to_byte(source, Point::new(1, 10)), None);
+        assert_eq!(point_to_byte(source, Point::new(2, 0)), Some(28));
+        assert_eq!(byte_to_point(source, source.len()), Some(Point::new(3, 3)));
+        assert_eq!(point_to_byte(source, Point::new(4, 0)), None);
+        assert_eq!(byte_to_point(source, source.len() + 1), None);
+    }
+
+    #[test]
+    fn test_preview() {
+        let source: String = (1..=12).map(|i| format!("x{} = {}\n", i, i)).collect();
+        let parsed = crate::parse_string_sync(&source, Language::Python).unwrap();
+        let at = |line: usize| parsed.constructs.iter().find(|c| c.start_line == line).unwrap();
+        
+        // Numbers are right-aligned to the widest line number in the window
+        assert_eq!(preview(at(9), &source, 1), " 8 | x8 = 8\n 9 | x9 = 9\n10 | x10 = 10");
+        // The window is clipped at both ends of the file
+        assert_eq!(preview(at(1), &source, 2), "1 | x1 = 1\n2 | x2 = 2\n3 | x3 = 3");
+        assert_eq!(preview(at(12), &source, 3), " 9 | x9 = 9\n10 | x10 = 10\n11 | x11 = 11\n12 | x12 = 12");
+        assert_eq!(preview(at(5), &source, 0), "5 | x5 = 5");
+        assert_eq!(preview(at(5), "", 2), "");
+    }
+}